@@ -0,0 +1,62 @@
+use crate::context::CliContext;
+use crate::error::Result;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use yaak_plugins::events::{CallHttpAuthenticationRequest, HttpHeader};
+
+/// What an authentication plugin adds to a gRPC or WebSocket request
+#[derive(Default)]
+pub(crate) struct AuthResult {
+    pub headers: Vec<(String, String)>,
+    pub query_parameters: Vec<(String, String)>,
+}
+
+/// Call the authentication plugin for a resolved and rendered request, like the app does
+/// before connecting. HTTP requests are authenticated by `yaak_send` instead.
+pub(crate) async fn authenticate(
+    ctx: &CliContext,
+    workspace_id: &str,
+    authentication_type: Option<&str>,
+    authentication: &BTreeMap<String, Value>,
+    auth_context_id: &str,
+    url: &str,
+    headers: &[(String, String)],
+) -> Result<AuthResult> {
+    let authentication_type = match authentication_type {
+        // No authentication found. Not even inherited
+        None => return Ok(AuthResult::default()),
+        // Explicitly no authentication
+        Some("none") => return Ok(AuthResult::default()),
+        Some(t) => t,
+    };
+
+    let req = CallHttpAuthenticationRequest {
+        context_id: format!("{:x}", md5::compute(auth_context_id)),
+        values: serde_json::from_value(serde_json::to_value(authentication)?)?,
+        method: "POST".to_string(),
+        url: url.to_string(),
+        headers: headers
+            .iter()
+            .map(|(name, value)| HttpHeader { name: name.clone(), value: value.clone() })
+            .collect(),
+    };
+    let result = ctx
+        .plugin_manager
+        .call_http_authentication(&ctx.plugin_context(workspace_id), authentication_type, req)
+        .await?;
+
+    Ok(AuthResult {
+        headers: result
+            .set_headers
+            .unwrap_or_default()
+            .into_iter()
+            .map(|h| (h.name, h.value))
+            .collect(),
+        query_parameters: result
+            .set_query_parameters
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| (p.name, p.value))
+            .collect(),
+    })
+}
@@ -0,0 +1,123 @@
+use crate::error::Result;
+use crate::resolve;
+use std::path::PathBuf;
+use std::sync::Arc;
+use yaak_core::AppContext;
+use yaak_crypto::manager::EncryptionManager;
+use yaak_http::cookies::CookieStore;
+use yaak_http::manager::HttpConnectionManager;
+use yaak_models::blob_manager::BlobManager;
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{Environment, EnvironmentVariable};
+use yaak_models::query_manager::QueryManager;
+use yaak_plugins::events::{PluginContext, RenderPurpose};
+use yaak_plugins::manager::PluginManager;
+use yaak_plugins::template_callback::PluginTemplateCallback;
+
+/// Shared state for commands that render and send requests
+#[derive(Clone)]
+pub(crate) struct CliContext {
+    pub app_id: String,
+    pub data_dir: PathBuf,
+    pub query_manager: QueryManager,
+    pub blob_manager: BlobManager,
+    pub plugin_manager: Arc<PluginManager>,
+    pub encryption_manager: Arc<EncryptionManager>,
+    pub connection_manager: Arc<HttpConnectionManager>,
+    /// Environment ID or name, resolved within each request's workspace
+    pub environment: Option<String>,
+    /// `--var` overrides, applied on top of every environment
+    pub variables: Vec<(String, String)>,
+    /// Cookie jar to use instead of the workspace's first one
+    pub cookie_jar_id: Option<String>,
+    /// Send requests without a cookie jar
+    pub no_cookies: bool,
+    /// Cookies of the `--cookie-jar` file, used instead of the workspace's cookie jars
+    pub cookie_store: Option<CookieStore>,
+    /// Save responses and their events to the database, like the app does
+    pub persist: bool,
+    /// Models come from a sync directory, so changes would be thrown away
+    pub read_only: bool,
+}
+
+impl CliContext {
+    pub fn db(&self) -> DbContext<'_> {
+        self.query_manager.connect()
+    }
+
+    /// Plugin context for requests in a workspace. A stable ID lets requests to the same
+    /// workspace share a cached client.
+    pub fn plugin_context(&self, workspace_id: &str) -> PluginContext {
+        PluginContext {
+            id: format!("cli.{workspace_id}"),
+            label: None,
+            workspace_id: Some(workspace_id.to_string()),
+        }
+    }
+
+    pub fn template_callback(&self, workspace_id: &str) -> PluginTemplateCallback {
+        PluginTemplateCallback::new(
+            self.plugin_manager.clone(),
+            self.encryption_manager.clone(),
+            &self.plugin_context(workspace_id),
+            RenderPurpose::Send,
+        )
+    }
+
+    /// The `--environment` selected for a workspace, if any
+    pub fn environment(&self, workspace_id: &str) -> Result<Option<Environment>> {
+        match &self.environment {
+            Some(arg) => Ok(Some(resolve::environment(&self.db(), workspace_id, arg)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `--var` overrides as environment variables
+    pub fn variable_overrides(&self) -> Vec<EnvironmentVariable> {
+        self.variables
+            .iter()
+            .map(|(name, value)| EnvironmentVariable {
+                enabled: true,
+                name: name.clone(),
+                value: value.clone(),
+                id: None,
+            })
+            .collect()
+    }
+
+    /// Environments to render a request with, most specific first, like
+    /// `DbContext::resolve_environments` but with the `--var` overrides in front
+    pub fn environment_chain(
+        &self,
+        workspace_id: &str,
+        folder_id: Option<&str>,
+    ) -> Result<Vec<Environment>> {
+        let environment = self.environment(workspace_id)?;
+        let mut chain = self.db().resolve_environments(
+            workspace_id,
+            folder_id,
+            environment.as_ref().map(|e| e.id.as_str()),
+        )?;
+        chain.insert(0, self.overrides_environment());
+        Ok(chain)
+    }
+
+    /// An environment holding only the `--var` overrides, for requests outside a workspace
+    pub fn overrides_environment(&self) -> Environment {
+        Environment { variables: self.variable_overrides(), ..Default::default() }
+    }
+}
+
+impl AppContext for CliContext {
+    fn app_data_dir(&self) -> PathBuf {
+        self.data_dir.clone()
+    }
+
+    fn app_identifier(&self) -> &str {
+        &self.app_id
+    }
+
+    fn is_dev(&self) -> bool {
+        cfg!(debug_assertions)
+    }
+}
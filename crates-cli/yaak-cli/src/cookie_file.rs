@@ -0,0 +1,69 @@
+use crate::error::Result;
+use std::path::{Path, PathBuf};
+use yaak_http::cookie_file::{CookieFileFormat, parse_cookie_file, write_cookie_file};
+use yaak_http::cookies::CookieStore;
+
+/// A `--cookie-jar` file. Like with curl, cookies are read from it before the command runs and
+/// every cookie is written back afterwards, so it can be passed to the next command.
+pub(crate) struct CookieFile {
+    path: PathBuf,
+    format: CookieFileFormat,
+    pub store: CookieStore,
+}
+
+impl CookieFile {
+    /// Load the cookies of the file. A file that doesn't exist yet starts out empty.
+    pub fn load(path: &Path) -> Result<Self> {
+        let (format, cookies) = match std::fs::read_to_string(path) {
+            Ok(contents) => (CookieFileFormat::detect(&contents), parse_cookie_file(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (default_format(path), vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path: path.to_path_buf(), format, store: CookieStore::from_cookies(cookies) })
+    }
+
+    /// Write the cookies back, in the format the file was in
+    pub fn save(&self) -> Result<()> {
+        let contents = write_cookie_file(&self.store.get_all_cookies(), self.format)?;
+        std::fs::write(&self.path, contents)?;
+        Ok(())
+    }
+}
+
+/// New files are Netscape cookie files, like curl writes, unless they're named `*.json`
+fn default_format(path: &Path) -> CookieFileFormat {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => CookieFileFormat::Json,
+        _ => CookieFileFormat::Netscape,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = std::env::temp_dir().join(format!("yaak-cookies-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("cookies.txt");
+        let _ = std::fs::remove_file(&path);
+
+        let file = CookieFile::load(&path).unwrap();
+        assert!(file.store.get_all_cookies().is_empty());
+
+        let url = Url::parse("https://example.com/").unwrap();
+        file.store.store_cookies_from_response(&url, &["session=abc123".to_string()]);
+        file.save().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("# Netscape HTTP Cookie File"));
+        assert!(contents.contains("example.com\tFALSE\t/\tFALSE\t0\tsession\tabc123\n"));
+
+        let file = CookieFile::load(&path).unwrap();
+        assert_eq!(file.store.get_cookie_header(&url), Some("session=abc123".to_string()));
+
+        assert_eq!(default_format(Path::new("cookies.JSON")), CookieFileFormat::Json);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
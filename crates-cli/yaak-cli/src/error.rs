@@ -0,0 +1,71 @@
+use thiserror::Error;
+
+/// Exit code for errors without a more specific code
+pub(crate) const EXIT_FAILURE: u8 = 1;
+/// Exit code when a response was received but its status was not 2xx (or not OK, for gRPC)
+pub(crate) const EXIT_HTTP_STATUS: u8 = 3;
+/// Exit code when a request could not be rendered (e.g. a template function failed)
+pub(crate) const EXIT_RENDER_ERROR: u8 = 4;
+/// Exit code when a request could not be sent or the response could not be read
+pub(crate) const EXIT_NETWORK_ERROR: u8 = 5;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    TemplateError(#[from] yaak_templates::error::Error),
+
+    #[error(transparent)]
+    ModelError(#[from] yaak_models::error::Error),
+
+    #[error(transparent)]
+    HttpError(#[from] yaak_http::error::Error),
+
+    #[error(transparent)]
+    PluginError(#[from] yaak_plugins::error::Error),
+
+    #[error(transparent)]
+    SendError(#[from] yaak_send::error::Error),
+
+    #[error(transparent)]
+    SyncError(#[from] yaak_sync::error::Error),
+
+    #[error(transparent)]
+    GrpcError(#[from] yaak_grpc::error::Error),
+
+    #[error(transparent)]
+    WebsocketError(#[from] yaak_ws::error::Error),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("I/O error: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("{0}")]
+    GenericError(String),
+}
+
+impl Error {
+    /// Process exit code for this error, so scripts can tell failures apart
+    pub fn exit_code(&self) -> u8 {
+        use yaak_grpc::error::Error as GrpcError;
+        use yaak_send::error::Error as SendError;
+        use yaak_ws::error::Error as WebsocketError;
+        match self {
+            Error::TemplateError(_)
+            | Error::SendError(SendError::TemplateError(_))
+            | Error::WebsocketError(WebsocketError::TemplateError(_)) => EXIT_RENDER_ERROR,
+            Error::GrpcError(GrpcError::TonicError(_)) => EXIT_HTTP_STATUS,
+            Error::GrpcError(GrpcError::GrpcStreamError(e)) if e.status.is_some() => {
+                EXIT_HTTP_STATUS
+            }
+            Error::HttpError(_)
+            | Error::SendError(SendError::HttpError(_))
+            | Error::GrpcError(_)
+            | Error::WebsocketError(_) => EXIT_NETWORK_ERROR,
+            _ => EXIT_FAILURE,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
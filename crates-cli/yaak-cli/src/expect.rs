@@ -0,0 +1,252 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use std::collections::BTreeMap;
+use std::path::Path;
+use yaak_models::models::HttpRequest;
+
+/// Key in the expectations file that applies to requests without their own entry
+const WILDCARD_KEY: &str = "*";
+
+/// Expectations file contents, keyed by request ID or request name.
+///
+/// ```json
+/// {
+///   "*": { "status": "2xx" },
+///   "Get user": {
+///     "status": 200,
+///     "headers": { "content-type": "application/json" },
+///     "body": [{ "path": "$.id", "equals": 123 }, { "path": "$.email", "exists": true }]
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct Expectations(BTreeMap<String, Expectation>);
+
+impl Expectations {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let expectations: Expectations = serde_json::from_str(&contents)?;
+
+        // Validate JSONPath expressions up front so typos fail before anything is sent
+        for (key, expectation) in &expectations.0 {
+            for check in &expectation.body {
+                JsonPath::parse(&check.path).map_err(|e| {
+                    GenericError(format!("Invalid JSONPath {:?} for {key:?}: {e}", check.path))
+                })?;
+            }
+        }
+
+        Ok(expectations)
+    }
+
+    /// Find the expectation for a request, matching ID first, then name, then the wildcard
+    pub fn for_request(&self, request: &HttpRequest) -> Expectation {
+        self.0
+            .get(&request.id)
+            .or_else(|| self.0.get(&request.name))
+            .or_else(|| self.0.get(WILDCARD_KEY))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct Expectation {
+    /// Exact status code (`200`) or a pattern like `"2xx"`
+    pub status: Option<StatusExpectation>,
+    /// Header values that must match exactly. Names are case-insensitive.
+    pub headers: BTreeMap<String, String>,
+    /// JSONPath checks against the response body
+    pub body: Vec<BodyExpectation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum StatusExpectation {
+    Code(u16),
+    Pattern(String),
+}
+
+impl StatusExpectation {
+    fn matches(&self, status: u16) -> bool {
+        match self {
+            StatusExpectation::Code(code) => *code == status,
+            StatusExpectation::Pattern(pattern) => status_matches_pattern(pattern, status),
+        }
+    }
+}
+
+impl std::fmt::Display for StatusExpectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusExpectation::Code(code) => write!(f, "{code}"),
+            StatusExpectation::Pattern(pattern) => write!(f, "{pattern}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BodyExpectation {
+    /// JSONPath expression, e.g. `$.data[0].id`
+    pub path: String,
+    /// The first matched value must equal this
+    #[serde(default)]
+    pub equals: Option<Value>,
+    /// Whether the path must (or must not) match anything. Defaults to true.
+    #[serde(default)]
+    pub exists: Option<bool>,
+}
+
+impl Expectation {
+    /// Check a response and return a message for every failed expectation
+    pub fn check(&self, status: u16, headers: &[(String, String)], body: &[u8]) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        match &self.status {
+            Some(expected) if !expected.matches(status) => {
+                failures.push(format!("Expected status {expected} but got {status}"));
+            }
+            None if status >= 400 => {
+                failures.push(format!("Expected a successful status but got {status}"));
+            }
+            _ => {}
+        }
+
+        for (name, expected) in &self.headers {
+            let actual = headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v);
+            match actual {
+                None => failures.push(format!("Expected header {name} to be present")),
+                Some(v) if v != expected => failures
+                    .push(format!("Expected header {name} to be {expected:?} but got {v:?}")),
+                Some(_) => {}
+            }
+        }
+
+        if self.body.is_empty() {
+            return failures;
+        }
+
+        let json: Value = match serde_json::from_slice(body) {
+            Ok(v) => v,
+            Err(e) => {
+                failures.push(format!("Expected a JSON body but failed to parse it: {e}"));
+                return failures;
+            }
+        };
+
+        for check in &self.body {
+            // Paths were validated when the expectations were loaded
+            let path = match JsonPath::parse(&check.path) {
+                Ok(p) => p,
+                Err(e) => {
+                    failures.push(format!("Invalid JSONPath {:?}: {e}", check.path));
+                    continue;
+                }
+            };
+            let nodes = path.query(&json).all();
+            let should_exist = check.exists.unwrap_or(true);
+
+            if nodes.is_empty() {
+                if should_exist || check.equals.is_some() {
+                    failures.push(format!("Expected {} to match a value", check.path));
+                }
+                continue;
+            }
+
+            if !should_exist {
+                failures.push(format!("Expected {} to not match any value", check.path));
+                continue;
+            }
+
+            if let Some(expected) = &check.equals {
+                if nodes[0] != expected {
+                    failures.push(format!(
+                        "Expected {} to equal {} but got {}",
+                        check.path, expected, nodes[0]
+                    ));
+                }
+            }
+        }
+
+        failures
+    }
+}
+
+/// Match a status against a pattern where `x` matches any digit (e.g. `2xx`, `20x`, `404`)
+pub(crate) fn status_matches_pattern(pattern: &str, status: u16) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let status = status.to_string();
+    pattern.len() == status.len()
+        && pattern.chars().zip(status.chars()).all(|(p, s)| p == 'x' || p == s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_status_matches_pattern() {
+        assert!(status_matches_pattern("2xx", 200));
+        assert!(status_matches_pattern("2XX", 204));
+        assert!(status_matches_pattern("20x", 201));
+        assert!(status_matches_pattern("404", 404));
+        assert!(!status_matches_pattern("2xx", 301));
+        assert!(!status_matches_pattern("2x", 200));
+    }
+
+    #[test]
+    fn test_default_expectation_fails_on_error_status() {
+        let expectation = Expectation::default();
+        assert!(expectation.check(200, &[], b"").is_empty());
+        assert!(expectation.check(302, &[], b"").is_empty());
+        assert_eq!(expectation.check(500, &[], b"").len(), 1);
+    }
+
+    #[test]
+    fn test_header_expectation_is_case_insensitive() {
+        let expectation = Expectation {
+            headers: BTreeMap::from([("Content-Type".to_string(), "text/plain".to_string())]),
+            ..Default::default()
+        };
+        let headers = vec![("content-type".to_string(), "text/plain".to_string())];
+        assert!(expectation.check(200, &headers, b"").is_empty());
+
+        let headers = vec![("content-type".to_string(), "text/html".to_string())];
+        assert_eq!(expectation.check(200, &headers, b"").len(), 1);
+    }
+
+    #[test]
+    fn test_body_expectations() {
+        let expectation = Expectation {
+            body: vec![
+                BodyExpectation {
+                    path: "$.user.id".to_string(),
+                    equals: Some(json!(123)),
+                    exists: None,
+                },
+                BodyExpectation {
+                    path: "$.user.deleted".to_string(),
+                    equals: None,
+                    exists: Some(false),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let body = br#"{"user":{"id":123}}"#;
+        assert!(expectation.check(200, &[], body).is_empty());
+
+        let body = br#"{"user":{"id":456,"deleted":true}}"#;
+        assert_eq!(expectation.check(200, &[], body).len(), 2);
+
+        let body = b"not json";
+        assert_eq!(expectation.check(200, &[], body).len(), 1);
+    }
+}
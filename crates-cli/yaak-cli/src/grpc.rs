@@ -0,0 +1,337 @@
+use crate::auth::authenticate;
+use crate::context::CliContext;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::resolve;
+use clap::{Args, Subcommand};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use yaak_grpc::manager::{DynamicMessage, GrpcConfig, GrpcHandle};
+use yaak_grpc::{render_grpc_request, serialize_message};
+use yaak_http::client::HttpConnectionProxySetting;
+use yaak_models::models::{GrpcRequest, NetworkSettings};
+use yaak_models::render::make_vars_hashmap;
+use yaak_templates::{RenderErrorBehavior, RenderOptions, parse_and_render};
+use yaak_tls::render::render_client_certificate;
+use yaak_tls::{ClientCertificateConfig, TlsSettings, find_client_certificate};
+
+#[derive(Subcommand)]
+pub(crate) enum GrpcCommands {
+    /// List services and methods, using server reflection or --proto files
+    Services {
+        #[command(flatten)]
+        target: GrpcTarget,
+        /// Print the services and their input schemas as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Invoke a method and print each response message as a line of JSON (NDJSON).
+    /// Client-streaming methods read one JSON message per line from stdin.
+    Call {
+        #[command(flatten)]
+        target: GrpcTarget,
+        /// Method as `package.Service/Method` (required with --url)
+        #[arg(long)]
+        method: Option<String>,
+        /// JSON message, instead of the request's saved message
+        #[arg(long, short = 'd')]
+        message: Option<String>,
+    },
+}
+
+/// A saved gRPC request, or an ad-hoc server given with `--url`
+#[derive(Args)]
+pub(crate) struct GrpcTarget {
+    /// gRPC request ID, name, or path
+    #[arg(required_unless_present = "url", conflicts_with = "url")]
+    request: Option<String>,
+    /// Server URL for an ad-hoc call
+    #[arg(long)]
+    url: Option<String>,
+    /// Proto file or include directory to use instead of server reflection (repeatable)
+    #[arg(long = "proto", value_name = "PATH")]
+    protos: Vec<PathBuf>,
+    /// Add a metadata entry (repeatable)
+    #[arg(long = "metadata", value_name = "NAME=VALUE", value_parser = resolve::parse_var)]
+    metadata: Vec<(String, String)>,
+}
+
+/// A rendered request with everything needed to connect
+struct PreparedCall {
+    request: GrpcRequest,
+    uri: String,
+    protos: Vec<PathBuf>,
+    metadata: BTreeMap<String, String>,
+    tls_settings: TlsSettings,
+    client_cert: Option<ClientCertificateConfig>,
+    proxy: HttpConnectionProxySetting,
+    network: NetworkSettings,
+}
+
+pub(crate) async fn run(ctx: &CliContext, command: GrpcCommands) -> Result<()> {
+    match command {
+        GrpcCommands::Services { target, json } => {
+            let call = prepare(ctx, &target, None).await?;
+            let services = grpc_handle()
+                .services(
+                    &call.request.id,
+                    &call.uri,
+                    &call.protos,
+                    &call.metadata,
+                    call.tls_settings,
+                    call.client_cert,
+                    call.proxy,
+                    call.network,
+                )
+                .await?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&services)?);
+                return Ok(());
+            }
+
+            for service in services {
+                for method in service.methods {
+                    let streaming = match (method.client_streaming, method.server_streaming) {
+                        (true, true) => " (bidi streaming)",
+                        (true, false) => " (client streaming)",
+                        (false, true) => " (server streaming)",
+                        (false, false) => "",
+                    };
+                    println!("{}/{}{streaming}", service.name, method.name);
+                }
+            }
+        }
+        GrpcCommands::Call { target, method, message } => {
+            let mut call = prepare(ctx, &target, message).await?;
+            if let Some(method) = method {
+                let (service, method) = method.split_once('/').ok_or_else(|| {
+                    GenericError(format!("Expected method as package.Service/Method: {method}"))
+                })?;
+                call.request.service = Some(service.to_string());
+                call.request.method = Some(method.to_string());
+            }
+            invoke(&call).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn prepare(
+    ctx: &CliContext,
+    target: &GrpcTarget,
+    message: Option<String>,
+) -> Result<PreparedCall> {
+    let opt = RenderOptions { error_behavior: RenderErrorBehavior::Throw };
+
+    let (request, auth_context_id, environment_chain, tls_settings, certs) = match &target.url {
+        Some(url) => {
+            let request = GrpcRequest { url: url.clone(), ..Default::default() };
+            let tls_settings = TlsSettings::default();
+            let certs = ctx.db().get_settings().client_certificates;
+            (request, String::new(), vec![ctx.overrides_environment()], tls_settings, certs)
+        }
+        None => {
+            let db = ctx.db();
+            let arg = target.request.as_deref().unwrap_or_default();
+            let unrendered = resolve::grpc_request(&db, arg)?;
+            let workspace = db.get_workspace(&unrendered.workspace_id)?;
+
+            let mut request = unrendered.clone();
+            let (authentication_type, authentication, auth_context_id) =
+                db.resolve_auth_for_grpc_request(&unrendered)?;
+            request.authentication_type = authentication_type;
+            request.authentication = authentication;
+            request.metadata = db.resolve_metadata_for_grpc_request(&unrendered)?;
+
+            let environment_chain =
+                ctx.environment_chain(&unrendered.workspace_id, unrendered.folder_id.as_deref())?;
+            let tls_settings = TlsSettings::for_workspace(&workspace);
+            let certs = db.resolve_client_certificates(
+                &unrendered.workspace_id,
+                unrendered.folder_id.as_deref(),
+            )?;
+            (request, auth_context_id, environment_chain, tls_settings, certs)
+        }
+    };
+
+    // A request from a --url isn't in a workspace, so it has no network settings
+    let network = match &target.url {
+        Some(_) => NetworkSettings::default(),
+        None => ctx
+            .db()
+            .resolve_network_settings(&request.workspace_id, request.folder_id.as_deref())?,
+    };
+
+    let cb = ctx.template_callback(&request.workspace_id);
+    let mut request = render_grpc_request(&request, environment_chain.clone(), &cb, &opt).await?;
+
+    // The message isn't part of render_grpc_request, so it's rendered on its own like the app
+    let message = message.unwrap_or_else(|| request.message.clone());
+    let message = if message.is_empty() { "{}".to_string() } else { message };
+    let vars = make_vars_hashmap(environment_chain.clone());
+    request.message = parse_and_render(&message, &vars, &cb, &opt).await?;
+
+    let mut metadata: Vec<(String, String)> = request
+        .metadata
+        .iter()
+        .filter(|m| m.enabled && !(m.name.is_empty() && m.value.is_empty()))
+        .map(|m| (m.name.clone(), m.value.clone()))
+        .collect();
+    let auth = authenticate(
+        ctx,
+        &request.workspace_id,
+        request.authentication_type.as_deref(),
+        &request.authentication,
+        &auth_context_id,
+        &request.url,
+        &metadata,
+    )
+    .await?;
+    metadata.extend(auth.headers);
+    metadata.extend(target.metadata.iter().cloned());
+
+    let client_cert = match find_client_certificate(&request.url, &certs) {
+        Some(c) => Some(render_client_certificate(c, environment_chain, &cb, &opt).await?),
+        None => None,
+    };
+
+    Ok(PreparedCall {
+        uri: safe_uri(&request.url),
+        request,
+        protos: target.protos.clone(),
+        metadata: metadata.into_iter().collect(),
+        tls_settings,
+        client_cert,
+        proxy: ctx.db().get_settings().proxy.into(),
+        network,
+    })
+}
+
+async fn invoke(call: &PreparedCall) -> Result<()> {
+    let (service, method) = match (&call.request.service, &call.request.method) {
+        (Some(service), Some(method)) => (service.as_str(), method.as_str()),
+        _ => return Err(GenericError("Service and method are required".to_string())),
+    };
+
+    let connection = grpc_handle()
+        .connect(
+            &call.request.id,
+            &call.uri,
+            &call.protos,
+            &call.metadata,
+            call.tls_settings.clone(),
+            call.client_cert.clone(),
+            call.proxy.clone(),
+            call.network.clone(),
+        )
+        .await?;
+    let method_desc = connection.method(service, method).await?;
+
+    if !method_desc.is_client_streaming() && !method_desc.is_server_streaming() {
+        let response = connection
+            .unary(service, method, &call.request.message, &call.metadata, call.client_cert.clone())
+            .await?;
+        print_message(&response.into_inner())?;
+        return Ok(());
+    }
+
+    if !method_desc.is_client_streaming() {
+        let response = connection
+            .server_streaming(service, method, &call.request.message, &call.metadata)
+            .await?;
+        let mut stream = response.into_inner();
+        while let Some(msg) = stream.message().await.map_err(yaak_grpc::error::Error::from)? {
+            print_message(&msg)?;
+        }
+        return Ok(());
+    }
+
+    // Client messages are read from stdin, one JSON message per line
+    let (in_msg_tx, in_msg_rx) = mpsc::channel::<String>(16);
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if in_msg_tx.send(line).await.is_err() {
+                break;
+            }
+        }
+    });
+    let in_msg_stream = ReceiverStream::new(in_msg_rx);
+    let on_message = |result: std::result::Result<String, String>| {
+        if let Err(e) = result {
+            eprintln!("Failed to send message: {e}");
+        }
+    };
+
+    if method_desc.is_server_streaming() {
+        let response = connection
+            .streaming(
+                service,
+                method,
+                in_msg_stream,
+                &call.metadata,
+                call.client_cert.clone(),
+                on_message,
+            )
+            .await?;
+        let mut stream = response.into_inner();
+        while let Some(msg) = stream.message().await.map_err(yaak_grpc::error::Error::from)? {
+            print_message(&msg)?;
+        }
+    } else {
+        let response = connection
+            .client_streaming(
+                service,
+                method,
+                in_msg_stream,
+                &call.metadata,
+                call.client_cert.clone(),
+                on_message,
+            )
+            .await?;
+        print_message(&response.into_inner())?;
+    }
+
+    Ok(())
+}
+
+/// Print a message as a single line of JSON
+fn print_message(msg: &DynamicMessage) -> Result<()> {
+    let pretty = serialize_message(msg).map_err(GenericError)?;
+    println!("{}", serde_json::to_string(&serde_json::from_str::<Value>(&pretty)?)?);
+    Ok(())
+}
+
+/// Like the plugin runtime, `yaakprotoc` and its includes are found through an environment
+/// variable, falling back to the app's vendored copy in development
+fn grpc_handle() -> GrpcHandle {
+    let protoc_dir = std::env::var("YAAK_PROTOC_DIR").map(PathBuf::from).unwrap_or_else(|_| {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../crates-tauri/yaak-app/vendored/protoc")
+    });
+    let protoc_bin_name = if cfg!(windows) { "yaakprotoc.exe" } else { "yaakprotoc" };
+    GrpcHandle::new(GrpcConfig {
+        protoc_include_dir: protoc_dir.join("include"),
+        protoc_bin_path: protoc_dir.join(protoc_bin_name),
+    })
+}
+
+fn safe_uri(endpoint: &str) -> String {
+    if endpoint.starts_with("http://")
+        || endpoint.starts_with("https://")
+        || endpoint.starts_with("http+unix://")
+    {
+        endpoint.into()
+    } else {
+        format!("http://{}", endpoint)
+    }
+}
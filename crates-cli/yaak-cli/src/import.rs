@@ -0,0 +1,67 @@
+use crate::context::CliContext;
+use crate::error::Result;
+use crate::resolve;
+use std::path::Path;
+use yaak_core::WorkspaceContext;
+use yaak_models::util::{BatchUpsertResult, UpdateSource};
+use yaak_plugins::import::upsert_import_resources;
+
+/// Import a file with the importer plugins (OpenAPI, Postman, Insomnia, curl, ...), like
+/// the app's import dialog. Importers that add to the current workspace use `workspace`.
+pub(crate) async fn import(
+    ctx: &CliContext,
+    file: &Path,
+    workspace: Option<&str>,
+) -> Result<BatchUpsertResult> {
+    let workspace = match workspace {
+        Some(arg) => Some(resolve::workspace(&ctx.db(), arg)?),
+        None => None,
+    };
+    let workspace_id = workspace.map(|w| w.id);
+
+    let content = std::fs::read_to_string(file)?;
+    let plugin_context = ctx.plugin_context(workspace_id.as_deref().unwrap_or_default());
+    let import_result = ctx.plugin_manager.import_data(&plugin_context, &content).await?;
+
+    let workspace_ctx = WorkspaceContext { workspace_id, ..Default::default() };
+    Ok(upsert_import_resources(
+        &ctx.query_manager,
+        &workspace_ctx,
+        import_result.resources,
+        &UpdateSource::Import,
+    )?)
+}
+
+/// One line per kind of resource that was imported, e.g. `2 folders`
+pub(crate) fn summary(result: &BatchUpsertResult) -> Vec<String> {
+    [
+        (result.workspaces.len(), "workspace", "workspaces"),
+        (result.environments.len(), "environment", "environments"),
+        (result.folders.len(), "folder", "folders"),
+        (result.http_requests.len(), "HTTP request", "HTTP requests"),
+        (result.grpc_requests.len(), "gRPC request", "gRPC requests"),
+        (result.websocket_requests.len(), "WebSocket request", "WebSocket requests"),
+    ]
+    .into_iter()
+    .filter(|(count, _, _)| *count > 0)
+    .map(|(count, one, many)| format!("{count} {}", if count == 1 { one } else { many }))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaak_models::models::{Folder, HttpRequest, Workspace};
+
+    #[test]
+    fn test_summary() {
+        let result = BatchUpsertResult {
+            workspaces: vec![Workspace::default()],
+            folders: vec![Folder::default(), Folder::default()],
+            http_requests: vec![HttpRequest::default(); 3],
+            ..Default::default()
+        };
+        assert_eq!(summary(&result), vec!["1 workspace", "2 folders", "3 HTTP requests"]);
+        assert!(summary(&BatchUpsertResult::default()).is_empty());
+    }
+}
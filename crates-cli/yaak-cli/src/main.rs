@@ -1,38 +1,96 @@
-use clap::{Parser, Subcommand};
-use log::info;
-use serde_json::Value;
-use std::collections::BTreeMap;
+mod auth;
+mod context;
+mod cookie_file;
+mod error;
+mod expect;
+mod grpc;
+mod import;
+mod output;
+mod report;
+mod resolve;
+mod run;
+mod send;
+mod sync_dir;
+mod ws;
+
+use crate::context::CliContext;
+use crate::cookie_file::CookieFile;
+use crate::error::Error::GenericError;
+use crate::error::{EXIT_FAILURE, EXIT_HTTP_STATUS, Result};
+use crate::grpc::GrpcCommands;
+use crate::output::{CliResponse, OutputArgs, Timing, spawn_event_listener};
+use crate::run::RunOptions;
+use crate::send::{read_response_body, send_http_request};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant};
 use yaak_crypto::manager::EncryptionManager;
-use yaak_http::path_placeholders::apply_path_placeholders;
-use yaak_http::sender::{HttpSender, ReqwestSender};
+use yaak_http::manager::HttpConnectionManager;
+use yaak_http::sender::{BodyStats, HttpSender, ReqwestSender};
+use yaak_http::transaction::HttpTransaction;
 use yaak_http::types::{SendableHttpRequest, SendableHttpRequestOptions};
-use yaak_models::models::{HttpRequest, HttpRequestHeader, HttpUrlParameter};
-use yaak_models::render::make_vars_hashmap;
-use yaak_models::util::UpdateSource;
-use yaak_plugins::events::{PluginContext, RenderPurpose};
+use yaak_models::models::HttpRequest;
+use yaak_models::util::{UpdateSource, get_workspace_export_resources};
+use yaak_plugins::events::PluginContext;
 use yaak_plugins::manager::PluginManager;
-use yaak_plugins::template_callback::PluginTemplateCallback;
-use yaak_templates::{RenderOptions, parse_and_render, render_json_value_raw};
 
 #[derive(Parser)]
 #[command(name = "yapicli")]
 #[command(about = "Yapi CLI - API client from the command line")]
+#[command(
+    after_help = "Exit codes: 0 success, 1 error, 3 non-2xx response or non-OK gRPC status, \
+        4 render error, 5 network error"
+)]
 struct Cli {
     /// Use a custom data directory
     #[arg(long, global = true)]
     data_dir: Option<PathBuf>,
 
-    /// Environment ID to use for variable substitution
+    /// Load workspaces and requests from a sync directory instead of the app's database
+    #[arg(long, global = true, conflicts_with = "persist")]
+    sync_dir: Option<PathBuf>,
+
+    /// Environment ID or name to use for variable substitution
     #[arg(long, short, global = true)]
     environment: Option<String>,
 
-    /// Enable verbose logging
+    /// Set a variable, overriding every environment (repeatable)
+    #[arg(
+        long = "var",
+        value_name = "NAME=VALUE",
+        global = true,
+        value_parser = resolve::parse_var
+    )]
+    vars: Vec<(String, String)>,
+
+    /// Enable verbose logging and print a timeline of each request to stderr
     #[arg(long, short, global = true)]
     verbose: bool,
 
+    /// Cookie jar ID to use (defaults to the workspace's first cookie jar)
+    #[arg(long, global = true, conflicts_with = "no_cookies")]
+    cookie_jar_id: Option<String>,
+
+    /// Send requests without a cookie jar
+    #[arg(long, global = true)]
+    no_cookies: bool,
+
+    /// Read cookies from this file instead of the workspace's cookie jar, and write them back
+    /// afterwards, like curl's --cookie-jar. Netscape cookie files and JSON dumps of browser
+    /// cookies are supported.
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        conflicts_with_all = ["cookie_jar_id", "no_cookies"]
+    )]
+    cookie_jar: Option<PathBuf>,
+
+    /// Save responses and their events to the database so they show up in the app
+    #[arg(long, global = true)]
+    persist: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -43,23 +101,83 @@ enum Commands {
     Workspaces,
     /// List requests in a workspace
     Requests {
-        /// Workspace ID
-        workspace_id: String,
+        /// Workspace ID or name
+        workspace: String,
     },
-    /// Send an HTTP request by ID
+    /// Send an HTTP request by ID, name, or path (e.g. "My API/Users/Get user")
     Send {
-        /// Request ID
-        request_id: String,
+        /// Request ID, name, or path
+        request: String,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Send every request in a workspace or folder and check the responses
+    Run {
+        /// Workspace or folder ID, name, or path
+        target: String,
+        /// JSON file with expectations keyed by request ID or name
+        #[arg(long)]
+        expectations: Option<PathBuf>,
+        /// Expected status for requests without one in the expectations file (e.g. 200 or 2xx)
+        #[arg(long)]
+        expect_status: Option<String>,
+        /// Write a JUnit XML report to this file
+        #[arg(long)]
+        junit: Option<PathBuf>,
+        /// Write a JSON report to this file
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// Stop after the first failing request
+        #[arg(long)]
+        bail: bool,
+    },
+    /// Call gRPC services
+    Grpc {
+        #[command(subcommand)]
+        command: GrpcCommands,
+    },
+    /// Connect a WebSocket request, send stdin line by line, and print received frames
+    Ws {
+        /// WebSocket request ID, name, or path
+        request: String,
+        /// Milliseconds to keep printing frames after stdin is closed
+        #[arg(long, default_value_t = 1000)]
+        wait: u64,
+    },
+    /// Import a file (OpenAPI, Postman, Insomnia, curl, ...) using the importer plugins
+    Import {
+        /// File to import
+        file: PathBuf,
+        /// Workspace ID or name, for imports that add to an existing workspace
+        #[arg(long)]
+        workspace: Option<String>,
+    },
+    /// Export workspaces
+    Export {
+        /// Workspace IDs or names
+        #[arg(required = true)]
+        workspaces: Vec<String>,
+        /// Export format
+        #[arg(long, value_enum, default_value_t = ExportFormat::YaakJson)]
+        format: ExportFormat,
+        /// Write the export to this file instead of stdout
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Include environments that aren't shared (private environments)
+        #[arg(long)]
+        include_private_environments: bool,
     },
     /// Send a GET request to a URL
     Get {
         /// URL to request
         url: String,
+        #[command(flatten)]
+        output: OutputArgs,
     },
     /// Create a new HTTP request
     Create {
-        /// Workspace ID
-        workspace_id: String,
+        /// Workspace ID or name
+        workspace: String,
         /// Request name
         #[arg(short, long)]
         name: String,
@@ -72,84 +190,10 @@ enum Commands {
     },
 }
 
-/// Render an HTTP request with template variables and plugin functions
-async fn render_http_request(
-    r: &HttpRequest,
-    environment_chain: Vec<yaak_models::models::Environment>,
-    cb: &PluginTemplateCallback,
-    opt: &RenderOptions,
-) -> yaak_templates::error::Result<HttpRequest> {
-    let vars = &make_vars_hashmap(environment_chain);
-
-    let mut url_parameters = Vec::new();
-    for p in r.url_parameters.clone() {
-        if !p.enabled {
-            continue;
-        }
-        url_parameters.push(HttpUrlParameter {
-            enabled: p.enabled,
-            name: parse_and_render(p.name.as_str(), vars, cb, opt).await?,
-            value: parse_and_render(p.value.as_str(), vars, cb, opt).await?,
-            id: p.id,
-        })
-    }
-
-    let mut headers = Vec::new();
-    for p in r.headers.clone() {
-        if !p.enabled {
-            continue;
-        }
-        headers.push(HttpRequestHeader {
-            enabled: p.enabled,
-            name: parse_and_render(p.name.as_str(), vars, cb, opt).await?,
-            value: parse_and_render(p.value.as_str(), vars, cb, opt).await?,
-            id: p.id,
-        })
-    }
-
-    let mut body = BTreeMap::new();
-    for (k, v) in r.body.clone() {
-        body.insert(k, render_json_value_raw(v, vars, cb, opt).await?);
-    }
-
-    let authentication = {
-        let mut disabled = false;
-        let mut auth = BTreeMap::new();
-        match r.authentication.get("disabled") {
-            Some(Value::Bool(true)) => {
-                disabled = true;
-            }
-            Some(Value::String(tmpl)) => {
-                disabled = parse_and_render(tmpl.as_str(), vars, cb, opt)
-                    .await
-                    .unwrap_or_default()
-                    .is_empty();
-                info!(
-                    "Rendering authentication.disabled as a template: {disabled} from \"{tmpl}\""
-                );
-            }
-            _ => {}
-        }
-        if disabled {
-            auth.insert("disabled".to_string(), Value::Bool(true));
-        } else {
-            for (k, v) in r.authentication.clone() {
-                if k == "disabled" {
-                    auth.insert(k, Value::Bool(false));
-                } else {
-                    auth.insert(k, render_json_value_raw(v, vars, cb, opt).await?);
-                }
-            }
-        }
-        auth
-    };
-
-    let url = parse_and_render(r.url.clone().as_str(), vars, cb, opt).await?;
-
-    // Apply path placeholders (e.g., /users/:id -> /users/123)
-    let (url, url_parameters) = apply_path_placeholders(&url, &url_parameters);
-
-    Ok(HttpRequest { url, url_parameters, headers, body, authentication, ..r.to_owned() })
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// The JSON format the app imports and exports
+    YaakJson,
 }
 
 #[tokio::main]
@@ -168,13 +212,28 @@ async fn main() {
         dirs::data_dir().expect("Could not determine data directory").join(app_id)
     });
 
-    let db_path = data_dir.join("db.sqlite");
-    let blob_path = data_dir.join("blobs.sqlite");
-
-    let (query_manager, _blob_manager, _rx) =
-        yaak_models::init_standalone(&db_path, &blob_path).expect("Failed to initialize database");
+    let sync_db = match &cli.sync_dir {
+        Some(dir) => match sync_dir::load(dir) {
+            Ok(sync_db) => Some(sync_db),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(e.exit_code().into());
+            }
+        },
+        None => None,
+    };
 
-    let db = query_manager.connect();
+    let (query_manager, blob_manager, _rx) = match &sync_db {
+        Some(sync_db) => (sync_db.query_manager.clone(), sync_db.blob_manager.clone(), None),
+        None => {
+            let db_path = data_dir.join("db.sqlite");
+            let blob_path = data_dir.join("blobs.sqlite");
+            let (query_manager, blob_manager, rx) =
+                yaak_models::init_standalone(&db_path, &blob_path)
+                    .expect("Failed to initialize database");
+            (query_manager, blob_manager, Some(rx))
+        }
+    };
 
     // Initialize encryption manager for secure() template function
     // Use the same app_id as the Tauri app for keyring access
@@ -208,7 +267,7 @@ async fn main() {
     );
 
     // Initialize plugins from database
-    let plugins = db.list_plugins().unwrap_or_default();
+    let plugins = query_manager.connect().list_plugins().unwrap_or_default();
     if !plugins.is_empty() {
         let errors =
             plugin_manager.initialize_all_plugins(plugins, &PluginContext::new_empty()).await;
@@ -217,9 +276,60 @@ async fn main() {
         }
     }
 
-    match cli.command {
+    let cookie_file = match cli.cookie_jar.as_deref().map(CookieFile::load).transpose() {
+        Ok(cookie_file) => cookie_file,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(e.exit_code().into());
+        }
+    };
+
+    let ctx = CliContext {
+        app_id: app_id.to_string(),
+        data_dir: data_dir.clone(),
+        query_manager: query_manager.clone(),
+        blob_manager,
+        plugin_manager: plugin_manager.clone(),
+        encryption_manager: encryption_manager.clone(),
+        connection_manager: Arc::new(HttpConnectionManager::new()),
+        environment: cli.environment.clone(),
+        variables: cli.vars.clone(),
+        cookie_jar_id: cli.cookie_jar_id.clone(),
+        no_cookies: cli.no_cookies,
+        cookie_store: cookie_file.as_ref().map(|f| f.store.clone()),
+        persist: cli.persist,
+        read_only: sync_db.is_some(),
+    };
+
+    let mut exit_code = match run_command(&ctx, cli.command, cli.verbose).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            e.exit_code()
+        }
+    };
+
+    // Cookies are written even if the command failed, like curl does
+    if let Some(Err(e)) = cookie_file.map(|f| f.save()) {
+        eprintln!("Error: Failed to write cookie jar: {e}");
+        exit_code = exit_code.max(e.exit_code());
+    }
+
+    // Terminate plugin manager gracefully
+    plugin_manager.terminate().await;
+
+    if let Some(sync_db) = sync_db {
+        sync_db.cleanup();
+    }
+
+    std::process::exit(exit_code.into());
+}
+
+/// Run a command and return the process exit code
+async fn run_command(ctx: &CliContext, command: Commands, verbose: bool) -> Result<u8> {
+    match command {
         Commands::Workspaces => {
-            let workspaces = db.list_workspaces().expect("Failed to list workspaces");
+            let workspaces = ctx.db().list_workspaces()?;
             if workspaces.is_empty() {
                 println!("No workspaces found");
             } else {
@@ -228,111 +338,95 @@ async fn main() {
                 }
             }
         }
-        Commands::Requests { workspace_id } => {
-            let requests = db.list_http_requests(&workspace_id).expect("Failed to list requests");
+        Commands::Requests { workspace } => {
+            let workspace = resolve::workspace(&ctx.db(), &workspace)?;
+            let requests = ctx.db().list_http_requests(&workspace.id)?;
             if requests.is_empty() {
-                println!("No requests found in workspace {}", workspace_id);
+                println!("No requests found in workspace {}", workspace.name);
             } else {
                 for req in requests {
                     println!("{} - {} {}", req.id, req.method, req.name);
                 }
             }
         }
-        Commands::Send { request_id } => {
-            let request = db.get_http_request(&request_id).expect("Failed to get request");
-
-            // Resolve environment chain for variable substitution
-            let environment_chain = db
-                .resolve_environments(
-                    &request.workspace_id,
-                    request.folder_id.as_deref(),
-                    cli.environment.as_deref(),
-                )
-                .unwrap_or_default();
-
-            // Create template callback with plugin support
-            let plugin_context = PluginContext::new(None, Some(request.workspace_id.clone()));
-            let template_callback = PluginTemplateCallback::new(
-                plugin_manager.clone(),
-                encryption_manager.clone(),
-                &plugin_context,
-                RenderPurpose::Send,
-            );
-
-            // Render templates in the request
-            let rendered_request = render_http_request(
-                &request,
-                environment_chain,
-                &template_callback,
-                &RenderOptions::throw(),
-            )
-            .await
-            .expect("Failed to render request templates");
-
-            if cli.verbose {
-                println!("> {} {}", rendered_request.method, rendered_request.url);
-            }
-
-            // Convert to sendable request
-            let sendable = SendableHttpRequest::from_http_request(
-                &rendered_request,
-                SendableHttpRequestOptions::default(),
-            )
-            .await
-            .expect("Failed to build request");
-
-            // Create event channel for progress
-            let (event_tx, mut event_rx) = mpsc::channel(100);
-
-            // Spawn task to print events if verbose
-            let verbose = cli.verbose;
-            let verbose_handle = if verbose {
-                Some(tokio::spawn(async move {
-                    while let Some(event) = event_rx.recv().await {
-                        println!("{}", event);
-                    }
-                }))
-            } else {
-                // Drain events silently
-                tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
-                None
+        Commands::Send { request, output } => {
+            let request = resolve::http_request(&ctx.db(), &request)?;
+
+            // Render and send the request, printing the timeline if verbose
+            let (event_tx, events) = spawn_event_listener(verbose);
+            let response = send_http_request(ctx, &request, Some(event_tx)).await?;
+            let received_bytes = events.await.unwrap_or_default();
+
+            let body = read_response_body(&response)?;
+            let response = CliResponse {
+                status: response.status as u16,
+                status_reason: response.status_reason,
+                url: response.url,
+                version: response.version,
+                remote_addr: response.remote_addr,
+                headers: response.headers.into_iter().map(|h| (h.name, h.value)).collect(),
+                timing: Timing {
+                    dns: Duration::from_millis(response.elapsed_dns.max(0) as u64),
+                    connect: Duration::from_millis(response.elapsed_connect.max(0) as u64),
+                    tls: Duration::from_millis(response.elapsed_tls.max(0) as u64),
+                    ttfb: Duration::from_millis(response.elapsed_ttfb.max(0) as u64),
+                    headers: Duration::from_millis(response.elapsed_headers.max(0) as u64),
+                    total: Duration::from_millis(response.elapsed.max(0) as u64),
+                },
+                body_stats: BodyStats {
+                    size_compressed: received_bytes,
+                    size_decompressed: body.len() as u64,
+                },
+                body,
             };
-
-            // Send the request
-            let sender = ReqwestSender::new().expect("Failed to create HTTP client");
-            let response = sender.send(sendable, event_tx).await.expect("Failed to send request");
-
-            // Wait for event handler to finish
-            if let Some(handle) = verbose_handle {
-                let _ = handle.await;
-            }
-
-            // Print response
-            if verbose {
-                println!();
+            response.print(&output)?;
+            return Ok(if response.is_success() { 0 } else { EXIT_HTTP_STATUS });
+        }
+        Commands::Run { target, expectations, expect_status, junit, report, bail } => {
+            let opts = RunOptions { expectations, expect_status, junit, report, bail };
+            if !run::run(ctx, &target, opts).await? {
+                return Ok(EXIT_FAILURE);
             }
-            println!(
-                "HTTP {} {}",
-                response.status,
-                response.status_reason.as_deref().unwrap_or("")
-            );
-
-            if verbose {
-                for (name, value) in &response.headers {
-                    println!("{}: {}", name, value);
-                }
-                println!();
+        }
+        Commands::Grpc { command } => {
+            grpc::run(ctx, command).await?;
+        }
+        Commands::Ws { request, wait } => {
+            ws::run(ctx, &request, Duration::from_millis(wait), verbose).await?;
+        }
+        Commands::Import { .. } if ctx.read_only => {
+            return Err(GenericError("Data can't be imported with --sync-dir".to_string()));
+        }
+        Commands::Import { file, workspace } => {
+            let result = import::import(ctx, &file, workspace.as_deref()).await?;
+            let summary = import::summary(&result);
+            if summary.is_empty() {
+                println!("Nothing to import");
+            } else {
+                println!("Imported {}", summary.join(", "));
             }
-
-            // Print body
-            let (body, _stats) = response.text().await.expect("Failed to read response body");
-            println!("{}", body);
         }
-        Commands::Get { url } => {
-            if cli.verbose {
-                println!("> GET {}", url);
+        Commands::Export { workspaces, format, file, include_private_environments } => {
+            let db = ctx.db();
+            let workspace_ids = workspaces
+                .iter()
+                .map(|arg| resolve::workspace(&db, arg).map(|w| w.id))
+                .collect::<Result<Vec<_>>>()?;
+            let export = get_workspace_export_resources(
+                &db,
+                env!("CARGO_PKG_VERSION"),
+                workspace_ids.iter().map(String::as_str).collect(),
+                include_private_environments,
+            )?;
+            let content = match format {
+                ExportFormat::YaakJson => serde_json::to_string_pretty(&export)?,
+            };
+            match file {
+                Some(path) => std::fs::write(path, content)?,
+                None => println!("{content}"),
             }
-
+        }
+        Commands::Get { url, output } => {
             // Build a simple GET request
             let sendable = SendableHttpRequest {
                 url: url.clone(),
@@ -342,68 +436,67 @@ async fn main() {
                 options: SendableHttpRequestOptions::default(),
             };
 
-            // Create event channel for progress
-            let (event_tx, mut event_rx) = mpsc::channel(100);
-
-            // Spawn task to print events if verbose
-            let verbose = cli.verbose;
-            let verbose_handle = if verbose {
-                Some(tokio::spawn(async move {
-                    while let Some(event) = event_rx.recv().await {
-                        println!("{}", event);
-                    }
-                }))
-            } else {
-                tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
-                None
-            };
-
-            // Send the request
-            let sender = ReqwestSender::new().expect("Failed to create HTTP client");
-            let response = sender.send(sendable, event_tx).await.expect("Failed to send request");
-
-            if let Some(handle) = verbose_handle {
-                let _ = handle.await;
-            }
-
-            // Print response
-            if verbose {
-                println!();
-            }
-            println!(
-                "HTTP {} {}",
-                response.status,
-                response.status_reason.as_deref().unwrap_or("")
-            );
-
-            if verbose {
-                for (name, value) in &response.headers {
-                    println!("{}: {}", name, value);
+            // Send the request, printing the timeline if verbose
+            let (event_tx, events) = spawn_event_listener(verbose);
+            let start = Instant::now();
+            let sender = ReqwestSender::new()?;
+            let response = match &ctx.cookie_store {
+                // Cookies are only sent and stored by a transaction
+                Some(cookie_store) => {
+                    let (_cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+                    HttpTransaction::with_cookie_store(sender, cookie_store.clone())
+                        .execute_with_cancellation(sendable, cancel_rx, event_tx)
+                        .await?
                 }
-                println!();
-            }
-
-            // Print body
-            let (body, _stats) = response.text().await.expect("Failed to read response body");
-            println!("{}", body);
+                None => sender.send(sendable, event_tx).await?,
+            };
+            let headers_elapsed = start.elapsed();
+
+            let status = response.status;
+            let status_reason = response.status_reason.clone();
+            let url = response.url.clone();
+            let version = response.version.clone();
+            let remote_addr = response.remote_addr.clone();
+            let headers = response.headers.clone();
+            let (body, body_stats) = response.bytes().await?;
+            let _ = events.await;
+
+            let response = CliResponse {
+                status,
+                status_reason,
+                url,
+                version,
+                remote_addr,
+                headers,
+                timing: Timing {
+                    headers: headers_elapsed,
+                    total: start.elapsed(),
+                    ..Default::default()
+                },
+                body_stats,
+                body,
+            };
+            response.print(&output)?;
+            return Ok(if response.is_success() { 0 } else { EXIT_HTTP_STATUS });
+        }
+        Commands::Create { .. } if ctx.read_only => {
+            return Err(GenericError("Requests can't be created with --sync-dir".to_string()));
         }
-        Commands::Create { workspace_id, name, method, url } => {
+        Commands::Create { workspace, name, method, url } => {
+            let workspace = resolve::workspace(&ctx.db(), &workspace)?;
             let request = HttpRequest {
-                workspace_id,
+                workspace_id: workspace.id,
                 name,
                 method: method.to_uppercase(),
                 url,
                 ..Default::default()
             };
 
-            let created = db
-                .upsert_http_request(&request, &UpdateSource::Sync)
-                .expect("Failed to create request");
+            let created = ctx.db().upsert_http_request(&request, &UpdateSource::Sync)?;
 
             println!("Created request: {}", created.id);
         }
     }
 
-    // Terminate plugin manager gracefully
-    plugin_manager.terminate().await;
+    Ok(0)
 }
@@ -0,0 +1,347 @@
+use crate::error::Result;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use yaak_http::sender::{BodyStats, HttpResponseEvent};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Status line followed by the body
+    #[default]
+    Text,
+    /// A single JSON object with status, headers, timing and body
+    Json,
+}
+
+/// How a response is printed by `send` and `get`
+#[derive(Debug, Clone, Args)]
+pub(crate) struct OutputArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Print only the raw response body
+    #[arg(long, conflicts_with_all = ["output", "include"])]
+    pub body_only: bool,
+
+    /// Write the raw response body to this file instead of stdout
+    #[arg(long, conflicts_with_all = ["output", "include"])]
+    pub body_file: Option<PathBuf>,
+
+    /// Include the response headers in text output
+    #[arg(long, short)]
+    pub include: bool,
+
+    /// Print how long each phase of the request took to stderr, like curl's `-w` timings
+    #[arg(long)]
+    pub timing: bool,
+}
+
+/// A response ready to be printed, from either a stored request or an ad-hoc URL
+pub(crate) struct CliResponse {
+    pub status: u16,
+    pub status_reason: Option<String>,
+    pub url: String,
+    pub version: Option<String>,
+    pub remote_addr: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub timing: Timing,
+    pub body_stats: BodyStats,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Timing {
+    #[serde(rename = "dnsMs", serialize_with = "serialize_millis")]
+    pub dns: Duration,
+    #[serde(rename = "connectMs", serialize_with = "serialize_millis")]
+    pub connect: Duration,
+    #[serde(rename = "tlsMs", serialize_with = "serialize_millis")]
+    pub tls: Duration,
+    #[serde(rename = "ttfbMs", serialize_with = "serialize_millis")]
+    pub ttfb: Duration,
+    #[serde(rename = "headersMs", serialize_with = "serialize_millis")]
+    pub headers: Duration,
+    #[serde(rename = "totalMs", serialize_with = "serialize_millis")]
+    pub total: Duration,
+}
+
+impl Timing {
+    /// One line per phase with its duration and the time elapsed when it ended. The phases
+    /// of a connection that was reused (or not tracked) are left out.
+    pub fn waterfall(&self) -> Vec<String> {
+        let phases = [
+            ("DNS lookup", self.dns),
+            ("TCP connect", self.connect),
+            ("TLS handshake", self.tls),
+            ("Waiting (TTFB)", self.ttfb),
+        ];
+        let mut elapsed = Duration::ZERO;
+        let mut lines = Vec::new();
+        for (name, duration) in phases {
+            if duration.is_zero() {
+                continue;
+            }
+            elapsed += duration;
+            lines.push(format_phase(name, duration, elapsed));
+        }
+        let download = self.total.saturating_sub(self.headers);
+        lines.push(format_phase("Content download", download, self.total));
+        lines.push(format!("{:<16} {:>6}ms", "Total", self.total.as_millis()));
+        lines
+    }
+}
+
+fn format_phase(name: &str, duration: Duration, elapsed: Duration) -> String {
+    format!("{:<16} {:>6}ms  (at {}ms)", name, duration.as_millis(), elapsed.as_millis())
+}
+
+impl CliResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn print(&self, args: &OutputArgs) -> Result<()> {
+        if args.timing {
+            for line in self.timing.waterfall() {
+                eprintln!("{line}");
+            }
+        }
+
+        if let Some(path) = &args.body_file {
+            std::fs::write(path, &self.body)?;
+            return Ok(());
+        }
+
+        let mut stdout = std::io::stdout().lock();
+        if args.body_only {
+            stdout.write_all(&self.body)?;
+            stdout.flush()?;
+            return Ok(());
+        }
+
+        match args.output {
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut stdout, &self.to_json())?;
+                writeln!(stdout)?;
+            }
+            OutputFormat::Text => {
+                writeln!(
+                    stdout,
+                    "{} {} {}",
+                    self.version.as_deref().unwrap_or("HTTP"),
+                    self.status,
+                    self.status_reason.as_deref().unwrap_or("")
+                )?;
+                if args.include {
+                    for (name, value) in &self.headers {
+                        writeln!(stdout, "{name}: {value}")?;
+                    }
+                    writeln!(stdout)?;
+                }
+                writeln!(stdout, "{}", String::from_utf8_lossy(&self.body))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn to_json(&self) -> JsonResponse<'_> {
+        let (body_encoding, body) = match std::str::from_utf8(&self.body) {
+            Ok(text) => (BodyEncoding::Text, text.to_string()),
+            Err(_) => (BodyEncoding::Base64, BASE64.encode(&self.body)),
+        };
+        JsonResponse {
+            status: self.status,
+            status_reason: self.status_reason.as_deref(),
+            url: &self.url,
+            version: self.version.as_deref(),
+            remote_addr: self.remote_addr.as_deref(),
+            headers: self.headers.iter().map(|(name, value)| JsonHeader { name, value }).collect(),
+            timing: self.timing.clone(),
+            body_stats: JsonBodyStats {
+                size_compressed: self.body_stats.size_compressed,
+                size_decompressed: self.body_stats.size_decompressed,
+            },
+            body_encoding,
+            body,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonResponse<'a> {
+    status: u16,
+    status_reason: Option<&'a str>,
+    url: &'a str,
+    version: Option<&'a str>,
+    remote_addr: Option<&'a str>,
+    headers: Vec<JsonHeader<'a>>,
+    timing: Timing,
+    body_stats: JsonBodyStats,
+    body_encoding: BodyEncoding,
+    body: String,
+}
+
+#[derive(Serialize)]
+struct JsonHeader<'a> {
+    name: &'a str,
+    value: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonBodyStats {
+    size_compressed: u64,
+    size_decompressed: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BodyEncoding {
+    Text,
+    Base64,
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    d: &Duration,
+    s: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    s.serialize_u64(d.as_millis() as u64)
+}
+
+/// Listen to transaction events, printing a curl-like timeline to stderr if `verbose`.
+/// The task resolves to the number of body bytes received over the wire once the
+/// sender side is dropped.
+pub(crate) fn spawn_event_listener(
+    verbose: bool,
+) -> (mpsc::Sender<HttpResponseEvent>, JoinHandle<u64>) {
+    let (event_tx, mut event_rx) = mpsc::channel(100);
+    let handle = tokio::spawn(async move {
+        let mut received_bytes = 0;
+        while let Some(event) = event_rx.recv().await {
+            if let HttpResponseEvent::ChunkReceived { bytes } = &event {
+                received_bytes += *bytes as u64;
+            }
+            if verbose {
+                if let Some(line) = timeline_line(&event) {
+                    eprintln!("{line}");
+                }
+            }
+        }
+        received_bytes
+    });
+    (event_tx, handle)
+}
+
+/// Format an event as a curl-style timeline line (`*` info, `>` sent, `<` received).
+/// Body chunk events are too noisy for the timeline and are skipped.
+fn timeline_line(event: &HttpResponseEvent) -> Option<String> {
+    match event {
+        HttpResponseEvent::SendUrl { method, scheme, host, port, path, query, .. } => {
+            let query = if query.is_empty() { String::new() } else { format!("?{query}") };
+            Some(format!("* Connecting to {scheme}://{host}:{port}\n> {method} {path}{query}"))
+        }
+        HttpResponseEvent::ReceiveUrl { version, status } => {
+            Some(format!(">\n< {version:?} {status}"))
+        }
+        HttpResponseEvent::DnsResolved {
+            hostname,
+            addresses,
+            duration,
+            overridden: false,
+            source,
+            ..
+        } => Some(format!(
+            "* Resolved {hostname} to {} via {source} ({duration}ms)",
+            addresses.join(", ")
+        )),
+        HttpResponseEvent::ChunkSent { .. } | HttpResponseEvent::ChunkReceived { .. } => None,
+        // The remaining events already print in timeline form
+        event => Some(event.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &[u8]) -> CliResponse {
+        CliResponse {
+            status: 200,
+            status_reason: Some("OK".to_string()),
+            url: "https://example.com/".to_string(),
+            version: Some("HTTP/1.1".to_string()),
+            remote_addr: None,
+            headers: vec![("content-type".to_string(), "text/plain".to_string())],
+            timing: Timing::default(),
+            body_stats: BodyStats::default(),
+            body: body.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_json_body_encoding() {
+        let json = response(b"hello").to_json();
+        assert_eq!(json.body_encoding, BodyEncoding::Text);
+        assert_eq!(json.body, "hello");
+
+        let json = response(&[0xff, 0xfe, 0x00]).to_json();
+        assert_eq!(json.body_encoding, BodyEncoding::Base64);
+        assert_eq!(json.body, "//4A");
+    }
+
+    #[test]
+    fn test_timeline_lines() {
+        let event = HttpResponseEvent::SendUrl {
+            method: "GET".to_string(),
+            scheme: "https".to_string(),
+            username: String::new(),
+            password: String::new(),
+            host: "example.com".to_string(),
+            port: 443,
+            path: "/users".to_string(),
+            query: "page=2".to_string(),
+            fragment: String::new(),
+        };
+        assert_eq!(
+            timeline_line(&event).unwrap(),
+            "* Connecting to https://example.com:443\n> GET /users?page=2"
+        );
+
+        let event = HttpResponseEvent::HeaderUp("accept".to_string(), "*/*".to_string());
+        assert_eq!(timeline_line(&event).unwrap(), "> accept: */*");
+
+        assert!(timeline_line(&HttpResponseEvent::ChunkReceived { bytes: 10 }).is_none());
+    }
+
+    #[test]
+    fn test_timing_waterfall() {
+        let timing = Timing {
+            dns: Duration::from_millis(5),
+            connect: Duration::from_millis(10),
+            tls: Duration::ZERO,
+            ttfb: Duration::from_millis(100),
+            headers: Duration::from_millis(120),
+            total: Duration::from_millis(150),
+        };
+        assert_eq!(
+            timing.waterfall(),
+            vec![
+                "DNS lookup            5ms  (at 5ms)",
+                "TCP connect          10ms  (at 15ms)",
+                "Waiting (TTFB)      100ms  (at 115ms)",
+                "Content download     30ms  (at 150ms)",
+                "Total               150ms",
+            ]
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use serde::{Serialize, Serializer};
+use std::fmt::Write;
+use std::time::Duration;
+use yaak_models::models::HttpRequest;
+
+/// Outcome of a single request in a `run`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RequestResult {
+    pub id: String,
+    pub name: String,
+    pub folder: String,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    #[serde(rename = "elapsedMs", serialize_with = "serialize_millis")]
+    pub elapsed: Duration,
+    /// Failed expectations
+    pub failures: Vec<String>,
+    /// Set when the request could not be rendered or sent
+    pub error: Option<String>,
+}
+
+impl RequestResult {
+    pub fn new(request: &HttpRequest, folder: String) -> Self {
+        Self {
+            id: request.id.clone(),
+            name: request.name.clone(),
+            folder,
+            method: request.method.to_uppercase(),
+            url: request.url.clone(),
+            status: None,
+            elapsed: Duration::ZERO,
+            failures: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.failures.is_empty()
+    }
+
+    /// Request name including its folder path, falling back to the URL for unnamed requests
+    pub fn display_name(&self) -> String {
+        let name = if self.name.is_empty() { self.url.as_str() } else { self.name.as_str() };
+        if self.folder.is_empty() { name.to_string() } else { format!("{}/{}", self.folder, name) }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RunReport {
+    pub name: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    #[serde(rename = "elapsedMs", serialize_with = "serialize_millis")]
+    pub elapsed: Duration,
+    pub requests: Vec<RequestResult>,
+}
+
+impl RunReport {
+    pub fn new(name: String, requests: Vec<RequestResult>, elapsed: Duration) -> Self {
+        let passed = requests.iter().filter(|r| r.passed()).count();
+        let errors = requests.iter().filter(|r| r.error.is_some()).count();
+        Self {
+            name,
+            total: requests.len(),
+            passed,
+            failed: requests.len() - passed,
+            errors,
+            elapsed,
+            requests,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} requests, {} passed, {} failed ({:.2}s)",
+            self.total,
+            self.passed,
+            self.failed,
+            self.elapsed.as_secs_f64()
+        )
+    }
+
+    /// Render the report as JUnit XML, with one test case per request.
+    /// Unsendable requests are reported as errors and failed expectations as failures.
+    pub fn to_junit_xml(&self) -> String {
+        let assertion_failures = self.failed - self.errors;
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"yapicli\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            self.total,
+            assertion_failures,
+            self.errors,
+            self.elapsed.as_secs_f64()
+        );
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            escape_xml(&self.name),
+            self.total,
+            assertion_failures,
+            self.errors,
+            self.elapsed.as_secs_f64()
+        );
+
+        for r in &self.requests {
+            let classname = if r.folder.is_empty() { &self.name } else { &r.folder };
+            let _ = write!(
+                xml,
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\"",
+                escape_xml(classname),
+                escape_xml(&format!("{} {}", r.method, r.display_name())),
+                r.elapsed.as_secs_f64()
+            );
+
+            if r.passed() {
+                xml.push_str("/>\n");
+                continue;
+            }
+
+            xml.push_str(">\n");
+            if let Some(error) = &r.error {
+                let _ = writeln!(
+                    xml,
+                    "      <error message=\"{}\">{}</error>",
+                    escape_xml(error),
+                    escape_xml(&r.url)
+                );
+            } else {
+                let _ = writeln!(
+                    xml,
+                    "      <failure message=\"{}\">{}</failure>",
+                    escape_xml(&r.failures[0]),
+                    escape_xml(&r.failures.join("\n"))
+                );
+            }
+            xml.push_str("    </testcase>\n");
+        }
+
+        xml.push_str("  </testsuite>\n");
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+fn serialize_millis<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_u64(d.as_millis() as u64)
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(name: &str, failures: Vec<&str>, error: Option<&str>) -> RequestResult {
+        RequestResult {
+            id: format!("rq_{name}"),
+            name: name.to_string(),
+            folder: "Users".to_string(),
+            method: "GET".to_string(),
+            url: "https://example.com".to_string(),
+            status: Some(200),
+            elapsed: Duration::from_millis(10),
+            failures: failures.into_iter().map(|f| f.to_string()).collect(),
+            error: error.map(|e| e.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_report_counts() {
+        let report = RunReport::new(
+            "API".to_string(),
+            vec![
+                result("ok", vec![], None),
+                result("bad", vec!["Expected status 200 but got 500"], None),
+                result("broken", vec![], Some("connection refused")),
+            ],
+            Duration::from_secs(1),
+        );
+        assert_eq!(report.total, 3);
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.failed, 2);
+        assert_eq!(report.errors, 1);
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let report = RunReport::new(
+            "My <API>".to_string(),
+            vec![
+                result("ok", vec![], None),
+                result("bad", vec!["Expected header x-a to be \"1\""], None),
+                result("broken", vec![], Some("connection refused")),
+            ],
+            Duration::from_secs(1),
+        );
+        let xml = report.to_junit_xml();
+        assert!(
+            xml.contains(r#"<testsuite name="My &lt;API&gt;" tests="3" failures="1" errors="1""#)
+        );
+        assert!(xml.contains(r#"<testcase classname="Users" name="GET Users/ok" time="0.010"/>"#));
+        assert!(xml.contains(r#"<failure message="Expected header x-a to be &quot;1&quot;">"#));
+        assert!(xml.contains(r#"<error message="connection refused">"#));
+    }
+}
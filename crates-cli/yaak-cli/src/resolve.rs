@@ -0,0 +1,236 @@
+//! Resolve command arguments that may be an ID, a name, or a path like `My API/Users/Get user`.
+//!
+//! IDs always win. Otherwise the argument is split on `/` and matched against the end of
+//! each candidate's full path (workspace, folders, then the model's own name), so any
+//! unambiguous suffix works.
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use std::collections::HashMap;
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{
+    Environment, Folder, GrpcRequest, HttpRequest, WebsocketRequest, Workspace,
+};
+
+/// What a `run` executes: a whole workspace or a single folder
+pub(crate) enum RunTarget {
+    Workspace(Workspace),
+    Folder(Folder),
+}
+
+pub(crate) fn workspace(db: &DbContext, arg: &str) -> Result<Workspace> {
+    if let Ok(w) = db.get_workspace(arg) {
+        return Ok(w);
+    }
+
+    let candidates = db.list_workspaces()?.into_iter().map(|w| (vec![w.name.clone()], w)).collect();
+    find_by_path("workspace", candidates, arg)
+}
+
+pub(crate) fn http_request(db: &DbContext, arg: &str) -> Result<HttpRequest> {
+    if let Ok(r) = db.get_http_request(arg) {
+        return Ok(r);
+    }
+
+    let candidates = request_candidates(
+        db,
+        |w| db.list_http_requests(w),
+        |r| (r.folder_id.as_deref(), r.name.as_str()),
+    )?;
+    find_by_path("request", candidates, arg)
+}
+
+pub(crate) fn grpc_request(db: &DbContext, arg: &str) -> Result<GrpcRequest> {
+    if let Ok(r) = db.get_grpc_request(arg) {
+        return Ok(r);
+    }
+
+    let candidates = request_candidates(
+        db,
+        |w| db.list_grpc_requests(w),
+        |r| (r.folder_id.as_deref(), r.name.as_str()),
+    )?;
+    find_by_path("gRPC request", candidates, arg)
+}
+
+pub(crate) fn websocket_request(db: &DbContext, arg: &str) -> Result<WebsocketRequest> {
+    if let Ok(r) = db.get_websocket_request(arg) {
+        return Ok(r);
+    }
+
+    let candidates = request_candidates(
+        db,
+        |w| db.list_websocket_requests(w),
+        |r| (r.folder_id.as_deref(), r.name.as_str()),
+    )?;
+    find_by_path("WebSocket request", candidates, arg)
+}
+
+pub(crate) fn run_target(db: &DbContext, arg: &str) -> Result<RunTarget> {
+    if let Ok(w) = db.get_workspace(arg) {
+        return Ok(RunTarget::Workspace(w));
+    }
+    if let Ok(f) = db.get_folder(arg) {
+        return Ok(RunTarget::Folder(f));
+    }
+
+    let mut candidates = Vec::new();
+    for w in db.list_workspaces()? {
+        let folders = db.list_folders(&w.id)?;
+        let folder_paths = folder_paths(&w, &folders);
+        for f in folders {
+            let path = folder_paths.get(&f.id).cloned().unwrap_or_default();
+            candidates.push((path, RunTarget::Folder(f)));
+        }
+        candidates.push((vec![w.name.clone()], RunTarget::Workspace(w)));
+    }
+    find_by_path("workspace or folder", candidates, arg)
+}
+
+/// Resolve an environment by ID or name, within the workspace of the request being sent
+pub(crate) fn environment(db: &DbContext, workspace_id: &str, arg: &str) -> Result<Environment> {
+    if let Ok(e) = db.get_environment(arg) {
+        return Ok(e);
+    }
+
+    let candidates = db
+        .list_environments_ensure_base(workspace_id)?
+        .into_iter()
+        .map(|e| (vec![e.name.clone()], e))
+        .collect();
+    find_by_path("environment", candidates, arg)
+}
+
+/// Full path of every folder, keyed by folder ID
+fn folder_paths(workspace: &Workspace, folders: &[Folder]) -> HashMap<String, Vec<String>> {
+    let by_id: HashMap<&str, &Folder> = folders.iter().map(|f| (f.id.as_str(), f)).collect();
+    folders
+        .iter()
+        .map(|f| {
+            let mut names = vec![f.name.clone()];
+            let mut parent_id = f.folder_id.as_deref();
+            // Bounded by the folder count so a corrupt parent cycle can't loop forever
+            while let Some(parent) = parent_id.and_then(|id| by_id.get(id)) {
+                if names.len() > folders.len() {
+                    break;
+                }
+                names.push(parent.name.clone());
+                parent_id = parent.folder_id.as_deref();
+            }
+            names.push(workspace.name.clone());
+            names.reverse();
+            (f.id.clone(), names)
+        })
+        .collect()
+}
+
+/// Every request returned by `list` across all workspaces, paired with its full path.
+/// `folder_and_name` picks the parent folder ID and name out of a request.
+fn request_candidates<T>(
+    db: &DbContext,
+    list: impl Fn(&str) -> yaak_models::error::Result<Vec<T>>,
+    folder_and_name: impl Fn(&T) -> (Option<&str>, &str),
+) -> Result<Vec<(Vec<String>, T)>> {
+    let mut candidates = Vec::new();
+    for w in db.list_workspaces()? {
+        let folder_paths = folder_paths(&w, &db.list_folders(&w.id)?);
+        for r in list(&w.id)? {
+            let (folder_id, name) = folder_and_name(&r);
+            let mut path = parent_path(&w, &folder_paths, folder_id);
+            path.push(name.to_string());
+            candidates.push((path, r));
+        }
+    }
+    Ok(candidates)
+}
+
+fn parent_path(
+    workspace: &Workspace,
+    folder_paths: &HashMap<String, Vec<String>>,
+    folder_id: Option<&str>,
+) -> Vec<String> {
+    folder_id
+        .and_then(|id| folder_paths.get(id))
+        .cloned()
+        .unwrap_or_else(|| vec![workspace.name.clone()])
+}
+
+/// Pick the single candidate whose path ends with the segments of `arg`
+fn find_by_path<T>(kind: &str, candidates: Vec<(Vec<String>, T)>, arg: &str) -> Result<T> {
+    let query: Vec<&str> = arg.split('/').map(str::trim).filter(|s| !s.is_empty()).collect();
+    if query.is_empty() {
+        return Err(GenericError(format!("Invalid {kind} {arg:?}")));
+    }
+
+    let mut matches: Vec<(Vec<String>, T)> = candidates
+        .into_iter()
+        .filter(|(path, _)| {
+            path.len() >= query.len()
+                && path[path.len() - query.len()..].iter().zip(&query).all(|(a, b)| a == b)
+        })
+        .collect();
+
+    match matches.len() {
+        0 => Err(GenericError(format!("No {kind} found matching {arg:?}"))),
+        1 => Ok(matches.remove(0).1),
+        _ => {
+            let paths: Vec<String> =
+                matches.iter().map(|(path, _)| format!("  {}", path.join("/"))).collect();
+            Err(GenericError(format!(
+                "{arg:?} matches more than one {kind}. Use a longer path or an ID:\n{}",
+                paths.join("\n")
+            )))
+        }
+    }
+}
+
+/// Parse a `--var name=value` argument
+pub(crate) fn parse_var(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected NAME=VALUE but got {s:?}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates() -> Vec<(Vec<String>, &'static str)> {
+        vec![
+            (vec!["My API".into(), "Users".into(), "Get user".into()], "rq_1"),
+            (vec!["My API".into(), "Admin".into(), "Get user".into()], "rq_2"),
+            (vec!["My API".into(), "Users".into(), "List users".into()], "rq_3"),
+        ]
+    }
+
+    #[test]
+    fn test_find_by_path() {
+        assert_eq!(find_by_path("request", candidates(), "List users").unwrap(), "rq_3");
+        assert_eq!(find_by_path("request", candidates(), "Users/Get user").unwrap(), "rq_1");
+        assert_eq!(find_by_path("request", candidates(), "My API/Admin/Get user").unwrap(), "rq_2");
+        assert_eq!(find_by_path("request", candidates(), "/Admin/Get user/").unwrap(), "rq_2");
+    }
+
+    #[test]
+    fn test_find_by_path_errors() {
+        let err = find_by_path("request", candidates(), "Get user").unwrap_err().to_string();
+        assert!(err.contains("matches more than one request"));
+        assert!(err.contains("My API/Users/Get user"));
+        assert!(err.contains("My API/Admin/Get user"));
+
+        let err = find_by_path("request", candidates(), "ser/Get user").unwrap_err().to_string();
+        assert!(err.contains("No request found"));
+    }
+
+    #[test]
+    fn test_parse_var() {
+        assert_eq!(parse_var("host=localhost").unwrap(), ("host".into(), "localhost".into()));
+        assert_eq!(parse_var("q=a=b").unwrap(), ("q".into(), "a=b".into()));
+        assert_eq!(parse_var("empty=").unwrap(), ("empty".into(), "".into()));
+        assert!(parse_var("novalue").is_err());
+        assert!(parse_var("=value").is_err());
+    }
+}
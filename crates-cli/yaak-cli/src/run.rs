@@ -0,0 +1,178 @@
+use crate::context::CliContext;
+use crate::error::Result;
+use crate::expect::{Expectations, StatusExpectation};
+use crate::report::{RequestResult, RunReport};
+use crate::resolve::{self, RunTarget};
+use crate::send::{read_response_body, send_http_request};
+use std::path::PathBuf;
+use std::time::Instant;
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{Folder, HttpRequest};
+
+pub(crate) struct RunOptions {
+    pub expectations: Option<PathBuf>,
+    pub expect_status: Option<String>,
+    pub junit: Option<PathBuf>,
+    pub report: Option<PathBuf>,
+    pub bail: bool,
+}
+
+/// Send every request in a workspace or folder, in sidebar order, and check the responses.
+/// Returns true if every request passed.
+pub(crate) async fn run(ctx: &CliContext, target: &str, opts: RunOptions) -> Result<bool> {
+    let target = resolve::run_target(&ctx.db(), target)?;
+    let (suite_name, requests) = collect_requests(&ctx.db(), target)?;
+    if requests.is_empty() {
+        println!("No requests found in {suite_name}");
+        return Ok(true);
+    }
+
+    let expectations = match &opts.expectations {
+        Some(path) => Expectations::load(path)?,
+        None => Expectations::default(),
+    };
+
+    let start = Instant::now();
+    let mut results = Vec::new();
+    for (folder_path, request) in requests {
+        let result = run_request(ctx, &request, folder_path, &expectations, &opts).await;
+        print_result(&result);
+        let failed = !result.passed();
+        results.push(result);
+        if failed && opts.bail {
+            break;
+        }
+    }
+
+    let report = RunReport::new(suite_name, results, start.elapsed());
+    println!();
+    println!("{}", report.summary());
+
+    if let Some(path) = &opts.junit {
+        std::fs::write(path, report.to_junit_xml())?;
+    }
+
+    if let Some(path) = &opts.report {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+    }
+
+    Ok(report.failed == 0)
+}
+
+async fn run_request(
+    ctx: &CliContext,
+    request: &HttpRequest,
+    folder_path: String,
+    expectations: &Expectations,
+    opts: &RunOptions,
+) -> RequestResult {
+    let mut expectation = expectations.for_request(request);
+    if expectation.status.is_none() {
+        expectation.status = opts.expect_status.clone().map(StatusExpectation::Pattern);
+    }
+
+    let mut result = RequestResult::new(request, folder_path);
+    let start = Instant::now();
+
+    let response = match send_http_request(ctx, request, None).await {
+        Ok(r) => r,
+        Err(e) => {
+            result.elapsed = start.elapsed();
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+
+    result.elapsed = start.elapsed();
+    let status = response.status as u16;
+    let headers: Vec<(String, String)> =
+        response.headers.iter().map(|h| (h.name.clone(), h.value.clone())).collect();
+    result.status = Some(status);
+    let body = match read_response_body(&response) {
+        Ok(body) => body,
+        Err(e) => {
+            result.error = Some(format!("Failed to read response body: {e}"));
+            return result;
+        }
+    };
+    result.failures = expectation.check(status, &headers, &body);
+
+    result
+}
+
+fn print_result(result: &RequestResult) {
+    let label = if result.passed() { "PASS" } else { "FAIL" };
+    let status = result.status.map(|s| s.to_string()).unwrap_or_else(|| "---".to_string());
+    println!(
+        "{label} {} {} ({status}, {}ms)",
+        result.method,
+        result.display_name(),
+        result.elapsed.as_millis()
+    );
+    if let Some(error) = &result.error {
+        println!("     {error}");
+    }
+    for failure in &result.failures {
+        println!("     {failure}");
+    }
+}
+
+/// Collect the requests under a workspace or folder, depth-first in sidebar
+/// (`sort_priority`) order. Each request is paired with its folder path.
+fn collect_requests(
+    db: &DbContext,
+    target: RunTarget,
+) -> Result<(String, Vec<(String, HttpRequest)>)> {
+    let (suite_name, workspace_id, root_folder) = match target {
+        RunTarget::Workspace(w) => (w.name, w.id, None),
+        RunTarget::Folder(f) => (f.name.clone(), f.workspace_id.clone(), Some(f)),
+    };
+
+    let folders = db.list_folders(&workspace_id)?;
+    let http_requests = db.list_http_requests(&workspace_id)?;
+
+    let mut requests = Vec::new();
+    match root_folder {
+        None => walk_tree(&folders, &http_requests, None, "", &mut requests),
+        Some(f) => walk_tree(&folders, &http_requests, Some(&f.id), &f.name, &mut requests),
+    }
+
+    Ok((suite_name, requests))
+}
+
+enum TreeNode<'a> {
+    Folder(&'a Folder),
+    Request(&'a HttpRequest),
+}
+
+fn walk_tree(
+    folders: &[Folder],
+    http_requests: &[HttpRequest],
+    parent_id: Option<&str>,
+    path: &str,
+    out: &mut Vec<(String, HttpRequest)>,
+) {
+    let mut children: Vec<(f64, TreeNode)> = folders
+        .iter()
+        .filter(|f| f.folder_id.as_deref() == parent_id)
+        .map(|f| (f.sort_priority, TreeNode::Folder(f)))
+        .chain(
+            http_requests
+                .iter()
+                .filter(|r| r.folder_id.as_deref() == parent_id)
+                .map(|r| (r.sort_priority, TreeNode::Request(r))),
+        )
+        .collect();
+    children.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    for (_, child) in children {
+        match child {
+            TreeNode::Folder(f) => {
+                let folder_path =
+                    if path.is_empty() { f.name.clone() } else { format!("{path}/{}", f.name) };
+                walk_tree(folders, http_requests, Some(&f.id), &folder_path, out);
+            }
+            TreeNode::Request(r) => out.push((path.to_string(), r.clone())),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::context::CliContext;
+use crate::error::Result;
+use tokio::sync::mpsc;
+use yaak_http::cookies::CookieStore;
+use yaak_models::models::{CookieJar, HttpRequest, HttpResponse};
+use yaak_models::util::UpdateSource;
+use yaak_send::send::HttpSendContext;
+
+/// Send a stored HTTP request through the same pipeline as the app. The response body is
+/// written to the file at `body_path` of the returned response.
+///
+/// Events are forwarded to `event_tx` if given. With `--persist`, the response and its
+/// events are saved to the database.
+pub(crate) async fn send_http_request(
+    ctx: &CliContext,
+    request: &HttpRequest,
+    event_tx: Option<mpsc::Sender<yaak_http::sender::HttpResponseEvent>>,
+) -> Result<HttpResponse> {
+    let update_source = UpdateSource::Background;
+    let response = HttpResponse {
+        request_id: request.id.clone(),
+        workspace_id: request.workspace_id.clone(),
+        ..Default::default()
+    };
+    let response = if ctx.persist && !request.id.is_empty() {
+        ctx.db().upsert_http_response(&response, &update_source, &ctx.blob_manager)?
+    } else {
+        response
+    };
+
+    let environment = ctx.environment(&request.workspace_id)?;
+    let cookie_jar = get_cookie_jar(ctx, &request.workspace_id)?;
+    let plugin_context = ctx.plugin_context(&request.workspace_id);
+    let send_ctx = HttpSendContext {
+        app: ctx,
+        query_manager: &ctx.query_manager,
+        blob_manager: &ctx.blob_manager,
+        plugin_manager: ctx.plugin_manager.clone(),
+        encryption_manager: ctx.encryption_manager.clone(),
+        connection_manager: &ctx.connection_manager,
+        plugin_context: &plugin_context,
+        update_source,
+        event_tx,
+        variable_overrides: ctx.variable_overrides(),
+        cookie_store: ctx.cookie_store.clone(),
+    };
+
+    // The sender is held until the request is done, so the request is never canceled
+    let (_cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+    let response = yaak_send::send::send_http_request(
+        &send_ctx,
+        request,
+        &response,
+        environment,
+        cookie_jar,
+        &cancel_rx,
+    )
+    .await
+    .map_err(|e| e.error)?;
+
+    Ok(response)
+}
+
+/// The cookie jar selected with `--cookie-jar-id`, or the workspace's first one
+pub(crate) fn get_cookie_jar(ctx: &CliContext, workspace_id: &str) -> Result<Option<CookieJar>> {
+    if ctx.no_cookies {
+        return Ok(None);
+    }
+
+    let db = ctx.db();
+    let cookie_jar = match &ctx.cookie_jar_id {
+        Some(id) => db.get_cookie_jar(id)?,
+        None => db.list_cookie_jars(workspace_id)?.remove(0),
+    };
+
+    Ok(Some(cookie_jar))
+}
+
+/// The cookies of the `--cookie-jar` file, or of the selected cookie jar
+pub(crate) fn get_cookie_store(
+    ctx: &CliContext,
+    workspace_id: &str,
+) -> Result<Option<CookieStore>> {
+    if let Some(cookie_store) = &ctx.cookie_store {
+        return Ok(Some(cookie_store.clone()));
+    }
+    let cookie_jar = get_cookie_jar(ctx, workspace_id)?;
+    Ok(cookie_jar.map(|j| CookieStore::from_cookies(j.cookies)))
+}
+
+/// Read the body a send wrote to disk. Bodies of responses that aren't persisted are
+/// removed afterwards, since nothing else will read them.
+pub(crate) fn read_response_body(response: &HttpResponse) -> Result<Vec<u8>> {
+    let Some(body_path) = &response.body_path else {
+        return Ok(Vec::new());
+    };
+
+    let body = std::fs::read(body_path)?;
+    if response.id.is_empty() {
+        let _ = std::fs::remove_file(body_path);
+    }
+    Ok(body)
+}
@@ -0,0 +1,143 @@
+//! Run commands against a yaak-sync directory (the YAML files the app writes for Git) instead
+//! of the app's database. The directory is loaded into a throwaway database in a temp dir,
+//! so nothing is ever written back to the directory.
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use yaak_models::blob_manager::BlobManager;
+use yaak_models::query_manager::QueryManager;
+use yaak_models::util::{ModelPayload, UpdateSource, generate_id};
+use yaak_sync::models::SyncModel;
+use yaak_sync::sync::get_fs_candidates;
+
+/// A temporary database holding the models of a sync directory
+pub(crate) struct SyncDb {
+    pub query_manager: QueryManager,
+    pub blob_manager: BlobManager,
+    db_dir: PathBuf,
+    /// Kept alive so model changes have somewhere to go
+    _events_rx: mpsc::Receiver<ModelPayload>,
+}
+
+impl SyncDb {
+    /// Remove the temporary database. Must be called explicitly, because the CLI exits with
+    /// `std::process::exit`, which skips destructors.
+    pub fn cleanup(&self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.db_dir) {
+            log::warn!("Failed to remove temporary database {:?}: {e}", self.db_dir);
+        }
+    }
+}
+
+/// Load every workspace, environment, folder, and request in `sync_dir` into a new database
+pub(crate) fn load(sync_dir: &Path) -> Result<SyncDb> {
+    // get_fs_candidates creates missing directories, which would hide a typo in the path
+    if !sync_dir.is_dir() {
+        return Err(GenericError(format!("Sync directory {sync_dir:?} does not exist")));
+    }
+
+    let db_dir = std::env::temp_dir().join(format!("yapicli-{}", generate_id()));
+    let (query_manager, blob_manager, events_rx) =
+        yaak_models::init_standalone(db_dir.join("db.sqlite"), db_dir.join("blobs.sqlite"))?;
+    let sync_db = SyncDb { query_manager, blob_manager, db_dir, _events_rx: events_rx };
+
+    if let Err(e) = import_models(&sync_db.query_manager, sync_dir) {
+        sync_db.cleanup();
+        return Err(e);
+    }
+
+    Ok(sync_db)
+}
+
+fn import_models(query_manager: &QueryManager, sync_dir: &Path) -> Result<()> {
+    let mut workspaces = Vec::new();
+    let mut environments = Vec::new();
+    let mut folders = Vec::new();
+    let mut http_requests = Vec::new();
+    let mut grpc_requests = Vec::new();
+    let mut websocket_requests = Vec::new();
+
+    for candidate in get_fs_candidates(sync_dir)? {
+        match candidate.model {
+            SyncModel::Workspace(m) => workspaces.push(m),
+            SyncModel::Environment(m) => environments.push(m),
+            SyncModel::Folder(m) => folders.push(m),
+            SyncModel::HttpRequest(m) => http_requests.push(m),
+            SyncModel::GrpcRequest(m) => grpc_requests.push(m),
+            SyncModel::WebsocketRequest(m) => websocket_requests.push(m),
+        }
+    }
+
+    if workspaces.is_empty() {
+        return Err(GenericError(format!("No workspace found in sync directory {sync_dir:?}")));
+    }
+
+    query_manager.connect().batch_upsert(
+        workspaces,
+        environments,
+        folders,
+        http_requests,
+        grpc_requests,
+        websocket_requests,
+        &UpdateSource::Sync,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaak_models::models::{HttpRequest, Workspace};
+
+    fn write_model(dir: &Path, model: SyncModel) {
+        let rel_path = PathBuf::from(format!("yaak.{}.yaml", model.id()));
+        let (content, _) = model.to_file_contents(&rel_path).unwrap();
+        std::fs::write(dir.join(rel_path), content).unwrap();
+    }
+
+    #[test]
+    fn test_load() {
+        let sync_dir = std::env::temp_dir().join(format!("yapicli-test-{}", generate_id()));
+        std::fs::create_dir_all(&sync_dir).unwrap();
+        write_model(
+            &sync_dir,
+            SyncModel::Workspace(Workspace {
+                model: "workspace".into(),
+                id: "wk_1".into(),
+                name: "My API".into(),
+                ..Default::default()
+            }),
+        );
+        write_model(
+            &sync_dir,
+            SyncModel::HttpRequest(HttpRequest {
+                model: "http_request".into(),
+                id: "rq_1".into(),
+                workspace_id: "wk_1".into(),
+                name: "Get user".into(),
+                url: "https://example.com/users/1".into(),
+                ..Default::default()
+            }),
+        );
+
+        let sync_db = load(&sync_dir).unwrap();
+        let db = sync_db.query_manager.connect();
+        assert_eq!(db.get_workspace("wk_1").unwrap().name, "My API");
+        assert_eq!(db.list_http_requests("wk_1").unwrap()[0].url, "https://example.com/users/1");
+        drop(db);
+
+        sync_db.cleanup();
+        std::fs::remove_dir_all(&sync_dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_missing_dir() {
+        let sync_dir = std::env::temp_dir().join(format!("yapicli-test-{}", generate_id()));
+        let err = load(&sync_dir).err().unwrap().to_string();
+        assert!(err.contains("does not exist"));
+        assert!(!sync_dir.exists());
+    }
+}
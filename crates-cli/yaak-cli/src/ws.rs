@@ -0,0 +1,181 @@
+use crate::auth::authenticate;
+use crate::context::CliContext;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::resolve;
+use crate::send::get_cookie_store;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+use yaak_http::path_placeholders::apply_path_placeholders;
+use yaak_models::render::make_vars_hashmap;
+use yaak_templates::{RenderErrorBehavior, RenderOptions, parse_and_render};
+use yaak_tls::render::render_client_certificate;
+use yaak_tls::{TlsSettings, find_client_certificate};
+use yaak_ws::{HeaderMap, HeaderValue, WebsocketManager, render_websocket_request};
+
+/// Connect a saved WebSocket request, send its message (if any) and then each line of stdin
+/// as a text frame, and print every frame received. Binary frames are printed as base64.
+///
+/// Once stdin is closed, frames are still printed for `wait`, unless the server closes the
+/// connection first.
+pub(crate) async fn run(
+    ctx: &CliContext,
+    request: &str,
+    wait: Duration,
+    verbose: bool,
+) -> Result<()> {
+    let opt = RenderOptions { error_behavior: RenderErrorBehavior::Throw };
+    let db = ctx.db();
+    let unrendered = resolve::websocket_request(&db, request)?;
+    let workspace = db.get_workspace(&unrendered.workspace_id)?;
+
+    let mut request = unrendered.clone();
+    let (authentication_type, authentication, auth_context_id) =
+        db.resolve_auth_for_websocket_request(&unrendered)?;
+    request.authentication_type = authentication_type;
+    request.authentication = authentication;
+    request.headers = db.resolve_headers_for_websocket_request(&unrendered)?;
+
+    let environment_chain =
+        ctx.environment_chain(&unrendered.workspace_id, unrendered.folder_id.as_deref())?;
+    let cb = ctx.template_callback(&request.workspace_id);
+    let request = render_websocket_request(&request, environment_chain.clone(), &cb, &opt).await?;
+
+    let (mut url, url_parameters) = apply_path_placeholders(&request.url, &request.url_parameters);
+    if !url.starts_with("ws://") && !url.starts_with("wss://") {
+        url.insert_str(0, "ws://");
+    }
+    let mut url =
+        Url::parse(&url).map_err(|e| GenericError(format!("Failed to parse URL {url}: {e}")))?;
+
+    let headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .filter(|h| h.enabled && !(h.name.is_empty() && h.value.is_empty()))
+        .map(|h| (h.name.clone(), h.value.clone()))
+        .collect();
+    let auth = authenticate(
+        ctx,
+        &request.workspace_id,
+        request.authentication_type.as_deref(),
+        &request.authentication,
+        &auth_context_id,
+        &request.url,
+        &headers,
+    )
+    .await?;
+
+    let mut header_map = HeaderMap::new();
+    for (name, value) in headers.into_iter().chain(auth.headers) {
+        let name = http::HeaderName::from_str(&name)
+            .map_err(|e| GenericError(format!("Invalid header name {name:?}: {e}")))?;
+        let value = HeaderValue::from_str(&value)
+            .map_err(|e| GenericError(format!("Invalid value for header {name}: {e}")))?;
+        header_map.insert(name, value);
+    }
+
+    let query_parameters: Vec<(String, String)> = url_parameters
+        .into_iter()
+        .filter(|p| p.enabled && !p.name.is_empty())
+        .map(|p| (p.name, p.value))
+        .chain(auth.query_parameters)
+        .collect();
+    // Only touch the query if there is something to add, or it will append an empty `?`
+    if !query_parameters.is_empty() {
+        url.query_pairs_mut().extend_pairs(query_parameters);
+    }
+
+    // WebSocket upgrades are HTTP requests, so cookies are matched against the HTTP URL
+    if let Some(store) = get_cookie_store(ctx, &request.workspace_id)? {
+        let mut http_url = url.clone();
+        let _ = http_url.set_scheme(if url.scheme() == "wss" { "https" } else { "http" });
+        if let Some(cookie) = store.get_cookie_header(&http_url) {
+            let value = HeaderValue::from_str(&cookie)
+                .map_err(|e| GenericError(format!("Invalid cookie header: {e}")))?;
+            header_map.insert(http::header::COOKIE, value);
+        }
+    }
+
+    let certs =
+        db.resolve_client_certificates(&unrendered.workspace_id, unrendered.folder_id.as_deref())?;
+    let client_cert = match find_client_certificate(url.as_str(), &certs) {
+        Some(c) => Some(render_client_certificate(c, environment_chain.clone(), &cb, &opt).await?),
+        None => None,
+    };
+    let network =
+        db.resolve_network_settings(&unrendered.workspace_id, unrendered.folder_id.as_deref())?;
+
+    let connection_id = request.id.clone();
+    let (receive_tx, mut receive_rx) = mpsc::channel::<Message>(128);
+    let mut ws_manager = WebsocketManager::new();
+    let response = ws_manager
+        .connect(
+            &connection_id,
+            url.as_str(),
+            header_map,
+            receive_tx,
+            TlsSettings::for_workspace(&workspace),
+            client_cert,
+            db.get_settings().proxy.into(),
+            network,
+        )
+        .await?;
+    if verbose {
+        eprintln!("* Connected to {url} ({})", response.status());
+    }
+
+    let mut printer = tokio::spawn(async move {
+        while let Some(message) = receive_rx.recv().await {
+            match message {
+                Message::Text(text) => println!("{}", text.as_str()),
+                Message::Binary(data) => println!("{}", BASE64.encode(data)),
+                Message::Close(frame) => {
+                    if verbose {
+                        eprintln!("* Connection closed by server {frame:?}");
+                    }
+                    break;
+                }
+                _ => {}
+            }
+        }
+    });
+
+    if !request.message.is_empty() {
+        ws_manager.send(&connection_id, Message::Text(request.message.clone().into())).await?;
+    }
+
+    // Lines from stdin are rendered like messages typed in the app
+    let vars = make_vars_hashmap(environment_chain);
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let message = parse_and_render(&line, &vars, &cb, &opt).await?;
+                ws_manager.send(&connection_id, Message::Text(message.into())).await?;
+            }
+            _ = &mut printer => {
+                return Ok(());
+            }
+        }
+    }
+
+    let closed_by_server = tokio::time::timeout(wait, &mut printer).await.is_ok();
+    if !closed_by_server {
+        ws_manager.close(&connection_id).await?;
+        let _ = printer.await;
+    }
+
+    Ok(())
+}
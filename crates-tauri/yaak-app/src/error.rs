@@ -31,6 +31,9 @@ pub enum Error {
     #[error(transparent)]
     PluginError(#[from] yaak_plugins::error::Error),
 
+    #[error(transparent)]
+    SendError(#[from] yaak_send::error::Error),
+
     #[error(transparent)]
     TauriUtilsError(#[from] yaak_tauri_utils::error::Error),
 
@@ -6,7 +6,7 @@ use crate::grpc::{build_metadata, metadata_to_map, resolve_grpc_request};
 use crate::http_request::{resolve_http_request, send_http_request};
 use crate::import::import_data;
 use crate::models_ext::{BlobManagerExt, QueryManagerExt};
-use crate::render::{render_grpc_request, render_json_value, render_template};
+use crate::render::{render_json_value, render_template};
 use crate::uri_scheme::handle_deep_link;
 use error::Result as YaakResult;
 use eventsource_client::{EventParser, SSE};
@@ -29,7 +29,7 @@ use tokio::sync::Mutex;
 use tokio::task::block_in_place;
 use yaak_crypto::manager::EncryptionManager;
 use yaak_grpc::manager::{GrpcConfig, GrpcHandle};
-use yaak_grpc::{Code, ServiceDefinition, serialize_message};
+use yaak_grpc::{Code, ServiceDefinition, render_grpc_request, serialize_message};
 use yaak_mac_window::AppHandleMacWindowExt;
 use yaak_models::models::{
     AnyModel, CookieJar, Environment, GrpcConnection, GrpcConnectionState, GrpcEvent,
@@ -55,7 +55,8 @@ use yaak_sse::sse::ServerSentEvent;
 use yaak_tauri_utils::window::WorkspaceWindowTrait;
 use yaak_templates::format_json::format_json;
 use yaak_templates::{RenderErrorBehavior, RenderOptions, Tokens, transform_args};
-use yaak_tls::find_client_certificate;
+use yaak_tls::render::render_client_certificate;
+use yaak_tls::{TlsSettings, find_client_certificate};
 
 mod commands;
 mod encoding;
@@ -189,24 +190,33 @@ async fn cmd_grpc_reflect<R: Runtime>(
 
     let plugin_manager = Arc::new((*app_handle.state::<PluginManager>()).clone());
     let encryption_manager = Arc::new((*app_handle.state::<EncryptionManager>()).clone());
-    let req = render_grpc_request(
-        &resolved_request,
-        environment_chain,
-        &PluginTemplateCallback::new(
-            plugin_manager,
-            encryption_manager,
-            &PluginContext::new(Some(window.label().to_string()), window.workspace_id()),
-            RenderPurpose::Send,
-        ),
-        &RenderOptions { error_behavior: RenderErrorBehavior::Throw },
-    )
-    .await?;
+    let cb = PluginTemplateCallback::new(
+        plugin_manager,
+        encryption_manager,
+        &PluginContext::new(Some(window.label().to_string()), window.workspace_id()),
+        RenderPurpose::Send,
+    );
+    let render_options = RenderOptions { error_behavior: RenderErrorBehavior::Throw };
+    let req =
+        render_grpc_request(&resolved_request, environment_chain.clone(), &cb, &render_options)
+            .await?;
 
     let uri = safe_uri(&req.url);
     let metadata = build_metadata(&window, &req, &auth_context_id).await?;
-    let settings = window.db().get_settings();
-    let client_certificate =
-        find_client_certificate(req.url.as_str(), &settings.client_certificates);
+    let certificates = window.db().resolve_client_certificates(
+        &unrendered_request.workspace_id,
+        unrendered_request.folder_id.as_deref(),
+    )?;
+    let network = window.db().resolve_network_settings(
+        &unrendered_request.workspace_id,
+        unrendered_request.folder_id.as_deref(),
+    )?;
+    let client_certificate = match find_client_certificate(req.url.as_str(), &certificates) {
+        Some(c) => {
+            Some(render_client_certificate(c, environment_chain, &cb, &render_options).await?)
+        }
+        None => None,
+    };
     let proto_files: Vec<PathBuf> =
         proto_files.iter().map(|p| PathBuf::from_str(p).unwrap()).collect();
 
@@ -220,8 +230,10 @@ async fn cmd_grpc_reflect<R: Runtime>(
             &uri,
             &proto_files,
             &metadata,
-            workspace.setting_validate_certificates,
+            TlsSettings::for_workspace(&workspace),
             client_certificate,
+            app_handle.db().get_settings().proxy.into(),
+            network,
         )
         .await
         .map_err(|e| GenericError(e.to_string()))?)
@@ -263,8 +275,31 @@ async fn cmd_grpc_go<R: Runtime>(
     let metadata = build_metadata(&window, &request, &auth_context_id).await?;
 
     // Find matching client certificate for this URL
-    let settings = app_handle.db().get_settings();
-    let client_cert = find_client_certificate(&request.url, &settings.client_certificates);
+    let certificates = app_handle.db().resolve_client_certificates(
+        &unrendered_request.workspace_id,
+        unrendered_request.folder_id.as_deref(),
+    )?;
+    let network = app_handle.db().resolve_network_settings(
+        &unrendered_request.workspace_id,
+        unrendered_request.folder_id.as_deref(),
+    )?;
+    let client_cert = match find_client_certificate(&request.url, &certificates) {
+        Some(c) => Some(
+            render_client_certificate(
+                c,
+                environment_chain.clone(),
+                &PluginTemplateCallback::new(
+                    plugin_manager.clone(),
+                    encryption_manager.clone(),
+                    &PluginContext::new(Some(window.label().to_string()), window.workspace_id()),
+                    RenderPurpose::Send,
+                ),
+                &RenderOptions { error_behavior: RenderErrorBehavior::Throw },
+            )
+            .await?,
+        ),
+        None => None,
+    };
 
     let conn = app_handle.db().upsert_grpc_connection(
         &GrpcConnection {
@@ -313,8 +348,10 @@ async fn cmd_grpc_go<R: Runtime>(
             uri.as_str(),
             &proto_files.iter().map(|p| PathBuf::from_str(p).unwrap()).collect(),
             &metadata,
-            workspace.setting_validate_certificates,
+            TlsSettings::for_workspace(&workspace),
             client_cert.clone(),
+            app_handle.db().get_settings().proxy.into(),
+            network,
         )
         .await;
 
@@ -1768,7 +1805,10 @@ pub fn run() {
 }
 
 fn safe_uri(endpoint: &str) -> String {
-    if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+    if endpoint.starts_with("http://")
+        || endpoint.starts_with("https://")
+        || endpoint.starts_with("http+unix://")
+    {
         endpoint.into()
     } else {
         format!("http://{}", endpoint)
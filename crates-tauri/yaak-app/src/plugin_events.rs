@@ -2,27 +2,30 @@ use crate::error::Result;
 use crate::http_request::send_http_request_with_context;
 use crate::models_ext::BlobManagerExt;
 use crate::models_ext::QueryManagerExt;
-use crate::render::{render_grpc_request, render_http_request, render_json_value};
+use crate::render::render_json_value;
 use crate::window::{CreateWindowConfig, create_window};
 use crate::{
     call_frontend, cookie_jar_from_window, environment_from_window, get_window_from_plugin_context,
     workspace_from_window,
 };
 use chrono::Utc;
-use cookie::Cookie;
 use log::error;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_opener::OpenerExt;
 use yaak_crypto::manager::EncryptionManager;
+use yaak_grpc::render_grpc_request;
+use yaak_http::cookie_file::{parse_cookie_file, write_cookie_file};
+use yaak_http::cookies::CookieStore;
 use yaak_models::models::{AnyModel, HttpResponse, Plugin};
 use yaak_models::queries::any_request::AnyRequest;
 use yaak_models::util::UpdateSource;
 use yaak_plugins::error::Error::PluginErr;
 use yaak_plugins::events::{
-    Color, DeleteKeyValueResponse, EmptyPayload, ErrorResponse, FindHttpResponsesResponse,
-    GetCookieValueResponse, GetHttpRequestByIdResponse, GetKeyValueResponse, Icon, InternalEvent,
+    Color, CookieFileFormat, DeleteKeyValueResponse, EmptyPayload, ErrorResponse,
+    ExportCookiesResponse, FindHttpResponsesResponse, GetCookieValueResponse,
+    GetHttpRequestByIdResponse, GetKeyValueResponse, Icon, ImportCookiesResponse, InternalEvent,
     InternalEventPayload, ListCookieNamesResponse, ListHttpRequestsResponse,
     ListWorkspacesResponse, RenderGrpcRequestResponse, RenderHttpRequestResponse,
     SendHttpRequestResponse, SetKeyValueResponse, ShowToastRequest, TemplateRenderResponse,
@@ -31,6 +34,7 @@ use yaak_plugins::events::{
 use yaak_plugins::manager::PluginManager;
 use yaak_plugins::plugin_handle::PluginHandle;
 use yaak_plugins::template_callback::PluginTemplateCallback;
+use yaak_send::render::render_http_request;
 use yaak_tauri_utils::window::WorkspaceWindowTrait;
 use yaak_templates::{RenderErrorBehavior, RenderOptions};
 
@@ -424,11 +428,7 @@ pub(crate) async fn handle_plugin_event<R: Runtime>(
             let window = get_window_from_plugin_context(app_handle, &plugin_context)?;
             let names = match cookie_jar_from_window(&window) {
                 None => Vec::new(),
-                Some(j) => j
-                    .cookies
-                    .into_iter()
-                    .filter_map(|c| Cookie::parse(c.raw_cookie).ok().map(|c| c.name().to_string()))
-                    .collect(),
+                Some(j) => j.cookies.into_iter().map(|c| c.name).collect(),
             };
             Ok(Some(InternalEventPayload::ListCookieNamesResponse(ListCookieNamesResponse {
                 names,
@@ -438,15 +438,40 @@ pub(crate) async fn handle_plugin_event<R: Runtime>(
             let window = get_window_from_plugin_context(app_handle, &plugin_context)?;
             let value = match cookie_jar_from_window(&window) {
                 None => None,
-                Some(j) => j.cookies.into_iter().find_map(|c| match Cookie::parse(c.raw_cookie) {
-                    Ok(c) if c.name().to_string().eq(&req.name) => {
-                        Some(c.value_trimmed().to_string())
+                Some(j) => j.cookies.into_iter().find(|c| c.name == req.name).map(|c| {
+                    // Quotes around the value aren't part of it
+                    match c.value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+                        Some(v) => v.to_string(),
+                        None => c.value,
                     }
-                    _ => None,
                 }),
             };
             Ok(Some(InternalEventPayload::GetCookieValueResponse(GetCookieValueResponse { value })))
         }
+        InternalEventPayload::ImportCookiesRequest(req) => {
+            let window = get_window_from_plugin_context(app_handle, &plugin_context)?;
+            let mut cookie_jar = cookie_jar_from_window(&window)
+                .ok_or(PluginErr("Failed to get cookie jar from window".into()))?;
+            let cookies = parse_cookie_file(&req.contents)?;
+            let count = cookies.len() as i32;
+            let store = CookieStore::from_cookies(cookie_jar.cookies);
+            store.add_cookies(cookies);
+            cookie_jar.cookies = store.get_all_cookies();
+            window.db().upsert_cookie_jar(&cookie_jar, &UpdateSource::Plugin)?;
+            Ok(Some(InternalEventPayload::ImportCookiesResponse(ImportCookiesResponse { count })))
+        }
+        InternalEventPayload::ExportCookiesRequest(req) => {
+            let window = get_window_from_plugin_context(app_handle, &plugin_context)?;
+            let cookies = cookie_jar_from_window(&window).map(|j| j.cookies).unwrap_or_default();
+            let format = match req.format {
+                CookieFileFormat::Netscape => yaak_http::cookie_file::CookieFileFormat::Netscape,
+                CookieFileFormat::Json => yaak_http::cookie_file::CookieFileFormat::Json,
+            };
+            let contents = write_cookie_file(&cookies, format)?;
+            Ok(Some(InternalEventPayload::ExportCookiesResponse(ExportCookiesResponse {
+                contents,
+            })))
+        }
         InternalEventPayload::WindowInfoRequest(req) => {
             let w = app_handle
                 .get_webview_window(&req.label)
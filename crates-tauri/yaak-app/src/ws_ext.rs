@@ -25,7 +25,8 @@ use yaak_plugins::events::{CallHttpAuthenticationRequest, HttpHeader, RenderPurp
 use yaak_plugins::manager::PluginManager;
 use yaak_plugins::template_callback::PluginTemplateCallback;
 use yaak_templates::{RenderErrorBehavior, RenderOptions};
-use yaak_tls::find_client_certificate;
+use yaak_tls::render::render_client_certificate;
+use yaak_tls::{TlsSettings, find_client_certificate};
 use yaak_ws::{WebsocketManager, render_websocket_request};
 
 #[command]
@@ -202,14 +203,21 @@ pub async fn cmd_ws_connect<R: Runtime>(
         environment_id,
     )?;
     let workspace = app_handle.db().get_workspace(&unrendered_request.workspace_id)?;
-    let settings = app_handle.db().get_settings();
+    let certificates = app_handle.db().resolve_client_certificates(
+        &unrendered_request.workspace_id,
+        unrendered_request.folder_id.as_deref(),
+    )?;
+    let network = app_handle.db().resolve_network_settings(
+        &unrendered_request.workspace_id,
+        unrendered_request.folder_id.as_deref(),
+    )?;
     let (resolved_request, auth_context_id) =
         resolve_websocket_request(&window, &unrendered_request)?;
     let plugin_manager = Arc::new((*app_handle.state::<PluginManager>()).clone());
     let encryption_manager = Arc::new((*app_handle.state::<EncryptionManager>()).clone());
     let request = render_websocket_request(
         &resolved_request,
-        environment_chain,
+        environment_chain.clone(),
         &PluginTemplateCallback::new(
             plugin_manager.clone(),
             encryption_manager.clone(),
@@ -348,7 +356,23 @@ pub async fn cmd_ws_connect<R: Runtime>(
         }
     }
 
-    let client_cert = find_client_certificate(url.as_str(), &settings.client_certificates);
+    let client_cert = match find_client_certificate(url.as_str(), &certificates) {
+        Some(c) => Some(
+            render_client_certificate(
+                c,
+                environment_chain,
+                &PluginTemplateCallback::new(
+                    plugin_manager.clone(),
+                    encryption_manager.clone(),
+                    &window.plugin_context(),
+                    RenderPurpose::Send,
+                ),
+                &RenderOptions { error_behavior: RenderErrorBehavior::Throw },
+            )
+            .await?,
+        ),
+        None => None,
+    };
 
     let response = match ws_manager
         .connect(
@@ -356,8 +380,10 @@ pub async fn cmd_ws_connect<R: Runtime>(
             url.as_str(),
             headers,
             receive_tx,
-            workspace.setting_validate_certificates,
+            TlsSettings::for_workspace(&workspace),
             client_cert,
+            app_handle.db().get_settings().proxy.into(),
+            network,
         )
         .await
     {
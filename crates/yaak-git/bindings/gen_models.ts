@@ -1,23 +1,64 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type DnsOverride = { hostname: string, ipv4: Array<string>, ipv6: Array<string>, enabled?: boolean, };
+export type BodyCompression = "gzip" | "deflate" | "br" | "zstd";
+
+export type CertificatePin = { host: string, pin: string, enabled?: boolean, };
+
+export type ClientCertificate = { host: string, port: number | null, crtFile: string | null, keyFile: string | null, pfxFile: string | null, passphrase: string | null, enabled?: boolean, };
+
+export type DnsOverride = { 
+/**
+ * A hostname, or `*.example.com` for all subdomains of example.com
+ */
+hostname: string, 
+/**
+ * Only override connections to this port
+ */
+port?: number, ipv4: Array<string>, ipv6: Array<string>, 
+/**
+ * Connect to this port instead, like curl's `--connect-to`
+ */
+targetPort?: number, enabled?: boolean, };
 
 export type Environment = { model: "environment", id: string, workspaceId: string, createdAt: string, updatedAt: string, name: string, public: boolean, parentModel: string, parentId: string | null, variables: Array<EnvironmentVariable>, color: string | null, sortPriority: number, };
 
 export type EnvironmentVariable = { enabled?: boolean, name: string, value: string, id?: string, };
 
-export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, sortPriority: number, };
+export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, sortPriority: number, settingHttpVersion: HttpVersion | null, settingClientCertificates: Array<ClientCertificate>, settingRetryPolicy: RetryPolicy | null, settingRedirectPolicy: RedirectPolicy | null, settingNetwork: NetworkSettings | null, };
 
 export type GrpcRequest = { model: "grpc_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authenticationType: string | null, authentication: Record<string, any>, description: string, message: string, metadata: Array<HttpRequestHeader>, method: string | null, name: string, service: string | null, sortPriority: number, url: string, };
 
-export type HttpRequest = { model: "http_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, body: Record<string, any>, bodyType: string | null, description: string, headers: Array<HttpRequestHeader>, method: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, };
+export type HttpRequest = { model: "http_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, body: Record<string, any>, bodyType: string | null, bodyCompression: BodyCompression | null, description: string, headers: Array<HttpRequestHeader>, method: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, settingHttpVersion: HttpVersion | null, settingRetryPolicy: RetryPolicy | null, settingRedirectPolicy: RedirectPolicy | null, settingNetwork: NetworkSettings | null, };
 
 export type HttpRequestHeader = { enabled?: boolean, name: string, value: string, id?: string, };
 
+/**
+ * Which HTTP version to use when sending requests
+ */
+export type HttpVersion = "auto" | "http1" | "http2" | "http2_prior_knowledge";
+
 export type HttpUrlParameter = { enabled?: boolean, name: string, value: string, id?: string, };
 
+export type IpFamily = "auto" | "ipv4" | "ipv6";
+
+/**
+ * Where connections are made from, and over which IP versions
+ */
+export type NetworkSettings = { ipFamily: IpFamily, localAddress: string, interface: string, };
+
+export type RedirectPolicy = { maxRedirects: number, keepCrossOriginCredentials: boolean, convertPostToGet: boolean, };
+
+export type RetryErrorKind = "connect" | "timeout" | "network";
+
+export type RetryPolicy = { maxAttempts: number, statuses: Array<number>, errors: Array<RetryErrorKind>, initialDelay: number, maxDelay: number, jitter: boolean, respectRetryAfter: boolean, };
+
 export type SyncModel = { "type": "workspace" } & Workspace | { "type": "environment" } & Environment | { "type": "folder" } & Folder | { "type": "http_request" } & HttpRequest | { "type": "grpc_request" } & GrpcRequest | { "type": "websocket_request" } & WebsocketRequest;
 
-export type WebsocketRequest = { model: "websocket_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, message: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, };
+/**
+ * TLS protocol version, used to constrain the versions offered in the handshake
+ */
+export type TlsVersion = "tls12" | "tls13";
+
+export type WebsocketRequest = { model: "websocket_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, message: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, settingHttpVersion: HttpVersion | null, };
 
-export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, encryptionKeyChallenge: string | null, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingDnsOverrides: Array<DnsOverride>, };
+export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, encryptionKeyChallenge: string | null, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingConnectTimeout: number, settingReadTimeout: number, settingDnsOverrides: Array<DnsOverride>, settingDnsServer: string, settingHttpVersion: HttpVersion, settingCaCertificates: Array<string>, settingCertificatePins: Array<CertificatePin>, settingTlsMinVersion: TlsVersion | null, settingTlsMaxVersion: TlsVersion | null, settingTlsCipherSuites: Array<string>, settingTlsKxGroups: Array<string>, settingTlsSendSni: boolean, settingClientCertificates: Array<ClientCertificate>, settingRetryPolicy: RetryPolicy, settingRedirectPolicy: RedirectPolicy, settingNetwork: NetworkSettings, };
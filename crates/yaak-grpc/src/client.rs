@@ -1,16 +1,12 @@
 use crate::error::Error::GenericError;
 use crate::error::Result;
 use crate::manager::decorate_req;
-use crate::transport::get_transport;
+use crate::transport::{Route, Transport, get_transport};
 use async_recursion::async_recursion;
-use hyper_rustls::HttpsConnector;
-use hyper_util::client::legacy::Client;
-use hyper_util::client::legacy::connect::HttpConnector;
 use log::debug;
 use std::collections::BTreeMap;
 use tokio_stream::StreamExt;
 use tonic::Request;
-use tonic::body::BoxBody;
 use tonic::transport::Uri;
 use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
 use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
@@ -20,9 +16,9 @@ use tonic_reflection::pb::v1::{
 };
 use tonic_reflection::pb::v1::{ExtensionRequest, FileDescriptorResponse};
 use tonic_reflection::pb::{v1, v1alpha};
-use yaak_tls::ClientCertificateConfig;
+use yaak_tls::{ClientCertificateConfig, TlsSettings};
 
-pub struct AutoReflectionClient<T = Client<HttpsConnector<HttpConnector>, BoxBody>> {
+pub struct AutoReflectionClient<T = Transport> {
     use_v1alpha: bool,
     client_v1: v1::server_reflection_client::ServerReflectionClient<T>,
     client_v1alpha: v1alpha::server_reflection_client::ServerReflectionClient<T>,
@@ -31,15 +27,16 @@ pub struct AutoReflectionClient<T = Client<HttpsConnector<HttpConnector>, BoxBod
 impl AutoReflectionClient {
     pub fn new(
         uri: &Uri,
-        validate_certificates: bool,
+        tls_settings: TlsSettings,
         client_cert: Option<ClientCertificateConfig>,
+        route: Route,
     ) -> Result<Self> {
         let client_v1 = v1::server_reflection_client::ServerReflectionClient::with_origin(
-            get_transport(validate_certificates, client_cert.clone())?,
+            get_transport(tls_settings.clone(), client_cert.clone(), route.clone())?,
             uri.clone(),
         );
         let client_v1alpha = v1alpha::server_reflection_client::ServerReflectionClient::with_origin(
-            get_transport(validate_certificates, client_cert.clone())?,
+            get_transport(tls_settings.clone(), client_cert.clone(), route)?,
             uri.clone(),
         );
         Ok(AutoReflectionClient { use_v1alpha: false, client_v1, client_v1alpha })
@@ -11,6 +11,9 @@ pub enum Error {
     #[error(transparent)]
     TlsError(#[from] yaak_tls::error::Error),
 
+    #[error(transparent)]
+    HttpError(#[from] yaak_http::error::Error),
+
     #[error(transparent)]
     TonicError(#[from] Status),
 
@@ -9,8 +9,11 @@ pub mod error;
 mod json_schema;
 pub mod manager;
 mod reflection;
+pub mod render;
 mod transport;
 
+pub use render::render_grpc_request;
+
 pub use tonic::Code;
 pub use tonic::metadata::*;
 
@@ -4,11 +4,8 @@ use crate::error::Result;
 use crate::reflection::{
     fill_pool_from_files, fill_pool_from_reflection, method_desc_to_path, reflect_types_for_message,
 };
-use crate::transport::get_transport;
+use crate::transport::{Route, Transport, get_transport};
 use crate::{MethodDefinition, ServiceDefinition, json_schema};
-use hyper_rustls::HttpsConnector;
-use hyper_util::client::legacy::Client;
-use hyper_util::client::legacy::connect::HttpConnector;
 use log::{info, warn};
 pub use prost_reflect::DynamicMessage;
 use prost_reflect::{DescriptorPool, MethodDescriptor, ServiceDescriptor};
@@ -23,18 +20,24 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::body::BoxBody;
 use tonic::metadata::{MetadataKey, MetadataValue};
 use tonic::transport::Uri;
 use tonic::{IntoRequest, IntoStreamingRequest, Request, Response, Status, Streaming};
-use yaak_tls::ClientCertificateConfig;
+use url::Url;
+use yaak_http::client::HttpConnectionProxySetting;
+use yaak_http::proxy::ProxySelector;
+use yaak_http::socket::socket_path;
+use yaak_models::models::NetworkSettings;
+use yaak_tls::{ClientCertificateConfig, TlsSettings};
 
 #[derive(Clone)]
 pub struct GrpcConnection {
     pool: Arc<RwLock<DescriptorPool>>,
-    conn: Client<HttpsConnector<HttpConnector>, BoxBody>,
+    conn: Transport,
     pub uri: Uri,
     use_reflection: bool,
+    tls_settings: TlsSettings,
+    route: Route,
 }
 
 #[derive(Default, Debug)]
@@ -93,8 +96,16 @@ impl GrpcConnection {
         client_cert: Option<ClientCertificateConfig>,
     ) -> Result<Response<DynamicMessage>> {
         if self.use_reflection {
-            reflect_types_for_message(self.pool.clone(), &self.uri, message, metadata, client_cert)
-                .await?;
+            reflect_types_for_message(
+                self.pool.clone(),
+                &self.uri,
+                message,
+                metadata,
+                self.tls_settings.clone(),
+                client_cert,
+                self.route.clone(),
+            )
+            .await?;
         }
         let method = &self.method(&service, &method).await?;
         let input_message = method.input();
@@ -134,7 +145,9 @@ impl GrpcConnection {
             let uri = self.uri.clone();
             let md = metadata.clone();
             let use_reflection = self.use_reflection.clone();
+            let tls_settings = self.tls_settings.clone();
             let client_cert = client_cert.clone();
+            let route = self.route.clone();
             stream
                 .then(move |json| {
                     let pool = pool.clone();
@@ -142,13 +155,23 @@ impl GrpcConnection {
                     let input_message = input_message.clone();
                     let md = md.clone();
                     let use_reflection = use_reflection.clone();
+                    let tls_settings = tls_settings.clone();
                     let client_cert = client_cert.clone();
+                    let route = route.clone();
                     let on_message = on_message.clone();
                     let json_clone = json.clone();
                     async move {
                         if use_reflection {
-                            if let Err(e) =
-                                reflect_types_for_message(pool, &uri, &json, &md, client_cert).await
+                            if let Err(e) = reflect_types_for_message(
+                                pool,
+                                &uri,
+                                &json,
+                                &md,
+                                tls_settings,
+                                client_cert,
+                                route,
+                            )
+                            .await
                             {
                                 warn!("Failed to resolve Any types: {e}");
                             }
@@ -200,7 +223,9 @@ impl GrpcConnection {
             let uri = self.uri.clone();
             let md = metadata.clone();
             let use_reflection = self.use_reflection.clone();
+            let tls_settings = self.tls_settings.clone();
             let client_cert = client_cert.clone();
+            let route = self.route.clone();
             stream
                 .then(move |json| {
                     let pool = pool.clone();
@@ -208,13 +233,23 @@ impl GrpcConnection {
                     let input_message = input_message.clone();
                     let md = md.clone();
                     let use_reflection = use_reflection.clone();
+                    let tls_settings = tls_settings.clone();
                     let client_cert = client_cert.clone();
+                    let route = route.clone();
                     let on_message = on_message.clone();
                     let json_clone = json.clone();
                     async move {
                         if use_reflection {
-                            if let Err(e) =
-                                reflect_types_for_message(pool, &uri, &json, &md, client_cert).await
+                            if let Err(e) = reflect_types_for_message(
+                                pool,
+                                &uri,
+                                &json,
+                                &md,
+                                tls_settings,
+                                client_cert,
+                                route,
+                            )
+                            .await
                             {
                                 warn!("Failed to resolve Any types: {e}");
                             }
@@ -304,14 +339,17 @@ impl GrpcHandle {
         self.pools.remove(&key);
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn reflect(
         &mut self,
         id: &str,
         uri: &str,
         proto_files: &Vec<PathBuf>,
         metadata: &BTreeMap<String, String>,
-        validate_certificates: bool,
+        tls_settings: TlsSettings,
         client_cert: Option<ClientCertificateConfig>,
+        proxy: HttpConnectionProxySetting,
+        network: NetworkSettings,
     ) -> Result<bool> {
         let server_reflection = proto_files.is_empty();
         let key = make_pool_key(id, uri, proto_files);
@@ -322,8 +360,8 @@ impl GrpcHandle {
         }
 
         let pool = if server_reflection {
-            let full_uri = uri_from_str(uri)?;
-            fill_pool_from_reflection(&full_uri, metadata, validate_certificates, client_cert).await
+            let (full_uri, route) = resolve_route(uri, &proxy, network).await?;
+            fill_pool_from_reflection(&full_uri, metadata, tls_settings, client_cert, route).await
         } else {
             fill_pool_from_files(&self.config, proto_files).await
         }?;
@@ -332,19 +370,22 @@ impl GrpcHandle {
         Ok(server_reflection)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn services(
         &mut self,
         id: &str,
         uri: &str,
         proto_files: &Vec<PathBuf>,
         metadata: &BTreeMap<String, String>,
-        validate_certificates: bool,
+        tls_settings: TlsSettings,
         client_cert: Option<ClientCertificateConfig>,
+        proxy: HttpConnectionProxySetting,
+        network: NetworkSettings,
     ) -> Result<Vec<ServiceDefinition>> {
         // Ensure we have a pool; reflect only if missing
         if self.get_pool(id, uri, proto_files).is_none() {
             info!("Reflecting gRPC services for {} at {}", id, uri);
-            self.reflect(id, uri, proto_files, metadata, validate_certificates, client_cert)
+            self.reflect(id, uri, proto_files, metadata, tls_settings, client_cert, proxy, network)
                 .await?;
         }
 
@@ -377,14 +418,17 @@ impl GrpcHandle {
             .collect::<Vec<_>>()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn connect(
         &mut self,
         id: &str,
         uri: &str,
         proto_files: &Vec<PathBuf>,
         metadata: &BTreeMap<String, String>,
-        validate_certificates: bool,
+        tls_settings: TlsSettings,
         client_cert: Option<ClientCertificateConfig>,
+        proxy: HttpConnectionProxySetting,
+        network: NetworkSettings,
     ) -> Result<GrpcConnection> {
         let use_reflection = proto_files.is_empty();
         if self.get_pool(id, uri, proto_files).is_none() {
@@ -393,8 +437,10 @@ impl GrpcHandle {
                 uri,
                 proto_files,
                 metadata,
-                validate_certificates,
+                tls_settings.clone(),
                 client_cert.clone(),
+                proxy.clone(),
+                network.clone(),
             )
             .await?;
         }
@@ -402,9 +448,16 @@ impl GrpcHandle {
             .get_pool(id, uri, proto_files)
             .ok_or(GenericError("Failed to get pool".to_string()))?
             .clone();
-        let uri = uri_from_str(uri)?;
-        let conn = get_transport(validate_certificates, client_cert.clone())?;
-        Ok(GrpcConnection { pool: Arc::new(RwLock::new(pool)), use_reflection, conn, uri })
+        let (uri, route) = resolve_route(uri, &proxy, network).await?;
+        let conn = get_transport(tls_settings.clone(), client_cert.clone(), route.clone())?;
+        Ok(GrpcConnection {
+            pool: Arc::new(RwLock::new(pool)),
+            use_reflection,
+            conn,
+            uri,
+            tls_settings,
+            route,
+        })
     }
 
     fn get_pool(&self, id: &str, uri: &str, proto_files: &Vec<PathBuf>) -> Option<&DescriptorPool> {
@@ -423,6 +476,21 @@ pub(crate) fn decorate_req<T>(
     Ok(())
 }
 
+/// Parse the URI along with the route its connections take. Sockets are addressed with
+/// `http+unix://` URIs whose host is the percent-encoded path, and requests over them are sent
+/// to `http://localhost`.
+async fn resolve_route(
+    uri_str: &str,
+    proxy: &HttpConnectionProxySetting,
+    network: NetworkSettings,
+) -> Result<(Uri, Route)> {
+    if let Some(socket) = Url::parse(uri_str).ok().as_ref().and_then(socket_path) {
+        return Ok((Uri::from_static("http://localhost"), Route::Socket(socket)));
+    }
+    let uri = uri_from_str(uri_str)?;
+    Ok((uri, Route::Tcp(Arc::new(ProxySelector::new(proxy).await?), network)))
+}
+
 fn uri_from_str(uri_str: &str) -> Result<Uri> {
     match Uri::from_str(uri_str) {
         Ok(uri) => Ok(uri),
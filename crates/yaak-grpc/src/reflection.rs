@@ -3,6 +3,7 @@ use crate::client::AutoReflectionClient;
 use crate::error::Error::GenericError;
 use crate::error::Result;
 use crate::manager::GrpcConfig;
+use crate::transport::Route;
 use anyhow::anyhow;
 use async_recursion::async_recursion;
 use log::{debug, info, warn};
@@ -22,7 +23,7 @@ use tonic::transport::Uri;
 use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
 use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
 use yaak_common::command::new_xplatform_command;
-use yaak_tls::ClientCertificateConfig;
+use yaak_tls::{ClientCertificateConfig, TlsSettings};
 
 pub async fn fill_pool_from_files(
     config: &GrpcConfig,
@@ -117,11 +118,12 @@ pub async fn fill_pool_from_files(
 pub async fn fill_pool_from_reflection(
     uri: &Uri,
     metadata: &BTreeMap<String, String>,
-    validate_certificates: bool,
+    tls_settings: TlsSettings,
     client_cert: Option<ClientCertificateConfig>,
+    route: Route,
 ) -> Result<DescriptorPool> {
     let mut pool = DescriptorPool::new();
-    let mut client = AutoReflectionClient::new(uri, validate_certificates, client_cert)?;
+    let mut client = AutoReflectionClient::new(uri, tls_settings, client_cert, route)?;
 
     for service in list_services(&mut client, metadata).await? {
         if service == "grpc.reflection.v1alpha.ServerReflection" {
@@ -191,7 +193,9 @@ pub(crate) async fn reflect_types_for_message(
     uri: &Uri,
     json: &str,
     metadata: &BTreeMap<String, String>,
+    tls_settings: TlsSettings,
     client_cert: Option<ClientCertificateConfig>,
+    route: Route,
 ) -> Result<()> {
     // 1. Collect all Any types in the JSON
     let mut extra_types = Vec::new();
@@ -201,7 +205,7 @@ pub(crate) async fn reflect_types_for_message(
         return Ok(()); // nothing to do
     }
 
-    let mut client = AutoReflectionClient::new(uri, false, client_cert)?;
+    let mut client = AutoReflectionClient::new(uri, tls_settings, client_cert, route)?;
     for extra_type in extra_types {
         {
             let guard = pool.read().await;
@@ -1,29 +1,43 @@
 use crate::error::Result;
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use hyper_util::client::legacy::Client;
-use hyper_util::client::legacy::connect::HttpConnector;
-use hyper_util::rt::TokioExecutor;
+use hyper_util::client::legacy::connect::{Connected, Connection};
+use hyper_util::rt::{TokioExecutor, TokioIo};
 use log::info;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
 use tonic::body::BoxBody;
-use yaak_tls::{ClientCertificateConfig, get_tls_config};
+use tonic::transport::Uri;
+use tower_service::Service;
+use url::Url;
+use yaak_http::proxy::ProxySelector;
+use yaak_http::socket::SocketStream;
+use yaak_http::{socket, tunnel};
+use yaak_models::models::NetworkSettings;
+use yaak_tls::{ClientCertificateConfig, TlsSettings, get_tls_config};
 
 // I think ALPN breaks this because we're specifying http2_only
-const WITH_ALPN: bool = false;
+const ALPN_PROTOCOLS: &[&str] = &[];
+
+pub(crate) type Transport = Client<HttpsConnector<RouteConnector>, BoxBody>;
 
 pub(crate) fn get_transport(
-    validate_certificates: bool,
+    tls_settings: TlsSettings,
     client_cert: Option<ClientCertificateConfig>,
-) -> Result<Client<HttpsConnector<HttpConnector>, BoxBody>> {
-    let tls_config = get_tls_config(validate_certificates, WITH_ALPN, client_cert.clone())?;
-
-    let mut http = HttpConnector::new();
-    http.enforce_http(false);
+    route: Route,
+) -> Result<Transport> {
+    let tls_config = get_tls_config(&tls_settings, ALPN_PROTOCOLS, client_cert.clone())?;
 
     let connector = HttpsConnectorBuilder::new()
         .with_tls_config(tls_config)
         .https_or_http()
         .enable_http2()
-        .build();
+        .wrap_connector(RouteConnector { route });
 
     let client = Client::builder(TokioExecutor::new())
         .pool_max_idle_per_host(0)
@@ -32,9 +46,107 @@ pub(crate) fn get_transport(
 
     info!(
         "Created gRPC client validate_certs={} client_cert={}",
-        validate_certificates,
+        tls_settings.validate_certificates,
         client_cert.is_some()
     );
 
     Ok(client)
 }
+
+/// Where connections are made to
+#[derive(Clone)]
+pub(crate) enum Route {
+    /// The host of each URI, through the proxy chosen for it
+    Tcp(Arc<ProxySelector>, NetworkSettings),
+    /// A Unix domain socket or named pipe, whatever the URI
+    Socket(String),
+}
+
+/// Opens the connections of a [`Route`], which TLS is then layered on
+#[derive(Clone)]
+pub(crate) struct RouteConnector {
+    route: Route,
+}
+
+impl Service<Uri> for RouteConnector {
+    type Response = TokioIo<RouteStream>;
+    type Error = yaak_http::error::Error;
+    type Future =
+        Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let route = self.route.clone();
+        Box::pin(async move {
+            let stream = match route {
+                Route::Tcp(proxy, network) => {
+                    let url = Url::parse(&uri.to_string()).map_err(|e| {
+                        yaak_http::error::Error::ProxyError(format!("Invalid URI {uri}: {e}"))
+                    })?;
+                    let stream = tunnel::connect(&proxy, &url, &network).await?;
+                    stream.set_nodelay(true)?;
+                    RouteStream::Tcp(stream)
+                }
+                Route::Socket(path) => RouteStream::Socket(socket::connect(&path).await?),
+            };
+            Ok(TokioIo::new(stream))
+        })
+    }
+}
+
+pub(crate) enum RouteStream {
+    Tcp(TcpStream),
+    Socket(SocketStream),
+}
+
+impl Connection for RouteStream {
+    fn connected(&self) -> Connected {
+        match self {
+            RouteStream::Tcp(s) => s.connected(),
+            RouteStream::Socket(_) => Connected::new(),
+        }
+    }
+}
+
+impl AsyncRead for RouteStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            RouteStream::Tcp(s) => Pin::new(s).poll_read(cx, buf),
+            RouteStream::Socket(s) => Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for RouteStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            RouteStream::Tcp(s) => Pin::new(s).poll_write(cx, buf),
+            RouteStream::Socket(s) => Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            RouteStream::Tcp(s) => Pin::new(s).poll_flush(cx),
+            RouteStream::Socket(s) => Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            RouteStream::Tcp(s) => Pin::new(s).poll_shutdown(cx),
+            RouteStream::Socket(s) => Pin::new(s).poll_shutdown(cx),
+        }
+    }
+}
@@ -1,18 +1,23 @@
 use crate::dns::LocalhostResolver;
 use crate::error::Result;
-use log::{debug, info, warn};
+use crate::net;
+use crate::proxy::ProxySelector;
+use crate::timing::ConnectTimingLayer;
+use log::info;
 use reqwest::{Client, Proxy, redirect};
 use std::sync::Arc;
-use yaak_models::models::DnsOverride;
-use yaak_tls::{ClientCertificateConfig, get_tls_config};
+use yaak_models::models::{
+    DnsOverride, HttpVersion, NetworkSettings, ProxySetting, ProxySettingAuth,
+};
+use yaak_tls::{ClientCertificateConfig, TlsSettings, get_tls_config};
 
-#[derive(Clone)]
+#[derive(Clone, Hash)]
 pub struct HttpConnectionProxySettingAuth {
     pub user: String,
     pub password: String,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Hash)]
 pub enum HttpConnectionProxySetting {
     Disabled,
     System,
@@ -22,22 +27,75 @@ pub enum HttpConnectionProxySetting {
         auth: Option<HttpConnectionProxySettingAuth>,
         bypass: String,
     },
+    Socks {
+        host: String,
+        /// Resolve hostnames on the proxy (socks5h) instead of locally
+        remote_dns: bool,
+        auth: Option<HttpConnectionProxySettingAuth>,
+        bypass: String,
+    },
+    /// Choose the proxy per URL with a proxy auto-config script, loaded from a URL or file
+    Pac {
+        url: String,
+    },
+}
+
+impl From<ProxySettingAuth> for HttpConnectionProxySettingAuth {
+    fn from(ProxySettingAuth { user, password }: ProxySettingAuth) -> Self {
+        Self { user, password }
+    }
+}
+
+impl From<Option<ProxySetting>> for HttpConnectionProxySetting {
+    /// Map the app's proxy setting, where `None` and disabled proxies mean the system's
+    fn from(setting: Option<ProxySetting>) -> Self {
+        match setting {
+            None => HttpConnectionProxySetting::System,
+            Some(ProxySetting::Disabled) => HttpConnectionProxySetting::Disabled,
+            Some(ProxySetting::Enabled { disabled: true, .. })
+            | Some(ProxySetting::Socks { disabled: true, .. })
+            | Some(ProxySetting::Pac { disabled: true, .. }) => HttpConnectionProxySetting::System,
+            Some(ProxySetting::Enabled { http, https, auth, bypass, .. }) => {
+                HttpConnectionProxySetting::Enabled {
+                    http,
+                    https,
+                    bypass,
+                    auth: auth.map(Into::into),
+                }
+            }
+            Some(ProxySetting::Socks { host, remote_dns, auth, bypass, .. }) => {
+                HttpConnectionProxySetting::Socks {
+                    host,
+                    remote_dns,
+                    bypass,
+                    auth: auth.map(Into::into),
+                }
+            }
+            Some(ProxySetting::Pac { url, .. }) => HttpConnectionProxySetting::Pac { url },
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct HttpConnectionOptions {
     pub id: String,
-    pub validate_certificates: bool,
+    pub tls_settings: TlsSettings,
     pub proxy: HttpConnectionProxySetting,
     pub client_certificate: Option<ClientCertificateConfig>,
     pub dns_overrides: Vec<DnsOverride>,
+    /// DNS server to resolve with instead of the system resolver, empty for the system one
+    pub dns_server: String,
+    pub network: NetworkSettings,
+    pub http_version: HttpVersion,
 }
 
 impl HttpConnectionOptions {
-    /// Build a reqwest Client and return it along with the DNS resolver.
+    /// Build a reqwest Client and return it along with the DNS resolver and proxy selector.
     /// The resolver is returned separately so it can be configured per-request
     /// to emit DNS timing events to the appropriate channel.
-    pub(crate) fn build_client(&self) -> Result<(Client, Arc<LocalhostResolver>)> {
+    pub(crate) async fn build_client(
+        &self,
+    ) -> Result<(Client, Arc<LocalhostResolver>, Arc<ProxySelector>)> {
         let mut client = Client::builder()
             .connection_verbose(true)
             .redirect(redirect::Policy::none())
@@ -51,77 +109,60 @@ impl HttpConnectionOptions {
             // This is needed so we can emit DNS timing events for each request
             .pool_max_idle_per_host(0);
 
+        // Restrict the protocol. Over TLS, this is negotiated with ALPN
+        let alpn_protocols: &[&str] = match self.http_version {
+            HttpVersion::Auto => &["h2", "http/1.1"],
+            HttpVersion::Http1 => {
+                client = client.http1_only();
+                &["http/1.1"]
+            }
+            HttpVersion::Http2 => &["h2"],
+            HttpVersion::Http2PriorKnowledge => {
+                client = client.http2_prior_knowledge();
+                &["h2"]
+            }
+        };
+
         // Configure TLS with optional client certificate
         let config =
-            get_tls_config(self.validate_certificates, true, self.client_certificate.clone())?;
+            get_tls_config(&self.tls_settings, alpn_protocols, self.client_certificate.clone())?;
         client = client.use_preconfigured_tls(config);
 
         // Configure DNS resolver - keep a reference to configure per-request
-        let resolver = LocalhostResolver::new(self.dns_overrides.clone());
+        let resolver = LocalhostResolver::new(
+            self.dns_overrides.clone(),
+            &self.dns_server,
+            self.network.ip_family,
+        )?;
         client = client.dns_resolver(resolver.clone());
 
-        // Configure proxy
-        match self.proxy.clone() {
-            HttpConnectionProxySetting::System => { /* Default */ }
-            HttpConnectionProxySetting::Disabled => {
+        // Send from the local address and network interface, if any
+        client = net::apply_to_client(client, &self.network)?;
+
+        // Emit connect timing and TLS session events to the resolver's event sender
+        client = client.connector_layer(ConnectTimingLayer::new(resolver.event_sender()));
+
+        // Configure proxy. Custom proxies are chosen per URL by the selector, which the sender
+        // asks first to report the choice, so PAC results are cached by the time reqwest asks
+        let proxy = Arc::new(ProxySelector::new(&self.proxy).await?);
+        match proxy.as_ref() {
+            ProxySelector::System => { /* Default */ }
+            ProxySelector::Disabled => {
                 client = client.no_proxy();
             }
-            HttpConnectionProxySetting::Enabled { http, https, auth, bypass } => {
-                for p in build_enabled_proxy(http, https, auth, bypass) {
-                    client = client.proxy(p)
-                }
+            ProxySelector::Fixed { .. } | ProxySelector::Pac { .. } => {
+                let proxy = proxy.clone();
+                client = client.proxy(Proxy::custom(move |url| proxy.select_blocking(url).0));
             }
         }
 
         info!(
-            "Building new HTTP client validate_certificates={} client_cert={}",
-            self.validate_certificates,
-            self.client_certificate.is_some()
+            "Building new HTTP client validate_certificates={} client_cert={} http_version={}",
+            self.tls_settings.validate_certificates,
+            self.client_certificate.is_some(),
+            self.http_version,
         );
 
-        Ok((client.build()?, resolver))
-    }
-}
-
-fn build_enabled_proxy(
-    http: String,
-    https: String,
-    auth: Option<HttpConnectionProxySettingAuth>,
-    bypass: String,
-) -> Vec<Proxy> {
-    debug!("Using proxy http={http} https={https} bypass={bypass}");
-
-    let mut proxies = Vec::new();
-
-    if !http.is_empty() {
-        match Proxy::http(http) {
-            Ok(mut proxy) => {
-                if let Some(HttpConnectionProxySettingAuth { user, password }) = auth.clone() {
-                    debug!("Using http proxy auth");
-                    proxy = proxy.basic_auth(user.as_str(), password.as_str());
-                }
-                proxies.push(proxy.no_proxy(reqwest::NoProxy::from_string(&bypass)));
-            }
-            Err(e) => {
-                warn!("Failed to apply http proxy {e:?}");
-            }
-        };
-    }
-
-    if !https.is_empty() {
-        match Proxy::https(https) {
-            Ok(mut proxy) => {
-                if let Some(HttpConnectionProxySettingAuth { user, password }) = auth {
-                    debug!("Using https proxy auth");
-                    proxy = proxy.basic_auth(user.as_str(), password.as_str());
-                }
-                proxies.push(proxy.no_proxy(reqwest::NoProxy::from_string(&bypass)));
-            }
-            Err(e) => {
-                warn!("Failed to apply https proxy {e:?}");
-            }
-        };
+        Ok((client.build()?, resolver, proxy))
     }
-
-    proxies
 }
@@ -0,0 +1,85 @@
+use crate::error::{Error, Result};
+use async_compression::tokio::bufread::{BrotliEncoder, GzipEncoder, ZlibEncoder, ZstdEncoder};
+use flate2::Compression;
+use std::io::Write;
+use std::pin::Pin;
+use tokio::io::{AsyncBufRead, AsyncRead};
+use yaak_models::models::BodyCompression;
+
+/// Compress a request body with the given encoding
+pub fn compress(data: &[u8], compression: BodyCompression) -> Result<Vec<u8>> {
+    let compressed = match compression {
+        BodyCompression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).and_then(|_| encoder.finish())
+        }
+        // `deflate` is the zlib format (RFC 9110), not raw DEFLATE
+        BodyCompression::Deflate => {
+            let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).and_then(|_| encoder.finish())
+        }
+        BodyCompression::Br => {
+            let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+            writer.write_all(data).map(|_| writer.into_inner())
+        }
+        BodyCompression::Zstd => zstd::stream::encode_all(data, 0),
+    };
+
+    compressed
+        .map_err(|e| Error::CompressionError(format!("{compression} compression failed: {e}")))
+}
+
+/// Create a streaming compressor that wraps an async reader, for bodies that aren't buffered
+/// (e.g. files). The compressed size isn't known up front.
+pub fn streaming_encoder<R: AsyncBufRead + Send + 'static>(
+    reader: R,
+    compression: BodyCompression,
+) -> Pin<Box<dyn AsyncRead + Send + 'static>> {
+    match compression {
+        BodyCompression::Gzip => Box::pin(GzipEncoder::new(reader)),
+        BodyCompression::Deflate => Box::pin(ZlibEncoder::new(reader)),
+        BodyCompression::Br => Box::pin(BrotliEncoder::new(reader)),
+        BodyCompression::Zstd => Box::pin(ZstdEncoder::new(reader)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decompress::{ContentEncoding, decompress};
+    use tokio::io::AsyncReadExt;
+
+    const ALL: [(BodyCompression, ContentEncoding); 4] = [
+        (BodyCompression::Gzip, ContentEncoding::Gzip),
+        (BodyCompression::Deflate, ContentEncoding::Deflate),
+        (BodyCompression::Br, ContentEncoding::Brotli),
+        (BodyCompression::Zstd, ContentEncoding::Zstd),
+    ];
+
+    #[test]
+    fn test_compress_round_trip() {
+        let original = b"hello world, this is a test of request body compression";
+        for (compression, encoding) in ALL {
+            let compressed = compress(original, compression).unwrap();
+            assert_ne!(compressed, original);
+            if compression == BodyCompression::Deflate {
+                assert!(compressed.starts_with(&[0x78]), "deflate must be zlib-wrapped");
+            }
+            let result = decompress(compressed, &[encoding]).unwrap();
+            assert_eq!(result.data, original, "{compression}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_streaming_encoder_round_trip() {
+        let original = b"hello world, this is a test of streaming request body compression";
+        for (compression, encoding) in ALL {
+            let mut encoder =
+                streaming_encoder(std::io::Cursor::new(original.to_vec()), compression);
+            let mut compressed = Vec::new();
+            encoder.read_to_end(&mut compressed).await.unwrap();
+            let result = decompress(compressed, &[encoding]).unwrap();
+            assert_eq!(result.data, original, "{compression}");
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Import and export of cookies, so sessions can be moved between Yaak, curl and browsers.
+//!
+//! Two formats are supported:
+//! - Netscape cookie files, written by curl (`-c cookies.txt`), wget and browser extensions
+//! - JSON dumps of browser cookies, like those of the `chrome.cookies` API, EditThisCookie,
+//!   Puppeteer or a Playwright storage state
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use yaak_models::models::{Cookie, CookieDomain, CookieExpires, CookieSameSite};
+
+const NETSCAPE_HEADER: &str =
+    "# Netscape HTTP Cookie File\n# https://curl.se/docs/http-cookies.html\n";
+
+/// curl marks HttpOnly cookies by prefixing the domain, which otherwise looks like a comment
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CookieFileFormat {
+    Netscape,
+    Json,
+}
+
+impl CookieFileFormat {
+    /// Guess the format of a cookie file from its contents
+    pub fn detect(contents: &str) -> Self {
+        match contents.trim_start().chars().next() {
+            Some('[') | Some('{') => CookieFileFormat::Json,
+            _ => CookieFileFormat::Netscape,
+        }
+    }
+}
+
+/// Parse a cookie file in either format
+pub fn parse_cookie_file(contents: &str) -> Result<Vec<Cookie>> {
+    match CookieFileFormat::detect(contents) {
+        CookieFileFormat::Netscape => parse_netscape(contents),
+        CookieFileFormat::Json => parse_json(contents),
+    }
+}
+
+/// Write cookies in the given format. Cookies without a domain can't be represented, so
+/// they're left out.
+pub fn write_cookie_file(cookies: &[Cookie], format: CookieFileFormat) -> Result<String> {
+    match format {
+        CookieFileFormat::Netscape => Ok(write_netscape(cookies)),
+        CookieFileFormat::Json => write_json(cookies),
+    }
+}
+
+/// Parse a Netscape cookie file. Each line has the tab-separated fields domain,
+/// include-subdomains, path, secure, expiry (Unix seconds, 0 for session cookies), name and value.
+pub fn parse_netscape(contents: &str) -> Result<Vec<Cookie>> {
+    let mut cookies = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.trim_end_matches('\r').splitn(7, '\t').collect();
+        let [
+            domain,
+            include_subdomains,
+            path,
+            secure,
+            expires,
+            name,
+            rest @ ..,
+        ] = &fields[..]
+        else {
+            return Err(Error::CookieFileError(format!(
+                "Line {} doesn't have the 7 tab-separated fields of a Netscape cookie",
+                i + 1
+            )));
+        };
+        let expires = expires.trim().parse::<i64>().map_err(|_| {
+            Error::CookieFileError(format!("Line {} has an invalid expiry {expires}", i + 1))
+        })?;
+
+        let domain = domain.trim_start_matches('.').to_lowercase();
+        cookies.push(Cookie {
+            name: name.to_string(),
+            value: rest.first().unwrap_or(&"").to_string(),
+            domain: if include_subdomains.eq_ignore_ascii_case("TRUE") {
+                CookieDomain::Suffix(domain)
+            } else {
+                CookieDomain::HostOnly(domain)
+            },
+            expires: if expires > 0 {
+                CookieExpires::AtUtc(expires.to_string())
+            } else {
+                CookieExpires::SessionEnd
+            },
+            path: (path.to_string(), true),
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+            http_only,
+            same_site: CookieSameSite::Unspecified,
+        });
+    }
+    Ok(cookies)
+}
+
+fn write_netscape(cookies: &[Cookie]) -> String {
+    let mut contents = format!("{NETSCAPE_HEADER}\n");
+    for cookie in cookies {
+        let (domain, include_subdomains) = match &cookie.domain {
+            CookieDomain::HostOnly(d) => (d.to_string(), "FALSE"),
+            CookieDomain::Suffix(d) => (format!(".{d}"), "TRUE"),
+            CookieDomain::NotPresent | CookieDomain::Empty => continue,
+        };
+        let expires = match &cookie.expires {
+            CookieExpires::AtUtc(expires) => expires.as_str(),
+            CookieExpires::SessionEnd => "0",
+        };
+        contents.push_str(&format!(
+            "{}{domain}\t{include_subdomains}\t{}\t{}\t{expires}\t{}\t{}\n",
+            if cookie.http_only { HTTP_ONLY_PREFIX } else { "" },
+            cookie.path.0,
+            if cookie.secure { "TRUE" } else { "FALSE" },
+            cookie.name,
+            cookie.value,
+        ));
+    }
+    contents
+}
+
+/// A cookie as browsers and their automation tools dump it. Field names follow the
+/// `chrome.cookies` API, and Puppeteer's and Playwright's `expires` is accepted too.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrowserCookie {
+    name: String,
+    #[serde(default)]
+    value: String,
+    domain: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host_only: Option<bool>,
+    #[serde(default = "default_path")]
+    path: String,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    http_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    same_site: Option<String>,
+    #[serde(default)]
+    session: bool,
+    /// Unix seconds, possibly fractional. Playwright uses -1 for session cookies.
+    #[serde(alias = "expires", skip_serializing_if = "Option::is_none")]
+    expiration_date: Option<f64>,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// Dumps are either a list of cookies or an object with one, like a Playwright storage state
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BrowserCookies {
+    List(Vec<BrowserCookie>),
+    Object { cookies: Vec<BrowserCookie> },
+}
+
+/// Parse a JSON dump of browser cookies
+pub fn parse_json(contents: &str) -> Result<Vec<Cookie>> {
+    let cookies = match serde_json::from_str(contents) {
+        Ok(BrowserCookies::List(cookies)) | Ok(BrowserCookies::Object { cookies }) => cookies,
+        Err(e) => return Err(Error::CookieFileError(e.to_string())),
+    };
+    Ok(cookies.into_iter().map(Cookie::from).collect())
+}
+
+fn write_json(cookies: &[Cookie]) -> Result<String> {
+    let cookies: Vec<BrowserCookie> =
+        cookies.iter().filter_map(BrowserCookie::from_cookie).collect();
+    serde_json::to_string_pretty(&cookies).map_err(|e| Error::CookieFileError(e.to_string()))
+}
+
+impl From<BrowserCookie> for Cookie {
+    fn from(c: BrowserCookie) -> Self {
+        let host_only = c.host_only.unwrap_or(!c.domain.starts_with('.'));
+        let domain = c.domain.trim_start_matches('.').to_lowercase();
+        Cookie {
+            name: c.name,
+            value: c.value,
+            domain: if host_only {
+                CookieDomain::HostOnly(domain)
+            } else {
+                CookieDomain::Suffix(domain)
+            },
+            expires: match c.expiration_date {
+                Some(expires) if !c.session && expires > 0.0 => {
+                    CookieExpires::AtUtc((expires as i64).to_string())
+                }
+                _ => CookieExpires::SessionEnd,
+            },
+            path: (c.path, true),
+            secure: c.secure,
+            http_only: c.http_only,
+            same_site: match c.same_site.unwrap_or_default().to_lowercase().as_str() {
+                "strict" => CookieSameSite::Strict,
+                "lax" => CookieSameSite::Lax,
+                "none" | "no_restriction" => CookieSameSite::None,
+                _ => CookieSameSite::Unspecified,
+            },
+        }
+    }
+}
+
+impl BrowserCookie {
+    fn from_cookie(c: &Cookie) -> Option<Self> {
+        let (domain, host_only) = match &c.domain {
+            CookieDomain::HostOnly(d) => (d.to_string(), true),
+            CookieDomain::Suffix(d) => (format!(".{d}"), false),
+            CookieDomain::NotPresent | CookieDomain::Empty => return None,
+        };
+        let expiration_date = match &c.expires {
+            CookieExpires::AtUtc(expires) => expires.parse::<f64>().ok(),
+            CookieExpires::SessionEnd => None,
+        };
+        Some(BrowserCookie {
+            name: c.name.clone(),
+            value: c.value.clone(),
+            domain,
+            host_only: Some(host_only),
+            path: c.path.0.clone(),
+            secure: c.secure,
+            http_only: c.http_only,
+            same_site: Some(
+                match c.same_site {
+                    CookieSameSite::Strict => "strict",
+                    CookieSameSite::Lax => "lax",
+                    CookieSameSite::None => "no_restriction",
+                    CookieSameSite::Unspecified => "unspecified",
+                }
+                .to_string(),
+            ),
+            session: expiration_date.is_none(),
+            expiration_date,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_netscape() {
+        let contents = "# Netscape HTTP Cookie File\n\
+            \n\
+            .example.com\tTRUE\t/\tTRUE\t2000000000\tsession\tabc123\n\
+            #HttpOnly_api.example.com\tFALSE\t/v1\tFALSE\t0\ttoken\t\n\
+            # a comment\n";
+        let cookies = parse_netscape(contents).unwrap();
+        assert_eq!(cookies.len(), 2);
+
+        assert_eq!(cookies[0].name, "session");
+        assert_eq!(cookies[0].value, "abc123");
+        assert!(matches!(&cookies[0].domain, CookieDomain::Suffix(d) if d == "example.com"));
+        assert!(matches!(&cookies[0].expires, CookieExpires::AtUtc(e) if e == "2000000000"));
+        assert!(cookies[0].secure);
+        assert!(!cookies[0].http_only);
+
+        assert_eq!(cookies[1].name, "token");
+        assert_eq!(cookies[1].value, "");
+        assert!(matches!(&cookies[1].domain, CookieDomain::HostOnly(d) if d == "api.example.com"));
+        assert!(matches!(cookies[1].expires, CookieExpires::SessionEnd));
+        assert_eq!(cookies[1].path.0, "/v1");
+        assert!(cookies[1].http_only);
+
+        assert!(parse_netscape("example.com\tTRUE\t/\n").is_err());
+    }
+
+    #[test]
+    fn test_netscape_round_trip() {
+        let contents = "# Netscape HTTP Cookie File\n\
+            .example.com\tTRUE\t/\tTRUE\t2000000000\tsession\tabc123\n\
+            #HttpOnly_api.example.com\tFALSE\t/v1\tFALSE\t0\ttoken\txyz\n";
+        let cookies = parse_cookie_file(contents).unwrap();
+        let written = write_cookie_file(&cookies, CookieFileFormat::Netscape).unwrap();
+        assert!(written.starts_with(NETSCAPE_HEADER));
+        assert!(written.contains(".example.com\tTRUE\t/\tTRUE\t2000000000\tsession\tabc123\n"));
+        assert!(written.contains("#HttpOnly_api.example.com\tFALSE\t/v1\tFALSE\t0\ttoken\txyz\n"));
+        assert_eq!(parse_cookie_file(&written).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_json() {
+        // chrome.cookies / EditThisCookie
+        let contents = r#"[{
+            "domain": ".example.com",
+            "hostOnly": false,
+            "name": "session",
+            "value": "abc123",
+            "path": "/",
+            "secure": true,
+            "httpOnly": true,
+            "sameSite": "no_restriction",
+            "session": false,
+            "expirationDate": 2000000000.5
+        }]"#;
+        let cookies = parse_cookie_file(contents).unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert!(matches!(&cookies[0].domain, CookieDomain::Suffix(d) if d == "example.com"));
+        assert!(matches!(&cookies[0].expires, CookieExpires::AtUtc(e) if e == "2000000000"));
+        assert!(cookies[0].secure && cookies[0].http_only);
+        assert_eq!(cookies[0].same_site, CookieSameSite::None);
+
+        // Playwright storage state
+        let contents = r#"{"cookies": [{
+            "name": "token",
+            "value": "xyz",
+            "domain": "api.example.com",
+            "path": "/",
+            "expires": -1,
+            "httpOnly": false,
+            "secure": false,
+            "sameSite": "Lax"
+        }], "origins": []}"#;
+        let cookies = parse_cookie_file(contents).unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert!(matches!(&cookies[0].domain, CookieDomain::HostOnly(d) if d == "api.example.com"));
+        assert!(matches!(cookies[0].expires, CookieExpires::SessionEnd));
+        assert_eq!(cookies[0].same_site, CookieSameSite::Lax);
+
+        assert!(parse_cookie_file("[{\"value\": \"missing name\"}]").is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let cookie = Cookie {
+            name: "session".to_string(),
+            value: "abc123".to_string(),
+            domain: CookieDomain::HostOnly("example.com".to_string()),
+            expires: CookieExpires::AtUtc("2000000000".to_string()),
+            path: ("/".to_string(), true),
+            secure: true,
+            http_only: false,
+            same_site: CookieSameSite::Strict,
+        };
+        let written = write_cookie_file(&[cookie], CookieFileFormat::Json).unwrap();
+        let cookies = parse_cookie_file(&written).unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].name, "session");
+        assert!(matches!(&cookies[0].domain, CookieDomain::HostOnly(d) if d == "example.com"));
+        assert!(matches!(&cookies[0].expires, CookieExpires::AtUtc(e) if e == "2000000000"));
+        assert_eq!(cookies[0].same_site, CookieSameSite::Strict);
+    }
+}
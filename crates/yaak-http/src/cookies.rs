@@ -1,13 +1,16 @@
 //! Custom cookie handling for HTTP requests
 //!
 //! This module provides cookie storage and matching functionality that was previously
-//! delegated to reqwest. It implements RFC 6265 cookie domain and path matching.
+//! delegated to reqwest. It implements the storage and sending rules of RFC 6265 and its
+//! revision (6265bis): domain and path matching, cookies for public suffixes are rejected,
+//! `Secure` cookies are only set and sent over secure connections, `__Secure-` and `__Host-`
+//! prefixes are enforced, and `SameSite` cookies are withheld from cross-site requests.
 
 use log::debug;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use url::Url;
-use yaak_models::models::{Cookie, CookieDomain, CookieExpires};
+use url::{Host, Url};
+use yaak_models::models::{Cookie, CookieDomain, CookieExpires, CookieSameSite};
 
 /// A thread-safe cookie store that can be shared across requests
 #[derive(Debug, Clone)]
@@ -37,76 +40,123 @@ impl CookieStore {
         self.cookies.lock().unwrap().clone()
     }
 
-    /// Get the Cookie header value for the given URL
+    /// Add cookies from elsewhere, like an imported cookie file. Cookies with the same name,
+    /// domain and path are replaced.
+    pub fn add_cookies(&self, new_cookies: Vec<Cookie>) {
+        let mut cookies = self.cookies.lock().unwrap();
+        for cookie in new_cookies {
+            cookies.retain(|existing| !cookies_match(existing, &cookie));
+            cookies.push(cookie);
+        }
+    }
+
+    /// Get the Cookie header value for a same-site request to the given URL
     pub fn get_cookie_header(&self, url: &Url) -> Option<String> {
+        self.get_cookie_header_from(url, url, "GET")
+    }
+
+    /// Get the Cookie header value for a request that was initiated from another URL, like the
+    /// first URL of a redirect chain. Requests to another site only get `SameSite=None`
+    /// cookies, and `SameSite=Lax` (or unspecified) ones when the method is safe.
+    pub fn get_cookie_header_from(
+        &self,
+        url: &Url,
+        initiator: &Url,
+        method: &str,
+    ) -> Option<String> {
         let cookies = self.cookies.lock().unwrap();
         let now = SystemTime::now();
+        let cross_site = site(url) != site(initiator);
+        let safe_method =
+            matches!(method.to_uppercase().as_str(), "GET" | "HEAD" | "OPTIONS" | "TRACE");
 
-        let matching_cookies: Vec<_> = cookies
+        let mut matching_cookies: Vec<&Cookie> = cookies
             .iter()
             .filter(|cookie| self.cookie_matches(cookie, url, &now))
-            .filter_map(|cookie| {
-                // Parse the raw cookie to get name=value
-                parse_cookie_name_value(&cookie.raw_cookie)
+            .filter(|cookie| {
+                !cross_site
+                    || match cookie.same_site {
+                        CookieSameSite::None => true,
+                        CookieSameSite::Lax | CookieSameSite::Unspecified => safe_method,
+                        CookieSameSite::Strict => false,
+                    }
             })
             .collect();
 
         if matching_cookies.is_empty() {
-            None
-        } else {
-            Some(
-                matching_cookies
-                    .into_iter()
-                    .map(|(name, value)| format!("{}={}", name, value))
-                    .collect::<Vec<_>>()
-                    .join("; "),
-            )
+            return None;
         }
+
+        // Cookies with longer paths go first, otherwise they keep the order they were set in
+        matching_cookies.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.0.len()));
+        Some(
+            matching_cookies
+                .into_iter()
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
     }
 
     /// Parse Set-Cookie headers and add cookies to the store
     pub fn store_cookies_from_response(&self, url: &Url, set_cookie_headers: &[String]) {
         let mut cookies = self.cookies.lock().unwrap();
+        let now = SystemTime::now();
+        let secure_origin = is_secure_origin(url);
 
         for header_value in set_cookie_headers {
-            if let Some(cookie) = parse_set_cookie(header_value, url) {
-                // Remove any existing cookie with the same name and domain
-                cookies.retain(|existing| !cookies_match(existing, &cookie));
-                debug!(
-                    "Storing cookie: {} for domain {:?}",
-                    parse_cookie_name_value(&cookie.raw_cookie)
-                        .map(|(n, _)| n)
-                        .unwrap_or_else(|| "unknown".to_string()),
-                    cookie.domain
-                );
-                cookies.push(cookie);
+            let Some(cookie) = parse_set_cookie(header_value, url) else {
+                continue;
+            };
+
+            // Insecure origins can't overwrite secure cookies (RFC 6265bis section 5.7)
+            if !secure_origin
+                && cookies.iter().any(|existing| {
+                    existing.secure
+                        && existing.name == cookie.name
+                        && domains_overlap(&existing.domain, &cookie.domain)
+                        && path_matches(&cookie.path.0, &existing.path.0)
+                })
+            {
+                debug!("Ignoring cookie {} that would overwrite a secure cookie", cookie.name);
+                continue;
             }
+
+            // Replace any existing cookie with the same name, domain and path
+            cookies.retain(|existing| !cookies_match(existing, &cookie));
+
+            // Cookies that are already expired only delete the one they replace
+            if is_expired(&cookie, &now) {
+                debug!("Deleting cookie {} for domain {:?}", cookie.name, cookie.domain);
+                continue;
+            }
+
+            debug!("Storing cookie: {} for domain {:?}", cookie.name, cookie.domain);
+            cookies.push(cookie);
         }
+
+        cookies.retain(|cookie| !is_expired(cookie, &now));
     }
 
     /// Check if a cookie matches the given URL
     fn cookie_matches(&self, cookie: &Cookie, url: &Url, now: &SystemTime) -> bool {
-        // Check expiration
-        if let CookieExpires::AtUtc(expiry_str) = &cookie.expires {
-            if let Ok(expiry) = parse_cookie_date(expiry_str) {
-                if expiry < *now {
-                    return false;
-                }
-            }
+        if is_expired(cookie, now) {
+            return false;
+        }
+
+        if cookie.secure && !is_secure_origin(url) {
+            return false;
         }
 
         // Check domain
-        let url_host = match url.host_str() {
-            Some(h) => h.to_lowercase(),
+        let url_host = match canonical_host(url) {
+            Some(h) => h,
             None => return false,
         };
 
         let domain_matches = match &cookie.domain {
             CookieDomain::HostOnly(domain) => url_host == domain.to_lowercase(),
-            CookieDomain::Suffix(domain) => {
-                let domain_lower = domain.to_lowercase();
-                url_host == domain_lower || url_host.ends_with(&format!(".{}", domain_lower))
-            }
+            CookieDomain::Suffix(domain) => domain_matches(&url_host, &domain.to_lowercase()),
             // NotPresent and Empty should never occur in practice since we always set domain
             // when parsing Set-Cookie headers. Treat as non-matching to be safe.
             CookieDomain::NotPresent | CookieDomain::Empty => false,
@@ -124,44 +174,79 @@ impl CookieStore {
     }
 }
 
-/// Parse name=value from a cookie string (raw_cookie format)
-fn parse_cookie_name_value(raw_cookie: &str) -> Option<(String, String)> {
-    // The raw_cookie typically looks like "name=value" or "name=value; attr1; attr2=..."
-    let first_part = raw_cookie.split(';').next()?;
-    let mut parts = first_part.splitn(2, '=');
-    let name = parts.next()?.trim().to_string();
-    let value = parts.next().unwrap_or("").trim().to_string();
-
-    if name.is_empty() { None } else { Some((name, value)) }
-}
-
-/// Parse a Set-Cookie header into a Cookie
+/// Parse a Set-Cookie header into a Cookie, or `None` if it must be ignored. The cookie may
+/// already be expired, in which case it deletes the one it replaces.
 fn parse_set_cookie(header_value: &str, request_url: &Url) -> Option<Cookie> {
     let parsed = cookie::Cookie::parse(header_value).ok()?;
-
-    let raw_cookie = format!("{}={}", parsed.name(), parsed.value());
+    let name = parsed.name().to_string();
+    let host = canonical_host(request_url)?;
+    let secure_origin = is_secure_origin(request_url);
+    let secure = parsed.secure().unwrap_or(false);
+
+    if secure && !secure_origin {
+        debug!("Rejecting secure cookie {} from insecure origin {}", name, request_url);
+        return None;
+    }
 
     // Determine domain
-    let domain = if let Some(domain_attr) = parsed.domain() {
-        // Domain attribute present - this is a suffix match
-        let domain = domain_attr.trim_start_matches('.').to_lowercase();
-
-        // Reject single-component domains (TLDs) except localhost
-        if is_single_component_domain(&domain) && !is_localhost(&domain) {
-            debug!("Rejecting cookie with single-component domain: {}", domain);
+    let domain_attr = parsed.domain().map(|d| d.trim_start_matches('.').to_lowercase());
+    let domain = match domain_attr.as_deref() {
+        None | Some("") => CookieDomain::HostOnly(host.clone()),
+        // A cookie for a public suffix is only allowed for that exact host, and then it's
+        // host-only, so it can't be shared with every site under the suffix
+        Some(domain) if is_public_suffix(domain) => {
+            if domain != host {
+                debug!("Rejecting cookie {} for public suffix {}", name, domain);
+                return None;
+            }
+            CookieDomain::HostOnly(host.clone())
+        }
+        Some(domain) if !domain_matches(&host, domain) => {
+            debug!("Rejecting cookie {} for domain {} from host {}", name, domain, host);
             return None;
         }
+        Some(domain) if is_ip_address(&host) => CookieDomain::HostOnly(domain.to_string()),
+        Some(domain) => CookieDomain::Suffix(domain.to_string()),
+    };
 
-        CookieDomain::Suffix(domain)
-    } else {
-        // No domain attribute - host-only cookie
-        CookieDomain::HostOnly(request_url.host_str().unwrap_or("").to_lowercase())
+    // Determine path
+    let path = match parsed.path() {
+        Some(path_attr) if path_attr.starts_with('/') => (path_attr.to_string(), true),
+        // Default path is the directory of the request URI
+        _ => (default_cookie_path(request_url.path()), false),
     };
 
-    // Determine expiration
+    // Prefixes guarantee how the cookie was set (RFC 6265bis section 4.1.3)
+    let lower_name = name.to_lowercase();
+    if lower_name.starts_with("__secure-") && !secure {
+        debug!("Rejecting cookie {} without the Secure attribute", name);
+        return None;
+    }
+    if lower_name.starts_with("__host-")
+        && !(secure && matches!(domain_attr.as_deref(), None | Some("")) && path.0 == "/")
+    {
+        debug!("Rejecting cookie {} that isn't secure, host-only, for path /", name);
+        return None;
+    }
+
+    let same_site = match parsed.same_site() {
+        Some(cookie::SameSite::Strict) => CookieSameSite::Strict,
+        Some(cookie::SameSite::Lax) => CookieSameSite::Lax,
+        Some(cookie::SameSite::None) => CookieSameSite::None,
+        None => CookieSameSite::Unspecified,
+    };
+    if same_site == CookieSameSite::None && !secure {
+        debug!("Rejecting cookie {} with SameSite=None without the Secure attribute", name);
+        return None;
+    }
+
+    // Determine expiration. Max-Age takes precedence over Expires
     let expires = if let Some(max_age) = parsed.max_age() {
-        let duration = Duration::from_secs(max_age.whole_seconds().max(0) as u64);
-        let expiry = SystemTime::now() + duration;
+        let expiry = if max_age.is_positive() {
+            SystemTime::now() + Duration::from_secs(max_age.whole_seconds() as u64)
+        } else {
+            UNIX_EPOCH
+        };
         let expiry_secs = expiry.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
         CookieExpires::AtUtc(format!("{}", expiry_secs))
     } else if let Some(expires_time) = parsed.expires() {
@@ -176,16 +261,16 @@ fn parse_set_cookie(header_value: &str, request_url: &Url) -> Option<Cookie> {
         CookieExpires::SessionEnd
     };
 
-    // Determine path
-    let path = if let Some(path_attr) = parsed.path() {
-        (path_attr.to_string(), true)
-    } else {
-        // Default path is the directory of the request URI
-        let default_path = default_cookie_path(request_url.path());
-        (default_path, false)
-    };
-
-    Some(Cookie { raw_cookie, domain, expires, path })
+    Some(Cookie {
+        name,
+        value: parsed.value().to_string(),
+        domain,
+        expires,
+        path,
+        secure,
+        http_only: parsed.http_only().unwrap_or(false),
+        same_site,
+    })
 }
 
 /// Get the default cookie path from a request path (RFC 6265 Section 5.1.4)
@@ -221,12 +306,14 @@ fn path_matches(request_path: &str, cookie_path: &str) -> bool {
     false
 }
 
-/// Check if two cookies match (same name and domain)
-fn cookies_match(a: &Cookie, b: &Cookie) -> bool {
-    let name_a = parse_cookie_name_value(&a.raw_cookie).map(|(n, _)| n);
-    let name_b = parse_cookie_name_value(&b.raw_cookie).map(|(n, _)| n);
+/// Check if a host is the domain or one of its subdomains (RFC 6265 Section 5.1.3)
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || (host.ends_with(&format!(".{}", domain)) && !is_ip_address(host))
+}
 
-    if name_a != name_b {
+/// Check if two cookies match (same name, domain and path)
+fn cookies_match(a: &Cookie, b: &Cookie) -> bool {
+    if a.name != b.name || a.path.0 != b.path.0 {
         return false;
     }
 
@@ -242,6 +329,27 @@ fn cookies_match(a: &Cookie, b: &Cookie) -> bool {
     }
 }
 
+/// Check if either cookie domain matches the other
+fn domains_overlap(a: &CookieDomain, b: &CookieDomain) -> bool {
+    let domain = |d: &CookieDomain| match d {
+        CookieDomain::HostOnly(d) | CookieDomain::Suffix(d) => Some(d.to_lowercase()),
+        CookieDomain::NotPresent | CookieDomain::Empty => None,
+    };
+    match (domain(a), domain(b)) {
+        (Some(a), Some(b)) => domain_matches(&a, &b) || domain_matches(&b, &a),
+        _ => false,
+    }
+}
+
+fn is_expired(cookie: &Cookie, now: &SystemTime) -> bool {
+    match &cookie.expires {
+        CookieExpires::AtUtc(expiry_str) => {
+            parse_cookie_date(expiry_str).is_ok_and(|expiry| expiry <= *now)
+        }
+        CookieExpires::SessionEnd => false,
+    }
+}
+
 /// Parse a cookie date string (Unix timestamp in our format)
 fn parse_cookie_date(date_str: &str) -> Result<SystemTime, ()> {
     let timestamp: i64 = date_str.parse().map_err(|_| ())?;
@@ -249,19 +357,43 @@ fn parse_cookie_date(date_str: &str) -> Result<SystemTime, ()> {
     Ok(UNIX_EPOCH + duration)
 }
 
-/// Check if a domain is a single-component domain (TLD)
-/// e.g., "com", "org", "net" - domains without any dots
-fn is_single_component_domain(domain: &str) -> bool {
-    // Empty or only dots
-    let trimmed = domain.trim_matches('.');
-    if trimmed.is_empty() {
-        return true;
-    }
-    // IPv6 addresses use colons, not dots - don't consider them single-component
-    if domain.contains(':') {
+/// The lowercase host of a URL, without the brackets of IPv6 addresses
+fn canonical_host(url: &Url) -> Option<String> {
+    Some(url.host_str()?.trim_start_matches('[').trim_end_matches(']').to_lowercase())
+}
+
+fn is_ip_address(host: &str) -> bool {
+    host.parse::<std::net::IpAddr>().is_ok()
+}
+
+/// Check if a domain is on the Public Suffix List, like `com`, `co.uk` or `github.io`. Unlisted
+/// top-level domains count as public suffixes too, except localhost.
+fn is_public_suffix(domain: &str) -> bool {
+    if is_localhost(domain) || is_ip_address(domain) {
         return false;
     }
-    !trimmed.contains('.')
+    psl::suffix_str(domain) == Some(domain)
+}
+
+/// The registrable domain of the URL's host, e.g. `example.co.uk` for `api.example.co.uk`.
+/// Requests are same-site when these are equal.
+fn site(url: &Url) -> Option<String> {
+    match url.host()? {
+        Host::Domain(domain) => {
+            let domain = domain.to_lowercase();
+            Some(psl::domain_str(&domain).unwrap_or(&domain).to_string())
+        }
+        _ => canonical_host(url),
+    }
+}
+
+/// Check if cookies can be sent to and set by a URL with the `Secure` attribute. Like in
+/// browsers, localhost is trusted too.
+fn is_secure_origin(url: &Url) -> bool {
+    matches!(url.scheme(), "https" | "wss")
+        || canonical_host(url).is_some_and(|host| {
+            is_localhost(&host) || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+        })
 }
 
 /// Check if a domain is localhost or a localhost variant
@@ -278,20 +410,6 @@ fn is_localhost(domain: &str) -> bool {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_parse_cookie_name_value() {
-        assert_eq!(
-            parse_cookie_name_value("session=abc123"),
-            Some(("session".to_string(), "abc123".to_string()))
-        );
-        assert_eq!(
-            parse_cookie_name_value("name=value; Path=/; HttpOnly"),
-            Some(("name".to_string(), "value".to_string()))
-        );
-        assert_eq!(parse_cookie_name_value("empty="), Some(("empty".to_string(), "".to_string())));
-        assert_eq!(parse_cookie_name_value(""), None);
-    }
-
     #[test]
     fn test_path_matches() {
         assert!(path_matches("/", "/"));
@@ -387,30 +505,6 @@ mod tests {
         assert_eq!(store.get_all_cookies().len(), 1);
     }
 
-    #[test]
-    fn test_is_single_component_domain() {
-        // Single-component domains (TLDs)
-        assert!(is_single_component_domain("com"));
-        assert!(is_single_component_domain("org"));
-        assert!(is_single_component_domain("net"));
-        assert!(is_single_component_domain("localhost")); // Still single-component, but allowed separately
-
-        // Multi-component domains
-        assert!(!is_single_component_domain("example.com"));
-        assert!(!is_single_component_domain("sub.example.com"));
-        assert!(!is_single_component_domain("co.uk"));
-
-        // Edge cases
-        assert!(is_single_component_domain("")); // Empty is treated as single-component
-        assert!(is_single_component_domain(".")); // Only dots
-        assert!(is_single_component_domain("..")); // Only dots
-
-        // IPv6 addresses (have colons, not dots)
-        assert!(!is_single_component_domain("::1")); // IPv6 localhost
-        assert!(!is_single_component_domain("[::1]")); // Bracketed IPv6
-        assert!(!is_single_component_domain("2001:db8::1")); // IPv6 address
-    }
-
     #[test]
     fn test_is_localhost() {
         // Localhost variants
@@ -481,4 +575,157 @@ mod tests {
         assert_eq!(store.get_all_cookies().len(), 1);
         assert!(store.get_cookie_header(&url).is_some());
     }
+
+    #[test]
+    fn test_reject_public_suffix_cookies() {
+        let store = CookieStore::new();
+        let url = Url::parse("https://shop.example.co.uk/").unwrap();
+        store.store_cookies_from_response(&url, &["bad=cookie; Domain=co.uk".to_string()]);
+        assert_eq!(store.get_all_cookies().len(), 0);
+
+        let url = Url::parse("https://user.github.io/").unwrap();
+        store.store_cookies_from_response(&url, &["bad=cookie; Domain=github.io".to_string()]);
+        assert_eq!(store.get_all_cookies().len(), 0);
+
+        // A public suffix can set a cookie for itself, but only host-only
+        let url = Url::parse("https://github.io/").unwrap();
+        store.store_cookies_from_response(&url, &["ok=cookie; Domain=github.io".to_string()]);
+        let cookies = store.get_all_cookies();
+        assert!(matches!(&cookies[0].domain, CookieDomain::HostOnly(d) if d == "github.io"));
+        assert!(store.get_cookie_header(&Url::parse("https://user.github.io/").unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_reject_cookies_for_other_domains() {
+        let store = CookieStore::new();
+        let url = Url::parse("https://example.com/").unwrap();
+        store.store_cookies_from_response(&url, &["bad=cookie; Domain=other.com".to_string()]);
+        store
+            .store_cookies_from_response(&url, &["bad=cookie; Domain=sub.example.com".to_string()]);
+        assert_eq!(store.get_all_cookies().len(), 0);
+    }
+
+    #[test]
+    fn test_secure_cookies() {
+        let store = CookieStore::new();
+        let https_url = Url::parse("https://example.com/").unwrap();
+        let http_url = Url::parse("http://example.com/").unwrap();
+
+        // Secure cookies can't be set over plain HTTP
+        store.store_cookies_from_response(&http_url, &["a=1; Secure".to_string()]);
+        assert_eq!(store.get_all_cookies().len(), 0);
+
+        // And they're only sent over HTTPS
+        store.store_cookies_from_response(&https_url, &["a=1; Secure; HttpOnly".to_string()]);
+        assert_eq!(store.get_cookie_header(&https_url), Some("a=1".to_string()));
+        assert_eq!(store.get_cookie_header(&http_url), None);
+
+        // Plain HTTP can't overwrite them either
+        store.store_cookies_from_response(&http_url, &["a=2".to_string()]);
+        assert_eq!(store.get_cookie_header(&https_url), Some("a=1".to_string()));
+
+        let cookies = store.get_all_cookies();
+        assert!(cookies[0].secure);
+        assert!(cookies[0].http_only);
+
+        // Localhost is trusted like HTTPS
+        let local_url = Url::parse("http://localhost:3000/").unwrap();
+        store.store_cookies_from_response(&local_url, &["b=1; Secure".to_string()]);
+        assert_eq!(store.get_cookie_header(&local_url), Some("b=1".to_string()));
+    }
+
+    #[test]
+    fn test_cookie_prefixes() {
+        let store = CookieStore::new();
+        let url = Url::parse("https://example.com/app/").unwrap();
+        store.store_cookies_from_response(
+            &url,
+            &[
+                "__Secure-a=1".to_string(),
+                "__Host-b=1; Secure".to_string(),
+                "__Host-c=1; Secure; Path=/; Domain=example.com".to_string(),
+                "__Secure-ok=1; Secure".to_string(),
+                "__Host-ok=1; Secure; Path=/".to_string(),
+            ],
+        );
+        let mut names: Vec<_> = store.get_all_cookies().into_iter().map(|c| c.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["__Host-ok", "__Secure-ok"]);
+    }
+
+    #[test]
+    fn test_same_site_cookies() {
+        let store = CookieStore::new();
+        let url = Url::parse("https://example.com/").unwrap();
+        store.store_cookies_from_response(
+            &url,
+            &[
+                "strict=1; SameSite=Strict".to_string(),
+                "lax=1; SameSite=Lax".to_string(),
+                "none=1; SameSite=None; Secure".to_string(),
+                "default=1".to_string(),
+                // SameSite=None requires Secure
+                "rejected=1; SameSite=None".to_string(),
+            ],
+        );
+        assert_eq!(store.get_all_cookies().len(), 4);
+
+        // Same-site, including from a subdomain
+        let initiator = Url::parse("https://api.example.com/").unwrap();
+        assert_eq!(
+            store.get_cookie_header_from(&url, &initiator, "POST"),
+            Some("strict=1; lax=1; none=1; default=1".to_string())
+        );
+
+        // Cross-site requests only get Lax cookies when the method is safe
+        let initiator = Url::parse("https://other.com/").unwrap();
+        assert_eq!(
+            store.get_cookie_header_from(&url, &initiator, "GET"),
+            Some("lax=1; none=1; default=1".to_string())
+        );
+        assert_eq!(
+            store.get_cookie_header_from(&url, &initiator, "POST"),
+            Some("none=1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cookie_expiration() {
+        let store = CookieStore::new();
+        let url = Url::parse("https://example.com/").unwrap();
+
+        // Max-Age takes precedence over Expires
+        store.store_cookies_from_response(
+            &url,
+            &["a=1; Max-Age=3600; Expires=Thu, 01 Jan 1970 00:00:00 GMT".to_string()],
+        );
+        assert_eq!(store.get_cookie_header(&url), Some("a=1".to_string()));
+
+        // Expired cookies delete the cookie they replace
+        store.store_cookies_from_response(&url, &["a=1; Max-Age=0".to_string()]);
+        assert_eq!(store.get_all_cookies().len(), 0);
+
+        store.store_cookies_from_response(&url, &["b=1".to_string()]);
+        store.store_cookies_from_response(
+            &url,
+            &["b=1; Expires=Thu, 01 Jan 1970 00:00:00 GMT".to_string()],
+        );
+        assert_eq!(store.get_all_cookies().len(), 0);
+    }
+
+    #[test]
+    fn test_cookie_order() {
+        let store = CookieStore::new();
+        let url = Url::parse("https://example.com/api/v1").unwrap();
+        store.store_cookies_from_response(
+            &url,
+            &[
+                "a=1; Path=/".to_string(),
+                "b=1; Path=/api".to_string(),
+                "c=1; Path=/".to_string(),
+            ],
+        );
+        // Longer paths go first
+        assert_eq!(store.get_cookie_header(&url), Some("b=1; a=1; c=1".to_string()));
+    }
 }
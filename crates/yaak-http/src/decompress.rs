@@ -1,11 +1,14 @@
 use crate::error::{Error, Result};
 use async_compression::tokio::bufread::{
     BrotliDecoder, DeflateDecoder as AsyncDeflateDecoder, GzipDecoder,
-    ZstdDecoder as AsyncZstdDecoder,
+    ZlibDecoder as AsyncZlibDecoder, ZstdDecoder as AsyncZstdDecoder,
 };
-use flate2::read::{DeflateDecoder, GzDecoder};
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use log::warn;
 use std::io::Read;
-use tokio::io::{AsyncBufRead, AsyncRead};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt, BufReader, ReadBuf};
 
 /// Supported compression encodings
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,6 +32,27 @@ impl ContentEncoding {
             _ => ContentEncoding::Identity,
         }
     }
+
+    /// Parse the codings of one or more Content-Encoding header values, in the order they were
+    /// applied (e.g. `gzip, br` is gzip, then brotli). Identity codings are dropped. Returns an
+    /// empty list when any coding is unknown, because the body can't be decoded past it.
+    pub fn chain_from_headers<'a>(values: impl IntoIterator<Item = &'a str>) -> Vec<Self> {
+        let mut encodings = Vec::new();
+        for token in values.into_iter().flat_map(|v| v.split(',')) {
+            let token = token.trim();
+            if token.is_empty() || token.eq_ignore_ascii_case("identity") {
+                continue;
+            }
+            match ContentEncoding::from_header(Some(token)) {
+                ContentEncoding::Identity => {
+                    warn!("Unsupported Content-Encoding {token}, leaving body as is");
+                    return Vec::new();
+                }
+                encoding => encodings.push(encoding),
+            }
+        }
+        encodings
+    }
 }
 
 /// Result of decompression, containing both the decompressed data and size info
@@ -39,18 +63,22 @@ pub struct DecompressResult {
     pub decompressed_size: u64,
 }
 
-/// Decompress data based on the Content-Encoding.
-/// Returns the original data unchanged if encoding is Identity or unknown.
-pub fn decompress(data: Vec<u8>, encoding: ContentEncoding) -> Result<DecompressResult> {
+/// Decompress data based on the Content-Encoding codings, in the order they were applied.
+/// The codings are undone from the last one. Returns the original data unchanged if there
+/// are none.
+pub fn decompress(data: Vec<u8>, encodings: &[ContentEncoding]) -> Result<DecompressResult> {
     let compressed_size = data.len() as u64;
 
-    let decompressed = match encoding {
-        ContentEncoding::Identity => data,
-        ContentEncoding::Gzip => decompress_gzip(&data)?,
-        ContentEncoding::Deflate => decompress_deflate(&data)?,
-        ContentEncoding::Brotli => decompress_brotli(&data)?,
-        ContentEncoding::Zstd => decompress_zstd(&data)?,
-    };
+    let mut decompressed = data;
+    for encoding in encodings.iter().rev() {
+        decompressed = match encoding {
+            ContentEncoding::Identity => decompressed,
+            ContentEncoding::Gzip => decompress_gzip(&decompressed)?,
+            ContentEncoding::Deflate => decompress_deflate(&decompressed)?,
+            ContentEncoding::Brotli => decompress_brotli(&decompressed)?,
+            ContentEncoding::Zstd => decompress_zstd(&decompressed)?,
+        };
+    }
 
     let decompressed_size = decompressed.len() as u64;
 
@@ -66,15 +94,30 @@ fn decompress_gzip(data: &[u8]) -> Result<Vec<u8>> {
     Ok(decompressed)
 }
 
+/// `deflate` is the zlib format (RFC 9110), but some servers send raw DEFLATE, so that's tried
+/// when the data isn't zlib
 fn decompress_deflate(data: &[u8]) -> Result<Vec<u8>> {
-    let mut decoder = DeflateDecoder::new(data);
     let mut decompressed = Vec::new();
-    decoder
+    if ZlibDecoder::new(data).read_to_end(&mut decompressed).is_ok() {
+        return Ok(decompressed);
+    }
+
+    decompressed.clear();
+    DeflateDecoder::new(data)
         .read_to_end(&mut decompressed)
         .map_err(|e| Error::DecompressionError(format!("deflate decompression failed: {}", e)))?;
     Ok(decompressed)
 }
 
+/// Whether data starts with a zlib header: the deflate method, and a check value that makes
+/// the first two bytes a multiple of 31
+fn is_zlib_header(data: &[u8]) -> bool {
+    match data {
+        [cmf, flg, ..] => cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0,
+        _ => false,
+    }
+}
+
 fn decompress_brotli(data: &[u8]) -> Result<Vec<u8>> {
     let mut decompressed = Vec::new();
     brotli::BrotliDecompress(&mut std::io::Cursor::new(data), &mut decompressed)
@@ -88,20 +131,84 @@ fn decompress_zstd(data: &[u8]) -> Result<Vec<u8>> {
 }
 
 /// Create a streaming decompressor that wraps an async reader.
-/// Returns an AsyncRead that decompresses data on-the-fly.
+/// Returns an AsyncRead that decompresses data on-the-fly, undoing the codings from the last
+/// one applied.
 pub fn streaming_decoder<R: AsyncBufRead + Unpin + Send + 'static>(
+    reader: R,
+    encodings: &[ContentEncoding],
+) -> Box<dyn AsyncRead + Unpin + Send> {
+    let mut decoders = encodings.iter().rev();
+    let mut decoder = match decoders.next() {
+        Some(encoding) => single_decoder(reader, *encoding),
+        None => return Box::new(reader),
+    };
+    for encoding in decoders {
+        decoder = single_decoder(BufReader::new(decoder), *encoding);
+    }
+    decoder
+}
+
+fn single_decoder<R: AsyncBufRead + Unpin + Send + 'static>(
     reader: R,
     encoding: ContentEncoding,
 ) -> Box<dyn AsyncRead + Unpin + Send> {
     match encoding {
         ContentEncoding::Identity => Box::new(reader),
         ContentEncoding::Gzip => Box::new(GzipDecoder::new(reader)),
-        ContentEncoding::Deflate => Box::new(AsyncDeflateDecoder::new(reader)),
+        ContentEncoding::Deflate => Box::new(StreamingDeflateDecoder::new(reader)),
         ContentEncoding::Brotli => Box::new(BrotliDecoder::new(reader)),
         ContentEncoding::Zstd => Box::new(AsyncZstdDecoder::new(reader)),
     }
 }
 
+/// Decodes a `deflate` stream as zlib or raw DEFLATE, picked by the first two bytes of the
+/// stream. Reads may return less than that, so the header is collected before choosing.
+enum StreamingDeflateDecoder<R> {
+    Detecting { reader: Option<R>, header: Vec<u8> },
+    Decoding(Box<dyn AsyncRead + Unpin + Send>),
+}
+
+impl<R> StreamingDeflateDecoder<R> {
+    fn new(reader: R) -> Self {
+        Self::Detecting { reader: Some(reader), header: Vec::with_capacity(2) }
+    }
+}
+
+impl<R: AsyncBufRead + Unpin + Send + 'static> AsyncRead for StreamingDeflateDecoder<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        loop {
+            let (reader, header) = match &mut *self {
+                Self::Decoding(decoder) => return Pin::new(decoder).poll_read(cx, buf),
+                Self::Detecting { reader, header } => (reader, header),
+            };
+            let inner = reader.as_mut().expect("The reader is only taken to start decoding");
+            while header.len() < 2 {
+                let available = ready!(Pin::new(&mut *inner).poll_fill_buf(cx))?;
+                if available.is_empty() {
+                    break;
+                }
+                let n = available.len().min(2 - header.len());
+                header.extend_from_slice(&available[..n]);
+                Pin::new(&mut *inner).consume(n);
+            }
+
+            let zlib = is_zlib_header(header);
+            let reader = reader.take().expect("The reader is only taken to start decoding");
+            let header = std::io::Cursor::new(std::mem::take(header));
+            let reader = AsyncReadExt::chain(header, reader);
+            *self = Self::Decoding(if zlib {
+                Box::new(AsyncZlibDecoder::new(reader))
+            } else {
+                Box::new(AsyncDeflateDecoder::new(reader))
+            });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,10 +229,20 @@ mod tests {
         assert_eq!(ContentEncoding::from_header(None), ContentEncoding::Identity);
     }
 
+    #[test]
+    fn test_content_encoding_chain_from_headers() {
+        use ContentEncoding::*;
+        assert_eq!(ContentEncoding::chain_from_headers(["gzip, br"]), vec![Gzip, Brotli]);
+        assert_eq!(ContentEncoding::chain_from_headers(["deflate", "ZSTD"]), vec![Deflate, Zstd]);
+        assert_eq!(ContentEncoding::chain_from_headers(["identity, gzip"]), vec![Gzip]);
+        assert!(ContentEncoding::chain_from_headers(["gzip, compress"]).is_empty());
+        assert!(ContentEncoding::chain_from_headers([]).is_empty());
+    }
+
     #[test]
     fn test_decompress_identity() {
         let data = b"hello world".to_vec();
-        let result = decompress(data.clone(), ContentEncoding::Identity).unwrap();
+        let result = decompress(data.clone(), &[]).unwrap();
         assert_eq!(result.data, data);
         assert_eq!(result.compressed_size, 11);
         assert_eq!(result.decompressed_size, 11);
@@ -139,7 +256,7 @@ mod tests {
         encoder.write_all(original).unwrap();
         let compressed = encoder.finish().unwrap();
 
-        let result = decompress(compressed.clone(), ContentEncoding::Gzip).unwrap();
+        let result = decompress(compressed.clone(), &[ContentEncoding::Gzip]).unwrap();
         assert_eq!(result.data, original);
         assert_eq!(result.compressed_size, compressed.len() as u64);
         assert_eq!(result.decompressed_size, original.len() as u64);
@@ -153,12 +270,46 @@ mod tests {
         encoder.write_all(original).unwrap();
         let compressed = encoder.finish().unwrap();
 
-        let result = decompress(compressed.clone(), ContentEncoding::Deflate).unwrap();
+        let result = decompress(compressed.clone(), &[ContentEncoding::Deflate]).unwrap();
         assert_eq!(result.data, original);
         assert_eq!(result.compressed_size, compressed.len() as u64);
         assert_eq!(result.decompressed_size, original.len() as u64);
     }
 
+    #[test]
+    fn test_decompress_deflate_zlib() {
+        // zlib.compress(b"hello world"), which is what servers send for `deflate`
+        let zlib = vec![
+            0x78, 0x9c, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0x28, 0xcf, 0x2f, 0xca, 0x49, 0x01,
+            0x00, 0x1a, 0x0b, 0x04, 0x5d,
+        ];
+        assert!(is_zlib_header(&zlib));
+        let result = decompress(zlib, &[ContentEncoding::Deflate]).unwrap();
+        assert_eq!(result.data, b"hello world");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_decoder_deflate_zlib() {
+        use tokio::io::AsyncReadExt;
+
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"hello world").unwrap();
+        let zlib = encoder.finish().unwrap();
+
+        let encodings = [ContentEncoding::Deflate];
+        let mut decoder = streaming_decoder(std::io::Cursor::new(zlib.clone()), &encodings);
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed).await.unwrap();
+        assert_eq!(decompressed, b"hello world");
+
+        // The header is detected even when it arrives a byte at a time
+        let reader = BufReader::with_capacity(1, std::io::Cursor::new(zlib));
+        let mut decoder = streaming_decoder(reader, &encodings);
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed).await.unwrap();
+        assert_eq!(decompressed, b"hello world");
+    }
+
     #[test]
     fn test_decompress_brotli() {
         // Compress some data with brotli
@@ -168,7 +319,7 @@ mod tests {
         writer.write_all(original).unwrap();
         drop(writer);
 
-        let result = decompress(compressed.clone(), ContentEncoding::Brotli).unwrap();
+        let result = decompress(compressed.clone(), &[ContentEncoding::Brotli]).unwrap();
         assert_eq!(result.data, original);
         assert_eq!(result.compressed_size, compressed.len() as u64);
         assert_eq!(result.decompressed_size, original.len() as u64);
@@ -180,9 +331,44 @@ mod tests {
         let original = b"hello world, this is a test of zstd compression";
         let compressed = zstd::stream::encode_all(std::io::Cursor::new(original), 3).unwrap();
 
-        let result = decompress(compressed.clone(), ContentEncoding::Zstd).unwrap();
+        let result = decompress(compressed.clone(), &[ContentEncoding::Zstd]).unwrap();
         assert_eq!(result.data, original);
         assert_eq!(result.compressed_size, compressed.len() as u64);
         assert_eq!(result.decompressed_size, original.len() as u64);
     }
+
+    #[test]
+    fn test_decompress_chain() {
+        // Content-Encoding: gzip, zstd means gzip was applied first
+        let original = b"hello world, this is a test of stacked compression";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(original).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        let compressed = zstd::stream::encode_all(std::io::Cursor::new(gzipped), 3).unwrap();
+
+        let encodings = [ContentEncoding::Gzip, ContentEncoding::Zstd];
+        let result = decompress(compressed.clone(), &encodings).unwrap();
+        assert_eq!(result.data, original);
+        assert_eq!(result.compressed_size, compressed.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_decoder_chain() {
+        use tokio::io::AsyncReadExt;
+
+        let original = b"hello world, this is a test of stacked streaming compression";
+        let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(original).unwrap();
+        let deflated = encoder.finish().unwrap();
+        let mut compressed = Vec::new();
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 4, 22);
+        writer.write_all(&deflated).unwrap();
+        drop(writer);
+
+        let encodings = [ContentEncoding::Deflate, ContentEncoding::Brotli];
+        let mut decoder = streaming_decoder(std::io::Cursor::new(compressed), &encodings);
+        let mut decompressed = Vec::new();
+        decoder.read_to_end(&mut decompressed).await.unwrap();
+        assert_eq!(decompressed, original);
+    }
 }
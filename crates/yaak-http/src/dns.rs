@@ -1,61 +1,270 @@
+//! DNS resolution for the HTTP client. The workspace's overrides are checked first, then
+//! `*.localhost` hostnames resolve to the loopback addresses, and everything else goes to the
+//! workspace's DNS server, or the system resolver when there isn't one.
+
+use crate::error::{Error, Result};
+use crate::net::family_name;
 use crate::sender::HttpResponseEvent;
+use crate::timing::mark_dns_resolved;
+use hickory_resolver::TokioResolver;
+use hickory_resolver::config::{NameServerConfig, NameServerConfigGroup, ResolverConfig};
+use hickory_resolver::name_server::TokioConnectionProvider;
+use hickory_resolver::proto::xfer::Protocol;
 use hyper_util::client::legacy::connect::dns::{
     GaiResolver as HyperGaiResolver, Name as HyperName,
 };
 use log::info;
 use reqwest::dns::{Addrs, Name, Resolve, Resolving};
-use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{OnceCell, RwLock, mpsc};
 use tower_service::Service;
-use yaak_models::models::DnsOverride;
+use url::Url;
+use yaak_models::models::{DnsOverride, IpFamily};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+tokio::task_local! {
+    /// The override the sender matched for the request being sent. The resolver only sees
+    /// hostnames, so overrides for a port have to be matched by the sender.
+    static REQUEST_OVERRIDE: Option<ResolvedOverride>;
+}
+
+/// Run a request with the override the sender matched for its host and port
+pub(crate) async fn with_override<F: Future>(o: Option<ResolvedOverride>, f: F) -> F::Output {
+    REQUEST_OVERRIDE.scope(o, f).await
+}
 
 /// Stores resolved addresses for a hostname override
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct ResolvedOverride {
+    pattern: HostPattern,
+    pub port: Option<u16>,
     pub ipv4: Vec<Ipv4Addr>,
     pub ipv6: Vec<Ipv6Addr>,
+    pub target_port: Option<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum HostPattern {
+    Exact(String),
+    /// The suffix of a wildcard, including the dot, like `.example.com` for `*.example.com`
+    Wildcard(String),
+}
+
+impl ResolvedOverride {
+    fn from_override(o: &DnsOverride) -> Option<Self> {
+        let hostname = o.hostname.trim().to_lowercase();
+        let pattern = match hostname.strip_prefix('*') {
+            Some(suffix) if suffix.starts_with('.') => HostPattern::Wildcard(suffix.to_string()),
+            _ => HostPattern::Exact(hostname),
+        };
+
+        let ipv4: Vec<Ipv4Addr> =
+            o.ipv4.iter().filter_map(|s| s.trim().parse::<Ipv4Addr>().ok()).collect();
+
+        let ipv6: Vec<Ipv6Addr> =
+            o.ipv6.iter().filter_map(|s| s.trim().parse::<Ipv6Addr>().ok()).collect();
+
+        // Only keep overrides that change where connections go
+        if ipv4.is_empty() && ipv6.is_empty() && o.target_port.is_none() {
+            return None;
+        }
+
+        Some(Self { pattern, port: o.port, ipv4, ipv6, target_port: o.target_port })
+    }
+
+    fn matches_host(&self, host: &str) -> bool {
+        match &self.pattern {
+            HostPattern::Exact(hostname) => host == hostname,
+            HostPattern::Wildcard(suffix) => host.ends_with(suffix.as_str()),
+        }
+    }
+
+    /// Whether the override applies to a host and port. Without a port, only overrides for
+    /// any port apply.
+    fn matches(&self, host: &str, port: Option<u16>) -> bool {
+        let port_matches = match self.port {
+            Some(p) => port == Some(p),
+            None => true,
+        };
+        port_matches && self.matches_host(host)
+    }
+
+    /// Exact hostnames rank above wildcards, longer wildcards above shorter ones, and
+    /// overrides for a port above those for any port
+    fn rank(&self) -> (bool, usize, bool) {
+        match &self.pattern {
+            HostPattern::Exact(_) => (true, 0, self.port.is_some()),
+            HostPattern::Wildcard(suffix) => (false, suffix.len(), self.port.is_some()),
+        }
+    }
+
+    fn addresses(&self) -> Vec<SocketAddr> {
+        // Port 0 is fine; reqwest replaces it with the URL's explicit
+        // port or the scheme's default (80/443, etc.).
+        let ipv4 = self.ipv4.iter().map(|ip| SocketAddr::new(IpAddr::V4(*ip), 0));
+        let ipv6 = self.ipv6.iter().map(|ip| SocketAddr::new(IpAddr::V6(*ip), 0));
+        ipv4.chain(ipv6).collect()
+    }
+}
+
+/// A DNS server to resolve hostnames with instead of the system resolver
+#[derive(Debug, Clone, PartialEq)]
+pub enum DnsServer {
+    Udp(SocketAddr),
+    Tcp(SocketAddr),
+    /// DNS-over-HTTPS, like `https://cloudflare-dns.com/dns-query`
+    Https(Url),
+}
+
+impl DnsServer {
+    /// Parse a DNS server setting: an address like `1.1.1.1` or `[2606:4700::1111]:53` for UDP,
+    /// a `udp://` or `tcp://` URL, or an `https://` URL for DNS-over-HTTPS. Empty means the
+    /// system resolver.
+    pub fn parse(value: &str) -> Result<Option<Self>> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        let invalid = || Error::DnsError(format!("Invalid DNS server {value}"));
+
+        let (scheme, address) = value.split_once("://").unwrap_or(("udp", value));
+        let server = match scheme.to_lowercase().as_str() {
+            "https" => DnsServer::Https(Url::parse(value).map_err(|_| invalid())?),
+            "udp" => DnsServer::Udp(parse_socket_addr(address).ok_or_else(invalid)?),
+            "tcp" => DnsServer::Tcp(parse_socket_addr(address).ok_or_else(invalid)?),
+            _ => return Err(invalid()),
+        };
+        Ok(Some(server))
+    }
+
+    async fn build_resolver(&self) -> Result<TokioResolver> {
+        let name_servers = match self {
+            DnsServer::Udp(addr) => NameServerConfigGroup::from(vec![
+                NameServerConfig::new(*addr, Protocol::Udp),
+                // Truncated responses are retried over TCP
+                NameServerConfig::new(*addr, Protocol::Tcp),
+            ]),
+            DnsServer::Tcp(addr) => {
+                NameServerConfigGroup::from(vec![NameServerConfig::new(*addr, Protocol::Tcp)])
+            }
+            DnsServer::Https(url) => {
+                let host = url.host_str().unwrap_or_default().trim_matches(['[', ']']);
+                let port = url.port_or_known_default().unwrap_or(443);
+                // The server's own hostname is resolved by the system
+                let ips: Vec<IpAddr> = match host.parse::<IpAddr>() {
+                    Ok(ip) => vec![ip],
+                    Err(_) => tokio::net::lookup_host((host, port))
+                        .await
+                        .map_err(|e| Error::DnsError(format!("Failed to resolve {host}: {e}")))?
+                        .map(|a| a.ip())
+                        .collect(),
+                };
+                NameServerConfigGroup::from(https_name_servers(url, &ips))
+            }
+        };
+        let config = ResolverConfig::from_parts(None, vec![], name_servers);
+        Ok(TokioResolver::builder_with_config(config, TokioConnectionProvider::default()).build())
+    }
+}
+
+impl Display for DnsServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DnsServer::Udp(addr) => write!(f, "udp://{addr}"),
+            DnsServer::Tcp(addr) => write!(f, "tcp://{addr}"),
+            DnsServer::Https(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+/// Name servers for a DNS-over-HTTPS URL, whose host resolved to `ips`. Without a path, the
+/// standard `/dns-query` endpoint is used.
+fn https_name_servers(url: &Url, ips: &[IpAddr]) -> Vec<NameServerConfig> {
+    let host = url.host_str().unwrap_or_default().trim_matches(['[', ']']);
+    let port = url.port_or_known_default().unwrap_or(443);
+    let endpoint = match url.path() {
+        "/" => None,
+        path => Some(path.to_string()),
+    };
+    ips.iter()
+        .map(|ip| {
+            let mut config = NameServerConfig::new(SocketAddr::new(*ip, port), Protocol::Https);
+            config.tls_dns_name = Some(host.to_string());
+            config.http_endpoint = endpoint.clone();
+            config.trust_negative_responses = true;
+            config
+        })
+        .collect()
+}
+
+fn parse_socket_addr(address: &str) -> Option<SocketAddr> {
+    let address = address.trim_end_matches('/');
+    if let Ok(addr) = address.parse::<SocketAddr>() {
+        return Some(addr);
+    }
+    let ip = address.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().ok()?;
+    Some(SocketAddr::new(ip, 53))
+}
+
+/// A [`DnsServer`] along with its resolver, which is built on first use
+struct Upstream {
+    server: DnsServer,
+    resolver: OnceCell<TokioResolver>,
+}
+
+impl Upstream {
+    /// Resolve a hostname, returning its addresses and how many seconds they can be cached for
+    async fn lookup(&self, host: &str) -> Result<(Vec<SocketAddr>, u64)> {
+        let resolver = self.resolver.get_or_try_init(|| self.server.build_resolver()).await?;
+        let lookup = resolver
+            .lookup_ip(host)
+            .await
+            .map_err(|e| Error::DnsError(format!("Failed to resolve {host}: {e}")))?;
+        let ttl = lookup.valid_until().saturating_duration_since(Instant::now()).as_secs();
+        Ok((lookup.iter().map(|ip| SocketAddr::new(ip, 0)).collect(), ttl))
+    }
 }
 
 #[derive(Clone)]
 pub struct LocalhostResolver {
     fallback: HyperGaiResolver,
+    upstream: Option<Arc<Upstream>>,
     event_tx: Arc<RwLock<Option<mpsc::Sender<HttpResponseEvent>>>>,
-    overrides: Arc<HashMap<String, ResolvedOverride>>,
+    overrides: Arc<Vec<ResolvedOverride>>,
+    ip_family: IpFamily,
 }
 
 impl LocalhostResolver {
-    pub fn new(dns_overrides: Vec<DnsOverride>) -> Arc<Self> {
+    pub fn new(
+        dns_overrides: Vec<DnsOverride>,
+        dns_server: &str,
+        ip_family: IpFamily,
+    ) -> Result<Arc<Self>> {
         let resolver = HyperGaiResolver::new();
 
-        // Pre-parse DNS overrides into a lookup map
-        let mut overrides = HashMap::new();
-        for o in dns_overrides {
-            if !o.enabled {
-                continue;
-            }
-            let hostname = o.hostname.to_lowercase();
-
-            let ipv4: Vec<Ipv4Addr> =
-                o.ipv4.iter().filter_map(|s| s.parse::<Ipv4Addr>().ok()).collect();
+        // Pre-parse DNS overrides, skipping disabled and invalid ones
+        let overrides: Vec<ResolvedOverride> = dns_overrides
+            .iter()
+            .filter(|o| o.enabled)
+            .filter_map(ResolvedOverride::from_override)
+            .collect();
 
-            let ipv6: Vec<Ipv6Addr> =
-                o.ipv6.iter().filter_map(|s| s.parse::<Ipv6Addr>().ok()).collect();
+        let upstream = DnsServer::parse(dns_server)?
+            .map(|server| Arc::new(Upstream { server, resolver: OnceCell::new() }));
 
-            // Only add if at least one address is valid
-            if !ipv4.is_empty() || !ipv6.is_empty() {
-                overrides.insert(hostname, ResolvedOverride { ipv4, ipv6 });
-            }
-        }
-
-        Arc::new(Self {
+        Ok(Arc::new(Self {
             fallback: resolver,
+            upstream,
             event_tx: Arc::new(RwLock::new(None)),
             overrides: Arc::new(overrides),
-        })
+            ip_family,
+        }))
     }
 
     /// Set the event sender for the current request.
@@ -65,122 +274,221 @@ impl LocalhostResolver {
         let mut guard = self.event_tx.write().await;
         *guard = tx;
     }
+
+    /// The current request's event sender, shared with the connector so connection
+    /// timing events go to the same channel
+    pub(crate) fn event_sender(&self) -> Arc<RwLock<Option<mpsc::Sender<HttpResponseEvent>>>> {
+        self.event_tx.clone()
+    }
+
+    /// The override for connections to a host and port, if any
+    pub fn find_override(&self, host: &str, port: Option<u16>) -> Option<ResolvedOverride> {
+        let host = host.to_lowercase();
+        self.overrides.iter().filter(|o| o.matches(&host, port)).max_by_key(|o| o.rank()).cloned()
+    }
 }
 
 impl Resolve for LocalhostResolver {
     fn resolve(&self, name: Name) -> Resolving {
         let host = name.as_str().to_lowercase();
         let event_tx = self.event_tx.clone();
-        let overrides = self.overrides.clone();
+        let ip_family = self.ip_family;
 
         info!("DNS resolve called for: {}", host);
 
-        // Check for DNS override first
-        if let Some(resolved) = overrides.get(&host) {
+        // Check for DNS override first. The sender's match includes the port, so it wins.
+        let dns_override = REQUEST_OVERRIDE
+            .try_with(Clone::clone)
+            .ok()
+            .flatten()
+            .filter(|o| o.matches_host(&host))
+            .or_else(|| self.find_override(&host, None));
+        if let Some(addrs) = dns_override.map(|o| o.addresses()).filter(|a| !a.is_empty()) {
             log::debug!("DNS override found for: {}", host);
-            let hostname = host.clone();
-            let mut addrs: Vec<SocketAddr> = Vec::new();
-
-            // Add IPv4 addresses
-            for ip in &resolved.ipv4 {
-                addrs.push(SocketAddr::new(IpAddr::V4(*ip), 0));
-            }
-
-            // Add IPv6 addresses
-            for ip in &resolved.ipv6 {
-                addrs.push(SocketAddr::new(IpAddr::V6(*ip), 0));
-            }
-
-            let addresses: Vec<String> = addrs.iter().map(|a| a.ip().to_string()).collect();
-
             return Box::pin(async move {
-                // Emit DNS event for override
-                let guard = event_tx.read().await;
-                if let Some(tx) = guard.as_ref() {
-                    let _ = tx
-                        .send(HttpResponseEvent::DnsResolved {
-                            hostname,
-                            addresses,
-                            duration: 0,
-                            overridden: true,
-                        })
-                        .await;
-                }
-
-                Ok::<Addrs, Box<dyn std::error::Error + Send + Sync>>(Box::new(addrs.into_iter()))
+                let addrs = keep_family(ip_family, &host, addrs)?;
+                mark_dns_resolved();
+                let event = HttpResponseEvent::DnsResolved {
+                    hostname: host,
+                    addresses: addrs.iter().map(|a| a.ip().to_string()).collect(),
+                    duration: 0,
+                    overridden: true,
+                    source: "override".to_string(),
+                    ttl: None,
+                };
+                send_event(&event_tx, event).await;
+                Ok::<Addrs, BoxError>(Box::new(addrs.into_iter()))
             });
         }
 
         // Check for .localhost suffix
-        let is_localhost = host.ends_with(".localhost");
-        if is_localhost {
-            let hostname = host.clone();
-            // Port 0 is fine; reqwest replaces it with the URL's explicit
-            // port or the scheme's default (80/443, etc.).
+        if host.ends_with(".localhost") {
             let addrs: Vec<SocketAddr> = vec![
                 SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
                 SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 0),
             ];
-
-            let addresses: Vec<String> = addrs.iter().map(|a| a.ip().to_string()).collect();
-
             return Box::pin(async move {
-                // Emit DNS event for localhost resolution
-                let guard = event_tx.read().await;
-                if let Some(tx) = guard.as_ref() {
-                    let _ = tx
-                        .send(HttpResponseEvent::DnsResolved {
-                            hostname,
-                            addresses,
-                            duration: 0,
-                            overridden: false,
-                        })
-                        .await;
-                }
-
-                Ok::<Addrs, Box<dyn std::error::Error + Send + Sync>>(Box::new(addrs.into_iter()))
+                let addrs = keep_family(ip_family, &host, addrs)?;
+                mark_dns_resolved();
+                let event = HttpResponseEvent::DnsResolved {
+                    hostname: host,
+                    addresses: addrs.iter().map(|a| a.ip().to_string()).collect(),
+                    duration: 0,
+                    overridden: false,
+                    source: "localhost".to_string(),
+                    ttl: None,
+                };
+                send_event(&event_tx, event).await;
+                Ok::<Addrs, BoxError>(Box::new(addrs.into_iter()))
             });
         }
 
-        // Fall back to system DNS
+        // Use the workspace's DNS server, or fall back to system DNS
+        let upstream = self.upstream.clone();
         let mut fallback = self.fallback.clone();
         let name_str = name.as_str().to_string();
-        let hostname = host.clone();
 
         Box::pin(async move {
             let start = Instant::now();
 
-            let result = match HyperName::from_str(&name_str) {
-                Ok(n) => fallback.call(n).await,
-                Err(e) => return Err(Box::new(e) as Box<dyn std::error::Error + Send + Sync>),
+            let (addrs, source, ttl) = match upstream {
+                Some(upstream) => {
+                    let (addrs, ttl) = upstream.lookup(&name_str).await?;
+                    (addrs, upstream.server.to_string(), Some(ttl))
+                }
+                None => {
+                    let name = HyperName::from_str(&name_str)?;
+                    let addrs: Vec<SocketAddr> = fallback.call(name).await?.collect();
+                    (addrs, "system".to_string(), None)
+                }
             };
+            let addrs = keep_family(ip_family, &host, addrs)?;
 
             let duration = start.elapsed().as_millis() as u64;
+            mark_dns_resolved();
 
-            match result {
-                Ok(addrs) => {
-                    // Collect addresses for event emission
-                    let addr_vec: Vec<SocketAddr> = addrs.collect();
-                    let addresses: Vec<String> =
-                        addr_vec.iter().map(|a| a.ip().to_string()).collect();
-
-                    // Emit DNS event
-                    let guard = event_tx.read().await;
-                    if let Some(tx) = guard.as_ref() {
-                        let _ = tx
-                            .send(HttpResponseEvent::DnsResolved {
-                                hostname,
-                                addresses,
-                                duration,
-                                overridden: false,
-                            })
-                            .await;
-                    }
-
-                    Ok(Box::new(addr_vec.into_iter()) as Addrs)
-                }
-                Err(err) => Err(Box::new(err) as Box<dyn std::error::Error + Send + Sync>),
-            }
+            let event = HttpResponseEvent::DnsResolved {
+                hostname: host,
+                addresses: addrs.iter().map(|a| a.ip().to_string()).collect(),
+                duration,
+                overridden: false,
+                source,
+                ttl,
+            };
+            send_event(&event_tx, event).await;
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
         })
     }
 }
+
+/// Keep the addresses of the IP family connections are allowed over
+fn keep_family(
+    ip_family: IpFamily,
+    host: &str,
+    addrs: Vec<SocketAddr>,
+) -> std::result::Result<Vec<SocketAddr>, BoxError> {
+    let addrs: Vec<SocketAddr> = addrs.into_iter().filter(|a| ip_family.allows(&a.ip())).collect();
+    if addrs.is_empty() {
+        return Err(format!("No {} addresses for {host}", family_name(ip_family)).into());
+    }
+    Ok(addrs)
+}
+
+async fn send_event(
+    event_tx: &RwLock<Option<mpsc::Sender<HttpResponseEvent>>>,
+    event: HttpResponseEvent,
+) {
+    if let Some(tx) = event_tx.read().await.as_ref() {
+        let _ = tx.send(event).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dns_override(hostname: &str, port: Option<u16>, ipv4: &str) -> DnsOverride {
+        DnsOverride {
+            hostname: hostname.to_string(),
+            port,
+            ipv4: vec![ipv4.to_string()],
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_find_override() {
+        let resolver = LocalhostResolver::new(
+            vec![
+                dns_override("*.staging.internal", None, "10.0.0.1"),
+                dns_override("*.api.staging.internal", None, "10.0.0.2"),
+                dns_override("web.staging.internal", None, "10.0.0.3"),
+                dns_override("web.staging.internal", Some(8443), "10.0.0.4"),
+                DnsOverride { enabled: false, ..dns_override("off.internal", None, "10.0.0.5") },
+            ],
+            "",
+            IpFamily::Auto,
+        )
+        .unwrap();
+        let ip = |host: &str, port: Option<u16>| {
+            resolver.find_override(host, port).map(|o| o.ipv4[0].to_string())
+        };
+
+        assert_eq!(ip("a.staging.internal", None), Some("10.0.0.1".to_string()));
+        assert_eq!(ip("a.b.STAGING.internal", Some(443)), Some("10.0.0.1".to_string()));
+        assert_eq!(ip("staging.internal", None), None);
+        assert_eq!(ip("v1.api.staging.internal", None), Some("10.0.0.2".to_string()));
+        assert_eq!(ip("web.staging.internal", None), Some("10.0.0.3".to_string()));
+        assert_eq!(ip("web.staging.internal", Some(443)), Some("10.0.0.3".to_string()));
+        assert_eq!(ip("web.staging.internal", Some(8443)), Some("10.0.0.4".to_string()));
+        assert_eq!(ip("off.internal", None), None);
+    }
+
+    #[test]
+    fn test_parse_dns_server() {
+        let parse = |v: &str| DnsServer::parse(v).unwrap().map(|s| s.to_string());
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("1.1.1.1"), Some("udp://1.1.1.1:53".to_string()));
+        assert_eq!(parse("2606:4700::1111"), Some("udp://[2606:4700::1111]:53".to_string()));
+        assert_eq!(parse("tcp://10.0.0.2:5353"), Some("tcp://10.0.0.2:5353".to_string()));
+        assert_eq!(
+            parse("https://cloudflare-dns.com/dns-query"),
+            Some("https://cloudflare-dns.com/dns-query".to_string())
+        );
+        assert!(DnsServer::parse("dns.example.com").is_err());
+        assert!(DnsServer::parse("quic://1.1.1.1").is_err());
+    }
+
+    #[test]
+    fn test_https_name_servers() {
+        let ips = [IpAddr::from([1, 1, 1, 1])];
+        let url = Url::parse("https://dns.example.com:8443/custom/resolve").unwrap();
+        let servers = https_name_servers(&url, &ips);
+        assert_eq!(servers[0].socket_addr, "1.1.1.1:8443".parse().unwrap());
+        assert_eq!(servers[0].tls_dns_name.as_deref(), Some("dns.example.com"));
+        assert_eq!(servers[0].http_endpoint.as_deref(), Some("/custom/resolve"));
+
+        let url = Url::parse("https://dns.example.com").unwrap();
+        assert_eq!(https_name_servers(&url, &ips)[0].http_endpoint, None);
+    }
+
+    #[test]
+    fn test_keep_family() {
+        let addrs = vec![
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+            SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 0),
+        ];
+        let ips = |family| {
+            keep_family(family, "example.com", addrs.clone())
+                .map(|a| a.iter().map(|a| a.ip().to_string()).collect::<Vec<_>>())
+                .map_err(|e| e.to_string())
+        };
+        assert_eq!(ips(IpFamily::Auto), Ok(vec!["127.0.0.1".to_string(), "::1".to_string()]));
+        assert_eq!(ips(IpFamily::Ipv6), Ok(vec!["::1".to_string()]));
+
+        let err = keep_family(IpFamily::Ipv6, "example.com", addrs[..1].to_vec()).unwrap_err();
+        assert_eq!(err.to_string(), "No IPv6 addresses for example.com");
+    }
+}
@@ -18,13 +18,52 @@ pub enum Error {
     #[error("Timeout of {0:?} reached")]
     RequestTimeout(std::time::Duration),
 
+    #[error("Connect timeout of {0:?} reached")]
+    ConnectTimeout(std::time::Duration),
+
+    #[error("Read timeout of {0:?} reached waiting for the response body")]
+    ReadTimeout(std::time::Duration),
+
     #[error("Decompression error: {0}")]
     DecompressionError(String),
 
+    #[error("Compression error: {0}")]
+    CompressionError(String),
+
+    #[error("Proxy auto-config error: {0}")]
+    PacError(String),
+
+    #[error("Proxy error: {0}")]
+    ProxyError(String),
+
+    #[error("DNS error: {0}")]
+    DnsError(String),
+
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
+    #[error("Invalid cookie file: {0}")]
+    CookieFileError(String),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
     #[error("Failed to read response body: {0}")]
     BodyReadError(String),
 }
 
+impl Error {
+    /// Turn an error from reading a response body into an [`Error`], keeping the timeouts that
+    /// the body reader reports as I/O errors
+    pub fn from_body_read(err: std::io::Error) -> Self {
+        let message = err.to_string();
+        match err.into_inner().map(|inner| inner.downcast::<Error>()) {
+            Some(Ok(err)) => *err,
+            _ => Error::BodyReadError(message),
+        }
+    }
+}
+
 impl Serialize for Error {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
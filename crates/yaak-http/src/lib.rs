@@ -1,13 +1,22 @@
 mod chained_reader;
 pub mod client;
+pub mod compress;
+pub mod cookie_file;
 pub mod cookies;
 pub mod decompress;
 pub mod dns;
 pub mod error;
 pub mod manager;
+pub mod net;
+pub mod pac;
 pub mod path_placeholders;
 mod proto;
+pub mod proxy;
+mod retry;
 pub mod sender;
+pub mod socket;
 pub mod tee_reader;
+mod timing;
 pub mod transaction;
+pub mod tunnel;
 pub mod types;
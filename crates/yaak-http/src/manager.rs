@@ -1,18 +1,21 @@
 use crate::client::HttpConnectionOptions;
 use crate::dns::LocalhostResolver;
 use crate::error::Result;
+use crate::proxy::ProxySelector;
 use log::info;
 use reqwest::Client;
 use std::collections::BTreeMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
-/// A cached HTTP client along with its DNS resolver.
+/// A cached HTTP client along with its DNS resolver and proxy selector.
 /// The resolver is needed to set the event sender per-request.
 pub struct CachedClient {
     pub client: Client,
     pub resolver: Arc<LocalhostResolver>,
+    pub proxy: Arc<ProxySelector>,
 }
 
 pub struct HttpConnectionManager {
@@ -30,7 +33,15 @@ impl HttpConnectionManager {
 
     pub async fn get_client(&self, opt: &HttpConnectionOptions) -> Result<CachedClient> {
         let mut connections = self.connections.write().await;
-        let id = opt.id.clone();
+        // The HTTP version, TLS settings, proxy, DNS and network settings are fixed when the
+        // client is built, so they're part of the key
+        let mut hasher = DefaultHasher::new();
+        opt.tls_settings.hash(&mut hasher);
+        opt.proxy.hash(&mut hasher);
+        opt.dns_overrides.hash(&mut hasher);
+        opt.dns_server.hash(&mut hasher);
+        opt.network.hash(&mut hasher);
+        let id = format!("{}.{}.{:x}", opt.id, opt.http_version, hasher.finish());
 
         // Clean old connections
         connections.retain(|_, (_, last_used)| last_used.elapsed() <= self.ttl);
@@ -41,13 +52,18 @@ impl HttpConnectionManager {
             return Ok(CachedClient {
                 client: cached.client.clone(),
                 resolver: cached.resolver.clone(),
+                proxy: cached.proxy.clone(),
             });
         }
 
-        let (client, resolver) = opt.build_client()?;
-        let cached = CachedClient { client: client.clone(), resolver: resolver.clone() };
-        connections.insert(id.into(), (cached, Instant::now()));
+        let (client, resolver, proxy) = opt.build_client().await?;
+        let cached = CachedClient {
+            client: client.clone(),
+            resolver: resolver.clone(),
+            proxy: proxy.clone(),
+        };
+        connections.insert(id, (cached, Instant::now()));
 
-        Ok(CachedClient { client, resolver })
+        Ok(CachedClient { client, resolver, proxy })
     }
 }
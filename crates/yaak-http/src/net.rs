@@ -0,0 +1,215 @@
+//! Where outgoing connections are made from: the IP family, the local address and the network
+//! interface of a workspace's [`NetworkSettings`]. reqwest's client applies these itself, and
+//! [`connect_tcp`] applies them to the connections of other protocols.
+
+use crate::error::{Error, Result};
+use hyper::Uri;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::connect::dns::{GaiResolver, Name};
+use reqwest::ClientBuilder;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::net::TcpStream;
+use tower_service::Service;
+use yaak_models::models::{IpFamily, NetworkSettings};
+
+/// Apply the local address and network interface to a client. The IP family is applied by
+/// filtering the resolver's addresses.
+pub(crate) fn apply_to_client(
+    mut client: ClientBuilder,
+    network: &NetworkSettings,
+) -> Result<ClientBuilder> {
+    if let Some(addr) = local_address(network)? {
+        client = client.local_address(addr);
+    }
+    if let Some(name) = interface(network)? {
+        client = bind_interface::client(client, name);
+    }
+    Ok(client)
+}
+
+/// Open a TCP connection with the network settings, racing IPv6 and IPv4 addresses with happy
+/// eyeballs unless the settings only allow one family
+pub async fn connect_tcp(host: &str, port: u16, network: &NetworkSettings) -> Result<TcpStream> {
+    let mut connector = HttpConnector::new_with_resolver(FamilyResolver {
+        family: network.ip_family,
+        gai: GaiResolver::new(),
+    });
+    connector.enforce_http(false);
+    connector.set_nodelay(true);
+    connector.set_local_address(local_address(network)?);
+    if let Some(name) = interface(network)? {
+        bind_interface::connector(&mut connector, name);
+    }
+
+    let authority =
+        if host.contains(':') { format!("[{host}]:{port}") } else { format!("{host}:{port}") };
+    let uri: Uri = format!("tcp://{authority}")
+        .parse()
+        .map_err(|e| Error::NetworkError(format!("Invalid address {authority}: {e}")))?;
+
+    std::future::poll_fn(|cx| connector.poll_ready(cx))
+        .await
+        .map_err(|e| connect_error(&authority, e))?;
+    let stream = connector.call(uri).await.map_err(|e| connect_error(&authority, e))?;
+    Ok(stream.into_inner())
+}
+
+/// The name of an IP family for messages
+pub(crate) fn family_name(family: IpFamily) -> &'static str {
+    match family {
+        IpFamily::Auto => "IP",
+        IpFamily::Ipv4 => "IPv4",
+        IpFamily::Ipv6 => "IPv6",
+    }
+}
+
+fn local_address(network: &NetworkSettings) -> Result<Option<IpAddr>> {
+    let addr = network.local_address.trim();
+    if addr.is_empty() {
+        return Ok(None);
+    }
+    let addr = addr.trim_matches(['[', ']']).parse::<IpAddr>().map_err(|_| {
+        Error::NetworkError(format!("Invalid local address {}", network.local_address))
+    })?;
+    Ok(Some(addr))
+}
+
+fn interface(network: &NetworkSettings) -> Result<Option<&str>> {
+    let interface = network.interface.trim();
+    if interface.is_empty() {
+        return Ok(None);
+    }
+    if interface.contains('\0') {
+        return Err(Error::NetworkError(format!("Invalid network interface {interface}")));
+    }
+    if !bind_interface::SUPPORTED {
+        return Err(Error::NetworkError(
+            "Sending from a network interface isn't supported on this platform".to_string(),
+        ));
+    }
+    Ok(Some(interface))
+}
+
+/// Binding to an interface is only supported by some platforms, like it is in reqwest
+#[cfg(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+))]
+mod bind_interface {
+    use super::*;
+
+    pub(super) const SUPPORTED: bool = true;
+
+    pub(super) fn client(client: ClientBuilder, name: &str) -> ClientBuilder {
+        client.interface(name)
+    }
+
+    pub(super) fn connector<R>(connector: &mut HttpConnector<R>, name: &str) {
+        connector.set_interface(name);
+    }
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "fuchsia",
+    target_os = "illumos",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "tvos",
+    target_os = "visionos",
+    target_os = "watchos",
+)))]
+mod bind_interface {
+    use super::*;
+
+    pub(super) const SUPPORTED: bool = false;
+
+    pub(super) fn client(client: ClientBuilder, _name: &str) -> ClientBuilder {
+        client
+    }
+
+    pub(super) fn connector<R>(_connector: &mut HttpConnector<R>, _name: &str) {}
+}
+
+/// Map a connect error to an IO error, keeping its kind and the message of its cause
+fn connect_error(authority: &str, err: impl std::error::Error) -> Error {
+    let source = err.source();
+    let kind = source
+        .and_then(|s| s.downcast_ref::<io::Error>())
+        .map_or(io::ErrorKind::Other, |e| e.kind());
+    let message = source.map_or(err.to_string(), |s| s.to_string());
+    Error::IoError(io::Error::new(kind, format!("Failed to connect to {authority}: {message}")))
+}
+
+/// Resolves hostnames with the system resolver, keeping the addresses of one IP family
+#[derive(Clone)]
+struct FamilyResolver {
+    family: IpFamily,
+    gai: GaiResolver,
+}
+
+impl Service<Name> for FamilyResolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.gai.poll_ready(cx)
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let family = self.family;
+        let host = name.as_str().to_string();
+        let resolving = self.gai.call(name);
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> =
+                resolving.await?.filter(|a| family.allows(&a.ip())).collect();
+            if addrs.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("No {} addresses for {host}", family_name(family)),
+                ));
+            }
+            Ok(addrs.into_iter())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_connect_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let network = NetworkSettings {
+            ip_family: IpFamily::Ipv4,
+            local_address: "127.0.0.1".to_string(),
+            ..Default::default()
+        };
+        let stream = connect_tcp("localhost", port, &network).await.unwrap();
+        assert_eq!(stream.peer_addr().unwrap().port(), port);
+        assert_eq!(stream.local_addr().unwrap().ip().to_string(), "127.0.0.1");
+
+        let network = NetworkSettings { local_address: "nope".to_string(), ..Default::default() };
+        let err = connect_tcp("localhost", port, &network).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid local address nope"), "{err}");
+    }
+}
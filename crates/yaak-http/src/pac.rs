@@ -0,0 +1,361 @@
+//! Proxy auto-config (PAC) scripts, evaluated locally with an embedded JavaScript engine.
+//!
+//! The standard helpers are provided (`isPlainHostName`, `dnsDomainIs`, `localHostOrDomainIs`,
+//! `isResolvable`, `isInNet`, `dnsResolve`, `myIpAddress`, `dnsDomainLevels`, `shExpMatch` and
+//! `weekdayRange`). The date and time range helpers aren't.
+
+use crate::error::{Error, Result};
+use boa_engine::{Context, JsResult, JsString, JsValue, NativeFunction, Source, js_string};
+use log::warn;
+use std::cell::Cell;
+use std::net::{IpAddr, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use url::Url;
+
+const PAC_UTILS: &str = r#"
+function isPlainHostName(host) {
+  return host.indexOf('.') < 0;
+}
+function dnsDomainIs(host, domain) {
+  return host.length >= domain.length && host.substring(host.length - domain.length) === domain;
+}
+function localHostOrDomainIs(host, hostdom) {
+  return host === hostdom || hostdom.lastIndexOf(host + '.', 0) === 0;
+}
+function isResolvable(host) {
+  return dnsResolve(host) !== null;
+}
+function convert_addr(ipchars) {
+  var bytes = ipchars.split('.');
+  return ((bytes[0] & 0xff) << 24) | ((bytes[1] & 0xff) << 16) | ((bytes[2] & 0xff) << 8) | (bytes[3] & 0xff);
+}
+function isInNet(ipaddr, pattern, maskstr) {
+  if (!/^\d+\.\d+\.\d+\.\d+$/.test(ipaddr)) {
+    ipaddr = dnsResolve(ipaddr);
+    if (ipaddr === null) return false;
+  }
+  var mask = convert_addr(maskstr);
+  return (convert_addr(ipaddr) & mask) === (convert_addr(pattern) & mask);
+}
+function dnsDomainLevels(host) {
+  return host.split('.').length - 1;
+}
+function shExpMatch(str, pattern) {
+  pattern = pattern.replace(/[.+^${}()|[\]\\]/g, '\\$&').replace(/\*/g, '.*').replace(/\?/g, '.');
+  return new RegExp('^' + pattern + '$').test(str);
+}
+function weekdayRange(wd1, wd2, gmt) {
+  var days = ['SUN', 'MON', 'TUE', 'WED', 'THU', 'FRI', 'SAT'];
+  if (wd2 === 'GMT') {
+    gmt = wd2;
+    wd2 = undefined;
+  }
+  var now = new Date();
+  var day = gmt === 'GMT' ? now.getUTCDay() : now.getDay();
+  var start = days.indexOf(wd1);
+  var end = wd2 === undefined ? start : days.indexOf(wd2);
+  if (start < 0 || end < 0) return false;
+  return start <= end ? day >= start && day <= end : day >= start || day <= end;
+}
+"#;
+
+/// How long to wait for `FindProxyForURL`, so a slow script can't stall requests. Scripts that
+/// loop forever are stopped by the iteration limit instead, which frees the script's thread.
+const EVALUATION_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_LOOP_ITERATIONS: u64 = 1_000_000;
+
+/// Resolves a hostname for `dnsResolve`, returning its first IPv4 address
+pub type Resolver = fn(&str) -> Option<IpAddr>;
+
+thread_local! {
+    static RESOLVER: Cell<Resolver> = const { Cell::new(resolve_ipv4 as Resolver) };
+}
+
+struct Evaluation {
+    url: String,
+    host: String,
+    reply: Box<dyn FnOnce(Result<String>) + Send>,
+}
+
+/// A loaded PAC script. The script is compiled once on its own thread, which then runs every
+/// `FindProxyForURL` call and the DNS lookups the script makes, so they never block the async
+/// runtime. The thread exits once the last clone is dropped.
+#[derive(Clone)]
+pub struct PacScript {
+    evaluations: mpsc::Sender<Evaluation>,
+}
+
+impl PacScript {
+    pub fn new(source: String) -> Self {
+        Self::with_resolver(source, resolve_ipv4)
+    }
+
+    /// Like [`PacScript::new`], but resolves hostnames for the script with `resolver`
+    pub fn with_resolver(source: String, resolver: Resolver) -> Self {
+        let (evaluations, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .name("pac-script".to_string())
+            .spawn(move || run_script(&source, resolver, receiver));
+        if let Err(e) = spawned {
+            // Evaluations fail once the receiver is dropped, so the error surfaces there
+            warn!("Failed to start proxy auto-config thread: {e}");
+        }
+        Self { evaluations }
+    }
+
+    /// Load a script from an http(s) URL or a file path
+    pub async fn load(location: &str) -> Result<Self> {
+        let source = if location.starts_with("http://") || location.starts_with("https://") {
+            // The script decides which proxy to use, so it's always fetched directly
+            let client = reqwest::Client::builder().no_proxy().build()?;
+            let response = client.get(location).send().await?.error_for_status()?;
+            response.text().await?
+        } else {
+            let path = location.strip_prefix("file://").unwrap_or(location);
+            tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| Error::PacError(format!("Failed to read {location}: {e}")))?
+        };
+        Ok(Self::new(source))
+    }
+
+    /// Run `FindProxyForURL` for the URL, returning its result, e.g. `PROXY host:8080; DIRECT`
+    pub async fn find_proxy(&self, url: &Url) -> Result<String> {
+        let (tx, rx) = oneshot::channel();
+        self.evaluate(url, move |result| {
+            let _ = tx.send(result);
+        })?;
+        match tokio::time::timeout(EVALUATION_TIMEOUT, rx).await {
+            Ok(result) => result.map_err(|_| stopped())?,
+            Err(_) => Err(timed_out()),
+        }
+    }
+
+    /// Like [`PacScript::find_proxy`], but waits for the result on the current thread. Only for
+    /// callers that can't be async.
+    pub fn find_proxy_blocking(&self, url: &Url) -> Result<String> {
+        let (tx, rx) = mpsc::channel();
+        self.evaluate(url, move |result| {
+            let _ = tx.send(result);
+        })?;
+        match rx.recv_timeout(EVALUATION_TIMEOUT) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(timed_out()),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(stopped()),
+        }
+    }
+
+    fn evaluate(
+        &self,
+        url: &Url,
+        reply: impl FnOnce(Result<String>) + Send + 'static,
+    ) -> Result<()> {
+        let host = url.host_str().unwrap_or_default().trim_matches(['[', ']']).to_string();
+
+        // Like browsers, only pass the origin of secure URLs to the script
+        let url = match url.scheme() {
+            "https" | "wss" => format!("{}/", url.origin().ascii_serialization()),
+            _ => url.to_string(),
+        };
+
+        let evaluation = Evaluation { url, host, reply: Box::new(reply) };
+        self.evaluations.send(evaluation).map_err(|_| stopped())
+    }
+}
+
+fn stopped() -> Error {
+    Error::PacError("The proxy auto-config script isn't running".to_string())
+}
+
+fn timed_out() -> Error {
+    Error::PacError(format!(
+        "The proxy auto-config script didn't finish within {}s",
+        EVALUATION_TIMEOUT.as_secs()
+    ))
+}
+
+/// Compile the script, then answer evaluations until every [`PacScript`] is dropped
+fn run_script(source: &str, resolver: Resolver, evaluations: mpsc::Receiver<Evaluation>) {
+    RESOLVER.with(|r| r.set(resolver));
+    let mut context = compile(source).map_err(|e| e.to_string());
+    for Evaluation { url, host, reply } in evaluations {
+        let result = match &mut context {
+            Ok(context) => call_find_proxy(context, &url, &host).map_err(|e| e.to_string()),
+            Err(e) => Err(e.clone()),
+        };
+        reply(result.map_err(Error::PacError));
+    }
+}
+
+fn compile(source: &str) -> JsResult<Context> {
+    let mut context = Context::default();
+    context.runtime_limits_mut().set_loop_iteration_limit(MAX_LOOP_ITERATIONS);
+    context.register_global_callable(
+        js_string!("dnsResolve"),
+        1,
+        NativeFunction::from_fn_ptr(dns_resolve),
+    )?;
+    context.register_global_callable(
+        js_string!("myIpAddress"),
+        0,
+        NativeFunction::from_fn_ptr(my_ip_address),
+    )?;
+    context.eval(Source::from_bytes(PAC_UTILS))?;
+    context.eval(Source::from_bytes(source))?;
+    Ok(context)
+}
+
+fn call_find_proxy(context: &mut Context, url: &str, host: &str) -> JsResult<String> {
+    let find = context.global_object().get(js_string!("FindProxyForURL"), context)?;
+    let Some(find) = find.as_callable() else {
+        return Err(boa_engine::JsNativeError::typ()
+            .with_message("FindProxyForURL is not a function")
+            .into());
+    };
+    let args = [
+        JsValue::from(JsString::from(url)),
+        JsValue::from(JsString::from(host)),
+    ];
+    let result = find.call(&JsValue::undefined(), &args, context)?;
+    Ok(result.to_string(context)?.to_std_string_escaped())
+}
+
+/// Resolve a hostname to its first IPv4 address, or `null`
+fn dns_resolve(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let host = match args.first() {
+        Some(host) => host.to_string(context)?.to_std_string_escaped(),
+        None => return Ok(JsValue::null()),
+    };
+    Ok(match RESOLVER.with(|r| r.get())(&host) {
+        Some(ip) => JsValue::from(JsString::from(ip.to_string().as_str())),
+        None => JsValue::null(),
+    })
+}
+
+/// The IPv4 address of the interface used to reach the internet
+fn my_ip_address(_: &JsValue, _: &[JsValue], _: &mut Context) -> JsResult<JsValue> {
+    // Connecting a UDP socket doesn't send anything, but picks the outgoing interface
+    let ip = UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| socket.connect("8.8.8.8:80").map(|_| socket))
+        .and_then(|socket| socket.local_addr())
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::from([127, 0, 0, 1]));
+    Ok(JsValue::from(JsString::from(ip.to_string().as_str())))
+}
+
+fn resolve_ipv4(host: &str) -> Option<IpAddr> {
+    (host, 0).to_socket_addrs().ok()?.find(|a| a.is_ipv4()).map(|a| a.ip())
+}
+
+/// Pick the proxy for a `FindProxyForURL` result, or `None` to connect directly. Entries are
+/// tried in order, skipping the ones that aren't supported.
+pub(crate) fn parse_pac_result(result: &str) -> Option<Url> {
+    for entry in result.split(';') {
+        let mut parts = entry.split_whitespace();
+        let scheme = match parts.next().map(|k| k.to_ascii_uppercase()).as_deref() {
+            Some("DIRECT") => return None,
+            Some("PROXY") | Some("HTTP") => "http",
+            Some("HTTPS") => "https",
+            // Browsers resolve hostnames on SOCKS5 proxies
+            Some("SOCKS") | Some("SOCKS5") => "socks5h",
+            _ => continue,
+        };
+        let Some(address) = parts.next() else {
+            continue;
+        };
+        if let Ok(url) = Url::parse(&format!("{scheme}://{address}")) {
+            return Some(url);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r#"
+        function FindProxyForURL(url, host) {
+            if (isPlainHostName(host) || dnsDomainIs(host, ".internal.example.com")) {
+                return "DIRECT";
+            }
+            if (isInNet(host, "10.0.0.0", "255.0.0.0")) {
+                return "SOCKS5 socks.example.com:1080";
+            }
+            if (shExpMatch(url, "http://*.example.com/*")) {
+                return "PROXY proxy.example.com:8080; DIRECT";
+            }
+            return "PROXY fallback.example.com:3128";
+        }
+    "#;
+
+    fn resolve(host: &str) -> Option<IpAddr> {
+        match host {
+            "db.example.com" => Some(IpAddr::from([10, 0, 0, 5])),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_proxy() {
+        let script = PacScript::with_resolver(SCRIPT.to_string(), resolve);
+        let find = async |url: &str| script.find_proxy(&Url::parse(url).unwrap()).await.unwrap();
+        assert_eq!(find("http://intranet/").await, "DIRECT");
+        assert_eq!(find("http://app.internal.example.com/").await, "DIRECT");
+        assert_eq!(find("http://10.1.2.3/").await, "SOCKS5 socks.example.com:1080");
+        assert_eq!(find("http://db.example.com/").await, "SOCKS5 socks.example.com:1080");
+        assert_eq!(
+            find("http://www.example.com/path").await,
+            "PROXY proxy.example.com:8080; DIRECT"
+        );
+        // Only the origin of https URLs is passed to the script
+        assert_eq!(find("https://www.example.com/path").await, "PROXY fallback.example.com:3128");
+    }
+
+    #[test]
+    fn test_find_proxy_blocking() {
+        let script = PacScript::with_resolver(SCRIPT.to_string(), resolve);
+        let url = Url::parse("http://db.example.com/").unwrap();
+        assert_eq!(script.find_proxy_blocking(&url).unwrap(), "SOCKS5 socks.example.com:1080");
+    }
+
+    #[tokio::test]
+    async fn test_find_proxy_endless_loop() {
+        let script = PacScript::new(
+            r#"function FindProxyForURL(url, host) {
+                if (host === "loop.test") { while (true) {} }
+                return "DIRECT";
+            }"#
+            .to_string(),
+        );
+        let url = Url::parse("http://loop.test/").unwrap();
+        assert!(script.find_proxy(&url).await.is_err());
+        // The script's thread is free again for the next evaluation
+        let url = Url::parse("http://example.com/").unwrap();
+        assert_eq!(script.find_proxy(&url).await.unwrap(), "DIRECT");
+    }
+
+    #[tokio::test]
+    async fn test_find_proxy_errors() {
+        let url = Url::parse("http://example.com/").unwrap();
+        let script = PacScript::new("var x = 1;".to_string());
+        assert!(script.find_proxy(&url).await.is_err());
+        let script = PacScript::new("function FindProxyForURL(url, host) {".to_string());
+        assert!(script.find_proxy(&url).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_pac_result() {
+        let parse = |r| parse_pac_result(r).map(|u| u.to_string());
+        assert_eq!(parse("DIRECT"), None);
+        assert_eq!(parse("PROXY proxy:8080; DIRECT"), Some("http://proxy:8080/".to_string()));
+        assert_eq!(parse("HTTPS proxy:443"), Some("https://proxy/".to_string()));
+        assert_eq!(parse("SOCKS5 socks:1080"), Some("socks5h://socks:1080".to_string()));
+        // Unsupported entries fall through to the next one
+        assert_eq!(parse("SOCKS4 socks:1080; PROXY p:1"), Some("http://p:1/".to_string()));
+        assert_eq!(parse(""), None);
+    }
+}
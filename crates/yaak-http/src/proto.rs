@@ -6,7 +6,10 @@ pub(crate) fn ensure_proto(url_str: &str) -> String {
         return "".to_string();
     }
 
-    if url_str.starts_with("http://") || url_str.starts_with("https://") {
+    if url_str.starts_with("http://")
+        || url_str.starts_with("https://")
+        || url_str.starts_with("http+unix://")
+    {
         return url_str.to_string();
     }
 
@@ -0,0 +1,136 @@
+use crate::error::{Error, Result};
+use crate::sender::HttpResponse;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use yaak_models::models::{RetryErrorKind, RetryPolicy};
+
+/// Why an attempt should be sent again
+pub(crate) struct RetryReason {
+    pub message: String,
+    /// Delay asked for by the server with a Retry-After header
+    pub retry_after: Option<Duration>,
+}
+
+/// Check whether the outcome of an attempt is retryable under the policy
+pub(crate) fn retry_reason(
+    policy: &RetryPolicy,
+    result: &Result<HttpResponse>,
+) -> Option<RetryReason> {
+    match result {
+        Ok(response) if policy.statuses.contains(&response.status) => {
+            let retry_after = response
+                .headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case("retry-after"))
+                .and_then(|(_, v)| parse_retry_after(v, Utc::now()));
+            Some(RetryReason { message: format!("status {}", response.status), retry_after })
+        }
+        Ok(_) => None,
+        Err(e) => {
+            let kind = error_kind(e)?;
+            if !policy.errors.contains(&kind) {
+                return None;
+            }
+            let message = match kind {
+                RetryErrorKind::Connect => "connection failed",
+                RetryErrorKind::Timeout => "timed out",
+                RetryErrorKind::Network => "connection error",
+            };
+            Some(RetryReason { message: message.to_string(), retry_after: None })
+        }
+    }
+}
+
+fn error_kind(e: &Error) -> Option<RetryErrorKind> {
+    match e {
+        // Read timeouts happen while the body is streamed, after the response was returned, so
+        // they're never retried
+        Error::RequestTimeout(_) | Error::ConnectTimeout(_) => Some(RetryErrorKind::Timeout),
+        Error::Client(e) if e.is_timeout() => Some(RetryErrorKind::Timeout),
+        Error::Client(e) if e.is_connect() => Some(RetryErrorKind::Connect),
+        Error::Client(e) if e.is_request() || e.is_body() => Some(RetryErrorKind::Network),
+        _ => None,
+    }
+}
+
+/// Exponential backoff after the given number of attempts, with optional jitter that picks a
+/// delay between half and all of it
+pub(crate) fn backoff_delay(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(31);
+    let delay =
+        (policy.initial_delay as u64).saturating_mul(1 << exponent).min(policy.max_delay as u64);
+    let delay = if policy.jitter && delay > 0 {
+        delay / 2 + rand::random_range(0..=delay / 2)
+    } else {
+        delay
+    };
+    Duration::from_millis(delay)
+}
+
+/// Parse a Retry-After header, given either as seconds or as an HTTP date
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now: DateTime<Utc> =
+            DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT").unwrap().into();
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        // Dates in the past mean no delay
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let policy = RetryPolicy {
+            initial_delay: 100,
+            max_delay: 1000,
+            jitter: false,
+            ..Default::default()
+        };
+        assert_eq!(backoff_delay(&policy, 1), Duration::from_millis(100));
+        assert_eq!(backoff_delay(&policy, 2), Duration::from_millis(200));
+        assert_eq!(backoff_delay(&policy, 3), Duration::from_millis(400));
+        assert_eq!(backoff_delay(&policy, 5), Duration::from_millis(1000));
+        assert_eq!(backoff_delay(&policy, 100), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_backoff_delay_jitter() {
+        let policy = RetryPolicy { initial_delay: 400, jitter: true, ..Default::default() };
+        for _ in 0..20 {
+            let delay = backoff_delay(&policy, 2);
+            assert!(delay >= Duration::from_millis(400) && delay <= Duration::from_millis(800));
+        }
+    }
+
+    #[test]
+    fn test_retry_reason_errors() {
+        let policy = RetryPolicy::default();
+        let timeout = Err(Error::RequestTimeout(Duration::from_secs(1)));
+        assert_eq!(retry_reason(&policy, &timeout).unwrap().message, "timed out");
+        let connect_timeout = Err(Error::ConnectTimeout(Duration::from_secs(1)));
+        assert_eq!(retry_reason(&policy, &connect_timeout).unwrap().message, "timed out");
+
+        let policy = RetryPolicy { errors: vec![RetryErrorKind::Connect], ..Default::default() };
+        assert!(retry_reason(&policy, &timeout).is_none());
+
+        let other = Err(Error::RequestError("Invalid HTTP method".to_string()));
+        assert!(retry_reason(&RetryPolicy::default(), &other).is_none());
+    }
+}
@@ -1,16 +1,25 @@
 use crate::decompress::{ContentEncoding, streaming_decoder};
+use crate::dns::{self, LocalhostResolver};
 use crate::error::{Error, Result};
-use crate::types::{SendableBody, SendableHttpRequest};
+use crate::proxy::ProxySelector;
+use crate::socket::{self, socket_path};
+use crate::timing::track_connection;
+use crate::types::{SendableBody, SendableHttpRequest, SendableHttpRequestOptions};
 use async_trait::async_trait;
 use futures_util::StreamExt;
 use reqwest::{Client, Method, Version};
 use std::fmt::Display;
+use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncRead, AsyncReadExt, BufReader, ReadBuf};
 use tokio::sync::mpsc;
+use tokio::time::Sleep;
 use tokio_util::io::StreamReader;
+use url::Url;
+use yaak_models::models::TlsCertificate;
 
 #[derive(Debug, Clone)]
 pub enum RedirectBehavior {
@@ -57,6 +66,36 @@ pub enum HttpResponseEvent {
         addresses: Vec<String>,
         duration: u64,
         overridden: bool,
+        /// Where the addresses came from: "override", "localhost", "system" or the DNS server
+        source: String,
+        /// How many seconds the DNS server says the addresses can be cached for
+        ttl: Option<u64>,
+    },
+    /// A new connection was established, excluding DNS and the TLS handshake
+    Connected {
+        duration: u64,
+    },
+    TlsHandshake {
+        duration: u64,
+    },
+    /// Time from sending the request (on a ready connection) to receiving the first byte
+    FirstByte {
+        duration: u64,
+    },
+    /// Negotiated TLS parameters and the chain presented by the server, leaf first
+    TlsSession {
+        version: String,
+        cipher_suite: String,
+        alpn: String,
+        server_name: String,
+        certificates: Vec<TlsCertificate>,
+    },
+    /// The request is sent again after a delay, counting attempts from 1
+    Retry {
+        attempt: u32,
+        max_attempts: u32,
+        delay: u64,
+        reason: String,
     },
 }
 
@@ -89,19 +128,49 @@ impl Display for HttpResponseEvent {
             HttpResponseEvent::HeaderDown(name, value) => write!(f, "< {}: {}", name, value),
             HttpResponseEvent::ChunkSent { bytes } => write!(f, "> [{} bytes sent]", bytes),
             HttpResponseEvent::ChunkReceived { bytes } => write!(f, "< [{} bytes received]", bytes),
-            HttpResponseEvent::DnsResolved { hostname, addresses, duration, overridden } => {
+            HttpResponseEvent::DnsResolved {
+                hostname, addresses, duration, overridden, source, ttl
+            } => {
                 if *overridden {
                     write!(f, "* DNS override {} -> {}", hostname, addresses.join(", "))
                 } else {
                     write!(
                         f,
-                        "* DNS resolved {} to {} ({}ms)",
+                        "* DNS resolved {} to {} via {} ({}ms)",
                         hostname,
                         addresses.join(", "),
+                        source,
                         duration
-                    )
+                    )?;
+                    match ttl {
+                        Some(ttl) => write!(f, " ttl={}s", ttl),
+                        None => Ok(()),
+                    }
                 }
             }
+            HttpResponseEvent::Connected { duration } => write!(f, "* Connected ({}ms)", duration),
+            HttpResponseEvent::TlsHandshake { duration } => {
+                write!(f, "* TLS handshake completed ({}ms)", duration)
+            }
+            HttpResponseEvent::FirstByte { duration } => {
+                write!(f, "* Waited {}ms for the first byte", duration)
+            }
+            HttpResponseEvent::TlsSession { version, cipher_suite, alpn, server_name, certificates } => {
+                write!(f, "* TLS {} / {}, ALPN {}, SNI {}", version, cipher_suite, alpn, server_name)?;
+                for (i, cert) in certificates.iter().enumerate() {
+                    write!(f, "\n* Certificate {}: subject {}", i, cert.subject)?;
+                    write!(f, "\n*   issuer {}", cert.issuer)?;
+                    if !cert.sans.is_empty() {
+                        write!(f, "\n*   SANs {}", cert.sans.join(", "))?;
+                    }
+                    write!(f, "\n*   valid {} to {}", cert.not_before, cert.not_after)?;
+                    write!(f, "\n*   SHA-256 {}", cert.sha256_fingerprint)?;
+                }
+                Ok(())
+            }
+            HttpResponseEvent::Retry { attempt, max_attempts, delay, reason } => {
+                write!(f, "* Retrying in {}ms, attempt {} of {} ({})", delay, attempt, max_attempts, reason)
+            }
         }
     }
 }
@@ -130,8 +199,19 @@ impl From<HttpResponseEvent> for yaak_models::models::HttpResponseEventData {
             HttpResponseEvent::HeaderDown(name, value) => D::HeaderDown { name, value },
             HttpResponseEvent::ChunkSent { bytes } => D::ChunkSent { bytes },
             HttpResponseEvent::ChunkReceived { bytes } => D::ChunkReceived { bytes },
-            HttpResponseEvent::DnsResolved { hostname, addresses, duration, overridden } => {
-                D::DnsResolved { hostname, addresses, duration, overridden }
+            HttpResponseEvent::DnsResolved {
+                hostname, addresses, duration, overridden, source, ttl
+            } => {
+                D::DnsResolved { hostname, addresses, duration, overridden, source, ttl }
+            }
+            HttpResponseEvent::Connected { duration } => D::Connected { duration },
+            HttpResponseEvent::TlsHandshake { duration } => D::TlsHandshake { duration },
+            HttpResponseEvent::FirstByte { duration } => D::FirstByte { duration },
+            HttpResponseEvent::TlsSession { version, cipher_suite, alpn, server_name, certificates } => {
+                D::TlsSession { version, cipher_suite, alpn, server_name, certificates }
+            }
+            HttpResponseEvent::Retry { attempt, max_attempts, delay, reason } => {
+                D::Retry { attempt, max_attempts, delay, reason }
             }
         }
     }
@@ -146,16 +226,35 @@ pub struct BodyStats {
     pub size_decompressed: u64,
 }
 
-/// An AsyncRead wrapper that sends chunk events as data is read
+/// An AsyncRead wrapper that sends chunk events as data is read, and enforces the read
+/// timeout and the deadline of the request while the body is read
 pub struct TrackingRead<R> {
     inner: R,
     event_tx: mpsc::Sender<HttpResponseEvent>,
     ended: bool,
+    read_timeout: Option<(Duration, Pin<Box<Sleep>>)>,
+    deadline: Option<(Duration, Pin<Box<Sleep>>)>,
 }
 
 impl<R> TrackingRead<R> {
     pub fn new(inner: R, event_tx: mpsc::Sender<HttpResponseEvent>) -> Self {
-        Self { inner, event_tx, ended: false }
+        Self { inner, event_tx, ended: false, read_timeout: None, deadline: None }
+    }
+
+    /// Fail with [`Error::ReadTimeout`] when no data arrives for `read_timeout`, and with
+    /// [`Error::RequestTimeout`] once the request's `timeout` has passed since `sent_at`
+    pub fn with_timeouts(
+        mut self,
+        read_timeout: Option<Duration>,
+        timeout: Option<Duration>,
+        sent_at: Instant,
+    ) -> Self {
+        self.read_timeout = read_timeout.map(|d| (d, Box::pin(tokio::time::sleep(d))));
+        self.deadline = timeout.map(|d| {
+            let deadline = tokio::time::Instant::from_std(sent_at + d);
+            (d, Box::pin(tokio::time::sleep_until(deadline)))
+        });
+        self
     }
 }
 
@@ -165,22 +264,55 @@ impl<R: AsyncRead + Unpin> AsyncRead for TrackingRead<R> {
         cx: &mut Context<'_>,
         buf: &mut ReadBuf<'_>,
     ) -> Poll<std::io::Result<()>> {
+        if let Some((d, sleep)) = &self.deadline
+            && sleep.is_elapsed()
+        {
+            return Poll::Ready(Err(timed_out(Error::RequestTimeout(*d))));
+        }
+
         let before = buf.filled().len();
         let result = Pin::new(&mut self.inner).poll_read(cx, buf);
-        if let Poll::Ready(Ok(())) = &result {
-            let bytes_read = buf.filled().len() - before;
-            if bytes_read > 0 {
-                // Ignore send errors - receiver may have been dropped or channel is full
-                let _ =
-                    self.event_tx.try_send(HttpResponseEvent::ChunkReceived { bytes: bytes_read });
-            } else if !self.ended {
-                self.ended = true;
+        match &result {
+            Poll::Ready(Ok(())) => {
+                let bytes_read = buf.filled().len() - before;
+                if bytes_read > 0 {
+                    // Ignore send errors - receiver may have been dropped or channel is full
+                    let _ = self
+                        .event_tx
+                        .try_send(HttpResponseEvent::ChunkReceived { bytes: bytes_read });
+                    if let Some((d, sleep)) = &mut self.read_timeout {
+                        let next = tokio::time::Instant::now() + *d;
+                        sleep.as_mut().reset(next);
+                    }
+                } else if !self.ended {
+                    self.ended = true;
+                }
+            }
+            // Polling the timers registers them, so the read is woken up when one fires
+            Poll::Pending => {
+                if let Some((d, sleep)) = &mut self.deadline
+                    && sleep.as_mut().poll(cx).is_ready()
+                {
+                    return Poll::Ready(Err(timed_out(Error::RequestTimeout(*d))));
+                }
+                if let Some((d, sleep)) = &mut self.read_timeout
+                    && sleep.as_mut().poll(cx).is_ready()
+                {
+                    return Poll::Ready(Err(timed_out(Error::ReadTimeout(*d))));
+                }
             }
+            Poll::Ready(Err(_)) => {}
         }
         result
     }
 }
 
+/// A timeout error for readers of the body, which [`Error::from_body_read`] turns back into
+/// `err`
+fn timed_out(err: Error) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::TimedOut, err)
+}
+
 /// Type alias for the body stream
 type BodyStream = Pin<Box<dyn AsyncRead + Send>>;
 
@@ -207,8 +339,8 @@ pub struct HttpResponse {
 
     /// The body stream (consumed when calling bytes(), text(), write_to_file(), or drain())
     body_stream: Option<BodyStream>,
-    /// Content-Encoding for decompression
-    encoding: ContentEncoding,
+    /// Content-Encoding codings for decompression, in the order they were applied
+    encodings: Vec<ContentEncoding>,
 }
 
 impl std::fmt::Debug for HttpResponse {
@@ -222,7 +354,7 @@ impl std::fmt::Debug for HttpResponse {
             .field("remote_addr", &self.remote_addr)
             .field("version", &self.version)
             .field("body_stream", &"<stream>")
-            .field("encoding", &self.encoding)
+            .field("encodings", &self.encodings)
             .finish()
     }
 }
@@ -240,7 +372,7 @@ impl HttpResponse {
         remote_addr: Option<String>,
         version: Option<String>,
         body_stream: BodyStream,
-        encoding: ContentEncoding,
+        encodings: Vec<ContentEncoding>,
     ) -> Self {
         Self {
             status,
@@ -252,7 +384,7 @@ impl HttpResponse {
             remote_addr,
             version,
             body_stream: Some(body_stream),
-            encoding,
+            encodings,
         }
     }
 
@@ -264,7 +396,7 @@ impl HttpResponse {
         })?;
 
         let buf_reader = BufReader::new(stream);
-        let mut decoder = streaming_decoder(buf_reader, self.encoding);
+        let mut decoder = streaming_decoder(buf_reader, &self.encodings);
 
         let mut decompressed = Vec::new();
         let mut bytes_read = 0u64;
@@ -279,7 +411,7 @@ impl HttpResponse {
                     bytes_read += n as u64;
                 }
                 Err(e) => {
-                    return Err(Error::BodyReadError(e.to_string()));
+                    return Err(Error::from_body_read(e));
                 }
             }
         }
@@ -311,7 +443,7 @@ impl HttpResponse {
         })?;
 
         let buf_reader = BufReader::new(stream);
-        let decoder = streaming_decoder(buf_reader, self.encoding);
+        let decoder = streaming_decoder(buf_reader, &self.encodings);
 
         Ok(decoder)
     }
@@ -358,18 +490,32 @@ pub trait HttpSender: Send + Sync {
 /// Reqwest-based implementation of HttpSender
 pub struct ReqwestSender {
     client: Client,
+    proxy: Option<Arc<ProxySelector>>,
+    resolver: Option<Arc<LocalhostResolver>>,
 }
 
 impl ReqwestSender {
     /// Create a new ReqwestSender with a default client
     pub fn new() -> Result<Self> {
         let client = Client::builder().build().map_err(Error::Client)?;
-        Ok(Self { client })
+        Ok(Self { client, proxy: None, resolver: None })
     }
 
     /// Create a new ReqwestSender with a custom client
     pub fn with_client(client: Client) -> Self {
-        Self { client }
+        Self { client, proxy: None, resolver: None }
+    }
+
+    /// Report the proxy the client's selector chooses for each request
+    pub fn with_proxy(mut self, proxy: Arc<ProxySelector>) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Apply the client's DNS overrides that are specific to a port, or change the port
+    pub fn with_resolver(mut self, resolver: Arc<LocalhostResolver>) -> Self {
+        self.resolver = Some(resolver);
+        self
     }
 }
 
@@ -377,9 +523,14 @@ impl ReqwestSender {
 impl HttpSender for ReqwestSender {
     async fn send(
         &self,
-        request: SendableHttpRequest,
+        mut request: SendableHttpRequest,
         event_tx: mpsc::Sender<HttpResponseEvent>,
     ) -> Result<HttpResponse> {
+        // reqwest can't connect to sockets, so those requests are sent separately
+        if let Some(socket) = Url::parse(&request.url).ok().as_ref().and_then(socket_path) {
+            return socket::send(request, &socket, event_tx).await;
+        }
+
         // Helper to send events (ignores errors if receiver is dropped or channel is full)
         let send_event = |event: HttpResponseEvent| {
             let _ = event_tx.try_send(event);
@@ -389,8 +540,35 @@ impl HttpSender for ReqwestSender {
         let method = Method::from_bytes(request.method.as_bytes())
             .map_err(|e| Error::RequestError(format!("Invalid HTTP method: {}", e)))?;
 
+        // The resolver only sees hostnames, so overrides for a port are matched here
+        let mut url = Url::parse(&request.url)
+            .map_err(|e| Error::RequestError(format!("Invalid URL {}: {}", request.url, e)))?;
+        let dns_override = match (&self.resolver, url.host_str()) {
+            (Some(resolver), Some(host)) => {
+                resolver.find_override(host, url.port_or_known_default())
+            }
+            _ => None,
+        };
+
+        // Connect to another port, keeping the original host and port in the Host header
+        let mut rewritten_url = None;
+        if let Some(target_port) = dns_override.as_ref().and_then(|o| o.target_port) {
+            let authority = url[url::Position::BeforeHost..url::Position::AfterPort].to_string();
+            if url.set_port(Some(target_port)).is_ok() {
+                send_event(HttpResponseEvent::Info(format!(
+                    "Connecting to {} instead of {}",
+                    &url[url::Position::BeforeHost..url::Position::AfterPort],
+                    authority
+                )));
+                if !request.headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("host")) {
+                    request.headers.push(("Host".to_string(), authority));
+                }
+                rewritten_url = Some(url.to_string());
+            }
+        }
+
         // Build the request
-        let mut req_builder = self.client.request(method, &request.url);
+        let mut req_builder = self.client.request(method, url);
 
         // Add headers
         for header in request.headers {
@@ -400,13 +578,6 @@ impl HttpSender for ReqwestSender {
             req_builder = req_builder.header(&header.0, &header.1);
         }
 
-        // Configure timeout
-        if let Some(d) = request.options.timeout
-            && !d.is_zero()
-        {
-            req_builder = req_builder.timeout(d);
-        }
-
         // Add body
         match request.body {
             None => {}
@@ -423,14 +594,13 @@ impl HttpSender for ReqwestSender {
 
         // Send the request
         let sendable_req = req_builder.build()?;
-        send_event(HttpResponseEvent::Setting(
-            "timeout".to_string(),
-            if request.options.timeout.unwrap_or_default().is_zero() {
-                "Infinity".to_string()
-            } else {
-                format!("{:?}", request.options.timeout)
-            },
-        ));
+        let timeouts = Timeouts::from(&request.options);
+        timeouts.send_settings(send_event);
+
+        if let Some(proxy) = &self.proxy {
+            let (_, description) = proxy.select(sendable_req.url()).await;
+            send_event(HttpResponseEvent::Setting("proxy".to_string(), description));
+        }
 
         send_event(HttpResponseEvent::SendUrl {
             method: sendable_req.method().to_string(),
@@ -452,24 +622,47 @@ impl HttpSender for ReqwestSender {
         }
         send_event(HttpResponseEvent::Info("Sending request to server".to_string()));
 
-        // Map some errors to our own, so they look nicer
-        let response = self.client.execute(sendable_req).await.map_err(|e| {
-            if reqwest::Error::is_timeout(&e) {
-                Error::RequestTimeout(
-                    request.options.timeout.unwrap_or(Duration::from_secs(0)).clone(),
-                )
-            } else {
-                Error::Client(e)
+        let sent_at = Instant::now();
+        let execute = dns::with_override(
+            dns_override,
+            track_connection(timeouts.connect, self.client.execute(sendable_req)),
+        );
+        let (response, connection_ready) = match timeouts.total {
+            Some(d) => {
+                tokio::time::timeout(d, execute).await.map_err(|_| Error::RequestTimeout(d))?
             }
+            None => execute.await,
+        };
+
+        // Map some errors to our own, so they say which timeout was reached
+        let response = response.map_err(|e| match timeouts.connect {
+            Some(d) if e.is_connect() && e.is_timeout() => Error::ConnectTimeout(d),
+            _ => Error::Client(e),
         })?;
 
+        // Waiting starts once a new connection is ready, so connecting isn't counted
+        let wait_start = connection_ready.map_or(sent_at, |ready| ready.max(sent_at));
+        send_event(HttpResponseEvent::FirstByte {
+            duration: wait_start.elapsed().as_millis() as u64,
+        });
+
         let status = response.status().as_u16();
         let status_reason = response.status().canonical_reason().map(|s| s.to_string());
-        let url = response.url().to_string();
+        let url = match rewritten_url {
+            // Report the URL that was requested, not the one that was connected to
+            Some(rewritten) if rewritten == response.url().as_str() => request.url.clone(),
+            _ => response.url().to_string(),
+        };
         let remote_addr = response.remote_addr().map(|a| a.to_string());
         let version = Some(version_to_str(&response.version()));
         let content_length = response.content_length();
 
+        // The protocol is only known once the connection has been negotiated
+        send_event(HttpResponseEvent::Setting(
+            "http_version".to_string(),
+            version_to_str(&response.version()),
+        ));
+
         send_event(HttpResponseEvent::ReceiveUrl {
             version: response.version(),
             status: response.status().to_string(),
@@ -484,12 +677,12 @@ impl HttpSender for ReqwestSender {
             }
         }
 
-        // Determine content encoding for decompression
-        // HTTP headers are case-insensitive, so we need to search for any casing
-        let encoding = ContentEncoding::from_header(
+        // Determine content encodings for decompression. Codings can be stacked in one header
+        // or spread across several, and header names are case-insensitive.
+        let encodings = ContentEncoding::chain_from_headers(
             headers
                 .iter()
-                .find(|(k, _)| k.eq_ignore_ascii_case("content-encoding"))
+                .filter(|(k, _)| k.eq_ignore_ascii_case("content-encoding"))
                 .map(|(_, v)| v.as_str()),
         );
 
@@ -502,7 +695,11 @@ impl HttpSender for ReqwestSender {
         );
 
         // Wrap the stream with tracking to emit chunk received events via the same channel
-        let tracking_reader = TrackingRead::new(stream_reader, event_tx);
+        let tracking_reader = TrackingRead::new(stream_reader, event_tx).with_timeouts(
+            timeouts.read,
+            timeouts.total,
+            sent_at,
+        );
         let body_stream: BodyStream = Box::pin(tracking_reader);
 
         Ok(HttpResponse::new(
@@ -515,12 +712,45 @@ impl HttpSender for ReqwestSender {
             remote_addr,
             version,
             body_stream,
-            encoding,
+            encodings,
         ))
     }
 }
 
-fn version_to_str(version: &Version) -> String {
+/// The timeouts of a request, leaving out the ones that are zero
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Timeouts {
+    pub total: Option<Duration>,
+    pub connect: Option<Duration>,
+    pub read: Option<Duration>,
+}
+
+impl From<&SendableHttpRequestOptions> for Timeouts {
+    fn from(options: &SendableHttpRequestOptions) -> Self {
+        let non_zero = |d: Option<Duration>| d.filter(|d| !d.is_zero());
+        Self {
+            total: non_zero(options.timeout),
+            connect: non_zero(options.connect_timeout),
+            read: non_zero(options.read_timeout),
+        }
+    }
+}
+
+impl Timeouts {
+    /// Report the timeouts as settings on the timeline
+    pub(crate) fn send_settings(&self, send_event: impl Fn(HttpResponseEvent)) {
+        for (name, timeout) in [
+            ("timeout", self.total),
+            ("connect_timeout", self.connect),
+            ("read_timeout", self.read),
+        ] {
+            let value = timeout.map_or("Infinity".to_string(), |d| format!("{d:?}"));
+            send_event(HttpResponseEvent::Setting(name.to_string(), value));
+        }
+    }
+}
+
+pub(crate) fn version_to_str(version: &Version) -> String {
     match *version {
         Version::HTTP_09 => "HTTP/0.9".to_string(),
         Version::HTTP_10 => "HTTP/1.0".to_string(),
@@ -530,3 +760,34 @@ fn version_to_str(version: &Version) -> String {
         _ => "unknown".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn test_tracking_read_timeouts() {
+        let (event_tx, _event_rx) = mpsc::channel(10);
+        let mut buf = [0u8; 5];
+
+        let (mut server, client) = tokio::io::duplex(64);
+        let read_timeout = Some(Duration::from_millis(20));
+        let mut reader = TrackingRead::new(client, event_tx.clone()).with_timeouts(
+            read_timeout,
+            None,
+            Instant::now(),
+        );
+        server.write_all(b"hello").await.unwrap();
+        reader.read_exact(&mut buf).await.unwrap();
+        let err = reader.read(&mut buf).await.unwrap_err();
+        assert!(matches!(Error::from_body_read(err), Error::ReadTimeout(_)));
+
+        let (_server, client) = tokio::io::duplex(64);
+        let timeout = Some(Duration::from_millis(10));
+        let mut reader =
+            TrackingRead::new(client, event_tx).with_timeouts(None, timeout, Instant::now());
+        let err = reader.read(&mut buf).await.unwrap_err();
+        assert_eq!(Error::from_body_read(err).to_string(), "Timeout of 10ms reached");
+    }
+}
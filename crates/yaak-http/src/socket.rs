@@ -0,0 +1,280 @@
+//! Requests to local daemons that listen on a Unix domain socket or a Windows named pipe, like
+//! the Docker API. Sockets are addressed with `http+unix://` URLs whose host is the
+//! percent-encoded path, e.g. `http+unix://%2Fvar%2Frun%2Fdocker.sock/v1.43/containers/json`.
+//!
+//! reqwest can't connect to sockets, so these requests are sent with hyper over HTTP/1.1. They
+//! go through the same [`crate::transaction::HttpTransaction`] as any other request, so cookies,
+//! redirects and decompression work the same.
+
+use crate::decompress::ContentEncoding;
+use crate::error::{Error, Result};
+use crate::sender::{HttpResponse, HttpResponseEvent, Timeouts, TrackingRead, version_to_str};
+use crate::types::{SendableBody, SendableHttpRequest};
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt};
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Empty, Full, StreamBody};
+use hyper::body::Frame;
+use hyper::header::HOST;
+use hyper::{Method, Request};
+use hyper_util::rt::TokioIo;
+use log::debug;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio_util::io::{ReaderStream, StreamReader};
+use url::Url;
+
+pub const SOCKET_SCHEME: &str = "http+unix";
+
+#[cfg(unix)]
+pub type SocketStream = tokio::net::UnixStream;
+
+#[cfg(windows)]
+pub type SocketStream = tokio::net::windows::named_pipe::NamedPipeClient;
+
+/// The socket path of an `http+unix://` URL
+pub fn socket_path(url: &Url) -> Option<String> {
+    if url.scheme() != SOCKET_SCHEME {
+        return None;
+    }
+    let path = urlencoding::decode(url.host_str()?).ok()?;
+    if path.is_empty() { None } else { Some(path.into_owned()) }
+}
+
+/// Connect to a Unix domain socket, or a named pipe on Windows
+pub async fn connect(path: &str) -> Result<SocketStream> {
+    #[cfg(unix)]
+    let stream = tokio::net::UnixStream::connect(path).await;
+    #[cfg(windows)]
+    let stream = tokio::net::windows::named_pipe::ClientOptions::new().open(path);
+
+    stream.map_err(|e| Error::RequestError(format!("Failed to connect to socket {path}: {e}")))
+}
+
+/// Send a request over the socket of its `http+unix://` URL
+pub(crate) async fn send(
+    request: SendableHttpRequest,
+    socket: &str,
+    event_tx: mpsc::Sender<HttpResponseEvent>,
+) -> Result<HttpResponse> {
+    let send_event = |event: HttpResponseEvent| {
+        let _ = event_tx.try_send(event);
+    };
+
+    let url = Url::parse(&request.url)
+        .map_err(|e| Error::RequestError(format!("Invalid URL {}: {e}", request.url)))?;
+    let method = Method::from_bytes(request.method.as_bytes())
+        .map_err(|e| Error::RequestError(format!("Invalid HTTP method: {}", e)))?;
+
+    let path_and_query = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let mut builder = Request::builder().method(method).uri(path_and_query);
+
+    // The host of the URL is the socket path, which isn't a valid Host header
+    if !request.headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("host")) {
+        builder = builder.header(HOST, "localhost");
+    }
+    for (name, value) in &request.headers {
+        if name.is_empty() {
+            continue;
+        }
+        builder = builder.header(name, value);
+    }
+
+    let body: UnsyncBoxBody<Bytes, std::io::Error> = match request.body {
+        None => Empty::new().map_err(|never| match never {}).boxed_unsync(),
+        Some(SendableBody::Bytes(bytes)) => {
+            Full::new(bytes).map_err(|never| match never {}).boxed_unsync()
+        }
+        Some(SendableBody::Stream(stream)) => {
+            StreamBody::new(ReaderStream::new(stream).map_ok(Frame::data)).boxed_unsync()
+        }
+    };
+    let req = builder.body(body).map_err(|e| Error::RequestError(e.to_string()))?;
+
+    let timeouts = Timeouts::from(&request.options);
+    timeouts.send_settings(send_event);
+    send_event(HttpResponseEvent::Setting("socket".to_string(), socket.to_string()));
+    send_event(HttpResponseEvent::SendUrl {
+        method: req.method().to_string(),
+        scheme: url.scheme().to_string(),
+        username: url.username().to_string(),
+        password: url.password().unwrap_or_default().to_string(),
+        host: url.host_str().unwrap_or_default().to_string(),
+        port: 0,
+        path: url.path().to_string(),
+        query: url.query().unwrap_or_default().to_string(),
+        fragment: url.fragment().unwrap_or_default().to_string(),
+    });
+
+    let mut request_headers = Vec::new();
+    for (name, value) in req.headers() {
+        let v = value.to_str().unwrap_or_default().to_string();
+        request_headers.push((name.to_string(), v.clone()));
+        send_event(HttpResponseEvent::HeaderUp(name.to_string(), v));
+    }
+
+    let sent_at = Instant::now();
+    let exchange = async {
+        let connect_start = Instant::now();
+        let stream = match timeouts.connect {
+            Some(d) => tokio::time::timeout(d, connect(socket))
+                .await
+                .map_err(|_| Error::ConnectTimeout(d))?,
+            None => connect(socket).await,
+        }?;
+        send_event(HttpResponseEvent::Connected {
+            duration: connect_start.elapsed().as_millis() as u64,
+        });
+
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(|e| Error::RequestError(e.to_string()))?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                debug!("Socket connection closed with error: {e}");
+            }
+        });
+
+        send_event(HttpResponseEvent::Info("Sending request to server".to_string()));
+        let sent_at = Instant::now();
+        let response =
+            sender.send_request(req).await.map_err(|e| Error::RequestError(e.to_string()))?;
+        send_event(HttpResponseEvent::FirstByte { duration: sent_at.elapsed().as_millis() as u64 });
+        Ok::<_, Error>(response)
+    };
+
+    let response = match timeouts.total {
+        Some(d) => {
+            tokio::time::timeout(d, exchange).await.map_err(|_| Error::RequestTimeout(d))??
+        }
+        None => exchange.await?,
+    };
+
+    let status = response.status();
+    let version = version_to_str(&response.version());
+    send_event(HttpResponseEvent::Setting("http_version".to_string(), version.clone()));
+    send_event(HttpResponseEvent::ReceiveUrl {
+        version: response.version(),
+        status: status.to_string(),
+    });
+
+    let mut headers = Vec::new();
+    for (key, value) in response.headers() {
+        if let Ok(v) = value.to_str() {
+            send_event(HttpResponseEvent::HeaderDown(key.to_string(), v.to_string()));
+            headers.push((key.to_string(), v.to_string()));
+        }
+    }
+
+    let encodings = ContentEncoding::chain_from_headers(
+        headers
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case("content-encoding"))
+            .map(|(_, v)| v.as_str()),
+    );
+    let content_length = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, v)| v.trim().parse::<u64>().ok());
+
+    let byte_stream =
+        response.into_body().into_data_stream().map(|r| r.map_err(std::io::Error::other));
+    let tracking_reader = TrackingRead::new(StreamReader::new(byte_stream), event_tx)
+        .with_timeouts(timeouts.read, timeouts.total, sent_at);
+    let body_stream = Box::pin(tracking_reader);
+
+    Ok(HttpResponse::new(
+        status.as_u16(),
+        status.canonical_reason().map(|s| s.to_string()),
+        headers,
+        request_headers,
+        content_length,
+        request.url,
+        Some(socket.to_string()),
+        Some(version),
+        body_stream,
+        encodings,
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::sender::{HttpSender, ReqwestSender};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    #[test]
+    fn test_socket_path() {
+        let path = |url: &str| socket_path(&Url::parse(url).unwrap());
+        assert_eq!(
+            path("http+unix://%2Fvar%2Frun%2Fdocker.sock/v1.43/containers/json"),
+            Some("/var/run/docker.sock".to_string())
+        );
+        assert_eq!(
+            path("http+unix://%5C%5C.%5Cpipe%5Cdocker_engine/_ping"),
+            Some(r"\\.\pipe\docker_engine".to_string())
+        );
+        assert_eq!(path("http://localhost/"), None);
+    }
+
+    #[tokio::test]
+    async fn test_send_over_socket() {
+        let dir = std::env::temp_dir().join(format!("yaak-socket-{}", rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("api.sock");
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            let mut byte = [0u8; 1];
+            while !head.ends_with(b"\r\n\r\n") {
+                stream.read_exact(&mut byte).await.unwrap();
+                head.push(byte[0]);
+            }
+            let body = "{\"ok\":true}";
+            let response =
+                format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(head).unwrap()
+        });
+
+        let url = format!(
+            "http+unix://{}/v1.43/containers/json?all=1",
+            urlencoding::encode(socket.to_str().unwrap())
+        );
+        let request = SendableHttpRequest {
+            url: url.clone(),
+            method: "GET".to_string(),
+            headers: vec![("Accept".to_string(), "application/json".to_string())],
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::channel(100);
+        let response = ReqwestSender::new().unwrap().send(request, tx).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.url, url);
+        let (body, _) = response.text().await.unwrap();
+        assert_eq!(body, "{\"ok\":true}");
+
+        let head = server.await.unwrap();
+        assert!(head.starts_with("GET /v1.43/containers/json?all=1 HTTP/1.1\r\n"), "{head}");
+        assert!(head.to_lowercase().contains("host: localhost\r\n"), "{head}");
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert!(events.iter().any(|e| matches!(
+            e,
+            HttpResponseEvent::Setting(name, value)
+                if name == "socket" && value == socket.to_str().unwrap()
+        )));
+        assert!(events.iter().any(|e| matches!(e, HttpResponseEvent::Connected { .. })));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
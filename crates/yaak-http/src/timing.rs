@@ -0,0 +1,330 @@
+//! Timing for the phases of a new connection (DNS, TCP connect, TLS handshake) and the wait
+//! for the first byte of the response.
+//!
+//! reqwest doesn't expose when each phase ends, so they're marked from the inside: the DNS
+//! resolver marks the end of resolution, in a task-local scoped to the connection future by
+//! [`ConnectTimingLayer`], and the start of the TLS handshake comes from
+//! [`yaak_tls::handshake::record_handshake`].
+//!
+//! The connect timeout of a request is enforced here too, since only the connector knows when a
+//! new connection is being established rather than an idle one reused.
+
+use crate::error::Error;
+use crate::sender::HttpResponseEvent;
+use hyper_util::client::legacy::connect::Connection;
+use log::debug;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, mpsc};
+use tower_layer::Layer;
+use tower_service::Service;
+use yaak_models::models::TlsCertificate;
+use yaak_tls::handshake::{HandshakeDetails, describe_certificate, record_handshake};
+
+tokio::task_local! {
+    /// Set while a connection is being established, to the end of DNS resolution
+    static DNS_END: Cell<Option<Instant>>;
+    /// Set while a request is in flight, so the connector can report when the connection
+    /// became ready
+    static CONNECTION_READY: Cell<Option<Instant>>;
+    /// Set while a request is in flight, to how long a new connection may take
+    static CONNECT_TIMEOUT: Option<Duration>;
+}
+
+/// Mark the end of DNS resolution for the connection being established, if any
+pub(crate) fn mark_dns_resolved() {
+    let _ = DNS_END.try_with(|d| d.set(Some(Instant::now())));
+}
+
+/// Run a request, returning its output along with the time a new connection became ready.
+/// The time is `None` when an existing connection was used. Establishing a new connection
+/// fails with [`Error::ConnectTimeout`] once it takes longer than `connect_timeout`.
+pub(crate) async fn track_connection<F: Future>(
+    connect_timeout: Option<Duration>,
+    f: F,
+) -> (F::Output, Option<Instant>) {
+    let tracked = CONNECTION_READY.scope(Cell::new(None), async move {
+        let output = f.await;
+        (output, CONNECTION_READY.with(Cell::get))
+    });
+    CONNECT_TIMEOUT.scope(connect_timeout, tracked).await
+}
+
+/// Peer certificate chains by server name, for handshakes that resumed a session and so
+/// didn't receive the chain again
+type CertificateCache = Arc<Mutex<HashMap<String, Vec<TlsCertificate>>>>;
+
+/// Connector layer that emits [`HttpResponseEvent::Connected`],
+/// [`HttpResponseEvent::TlsHandshake`] and [`HttpResponseEvent::TlsSession`] for every new
+/// connection
+#[derive(Clone)]
+pub(crate) struct ConnectTimingLayer {
+    event_tx: Arc<RwLock<Option<mpsc::Sender<HttpResponseEvent>>>>,
+    certificates: CertificateCache,
+}
+
+impl ConnectTimingLayer {
+    pub(crate) fn new(event_tx: Arc<RwLock<Option<mpsc::Sender<HttpResponseEvent>>>>) -> Self {
+        Self { event_tx, certificates: Default::default() }
+    }
+}
+
+impl<S> Layer<S> for ConnectTimingLayer {
+    type Service = ConnectTimingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectTimingService {
+            inner,
+            event_tx: self.event_tx.clone(),
+            certificates: self.certificates.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ConnectTimingService<S> {
+    inner: S,
+    event_tx: Arc<RwLock<Option<mpsc::Sender<HttpResponseEvent>>>>,
+    certificates: CertificateCache,
+}
+
+impl<S, R> Service<R> for ConnectTimingService<S>
+where
+    S: Service<R> + Clone + Send + 'static,
+    S::Response: Connection + Send + 'static,
+    S::Error: From<io::Error> + Send + 'static,
+    S::Future: Send + 'static,
+    R: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        // Use the service that was polled ready, leaving a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let event_tx = self.event_tx.clone();
+        let certificates = self.certificates.clone();
+        let connect_timeout = CONNECT_TIMEOUT.try_with(|t| *t).ok().flatten();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let ((result, dns_end), handshake) =
+                record_handshake(DNS_END.scope(Cell::new(None), async move {
+                    let result = match connect_timeout {
+                        Some(d) => tokio::time::timeout(d, inner.call(req))
+                            .await
+                            .unwrap_or_else(|_| Err(connect_timed_out(d).into())),
+                        None => inner.call(req).await,
+                    };
+                    (result, DNS_END.with(Cell::get))
+                }))
+                .await;
+            let end = Instant::now();
+
+            let mut events = Vec::new();
+            match &result {
+                Ok(conn) => {
+                    let _ = CONNECTION_READY.try_with(|r| r.set(Some(end)));
+
+                    // Addresses without a hostname skip DNS, so connecting starts right away
+                    let connect_start = dns_end.unwrap_or(start);
+                    match handshake.started_at {
+                        Some(tls_start) => {
+                            events.push(HttpResponseEvent::Connected {
+                                duration: millis(
+                                    tls_start.saturating_duration_since(connect_start),
+                                ),
+                            });
+                            events.push(HttpResponseEvent::TlsHandshake {
+                                duration: millis(end.saturating_duration_since(tls_start)),
+                            });
+                        }
+                        None => events.push(HttpResponseEvent::Connected {
+                            duration: millis(end.saturating_duration_since(connect_start)),
+                        }),
+                    }
+                    let h2 = conn.connected().is_negotiated_h2();
+                    events.extend(tls_session_event(handshake, h2, &certificates));
+                    debug!("Connection established {events:?}");
+                }
+                // Still report the session of a failed handshake, since the chain is what's
+                // needed to diagnose a rejected certificate
+                Err(_) => events.extend(tls_session_event(handshake, false, &certificates)),
+            }
+
+            let guard = event_tx.read().await;
+            if let Some(tx) = guard.as_ref() {
+                for event in events {
+                    let _ = tx.send(event).await;
+                }
+            }
+
+            result
+        })
+    }
+}
+
+/// The error of a connection that took too long. It's an IO error so reqwest sees a timeout
+/// while connecting, and the sender can tell it apart from the request timing out.
+fn connect_timed_out(timeout: Duration) -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, Error::ConnectTimeout(timeout))
+}
+
+fn millis(d: Duration) -> u64 {
+    d.as_millis() as u64
+}
+
+fn tls_session_event(
+    handshake: HandshakeDetails,
+    negotiated_h2: bool,
+    certificates: &CertificateCache,
+) -> Option<HttpResponseEvent> {
+    let version = handshake.version()?;
+    let cipher_suite = handshake.cipher_suite_name()?;
+    let server_name = handshake.server_name.unwrap_or_default();
+
+    let mut cache = certificates.lock().unwrap_or_else(|e| e.into_inner());
+    let certificates = if handshake.peer_certificates.is_empty() {
+        cache.get(&server_name).cloned().unwrap_or_default()
+    } else {
+        let chain: Vec<TlsCertificate> =
+            handshake.peer_certificates.iter().map(describe_certificate).collect();
+        cache.insert(server_name.clone(), chain.clone());
+        chain
+    };
+
+    Some(HttpResponseEvent::TlsSession {
+        version,
+        cipher_suite,
+        // Only h2 can be told apart, so anything else is reported as the HTTP/1.1 fallback
+        alpn: if negotiated_h2 { "h2" } else { "http/1.1" }.to_string(),
+        server_name,
+        certificates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_track_connection_without_connect() {
+        let (output, ready) = track_connection(None, async { 42 }).await;
+        assert_eq!(output, 42);
+        assert!(ready.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connect_timing_events() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let layer = ConnectTimingLayer::new(Arc::new(RwLock::new(Some(tx))));
+        let mut service = layer.layer(ServiceFn(|_: ()| async {
+            mark_dns_resolved();
+            Ok::<_, io::Error>(TestConn)
+        }));
+
+        let (result, ready) = track_connection(None, service.call(())).await;
+        assert!(result.is_ok());
+        assert!(ready.is_some());
+        assert!(matches!(rx.recv().await, Some(HttpResponseEvent::Connected { .. })));
+        drop(layer);
+        drop(service);
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout() {
+        let layer = ConnectTimingLayer::new(Arc::new(RwLock::new(None)));
+        let mut service = layer.layer(ServiceFn(|_: ()| async {
+            std::future::pending::<Result<TestConn, io::Error>>().await
+        }));
+
+        // The connector is called while the request is polled, so it sees the timeout
+        let timeout = Some(Duration::from_millis(10));
+        let (result, ready) = track_connection(timeout, async { service.call(()).await }).await;
+        let err = result.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(err.to_string(), "Connect timeout of 10ms reached");
+        assert!(ready.is_none());
+    }
+
+    #[test]
+    fn test_tls_session_reuses_chain_on_resumption() {
+        let cache = CertificateCache::default();
+        let handshake = HandshakeDetails {
+            started_at: Some(Instant::now()),
+            server_name: Some("example.com".to_string()),
+            cipher_suite: Some(rustls::crypto::ring::cipher_suite::TLS13_AES_128_GCM_SHA256),
+            peer_certificates: vec![b"not a certificate".to_vec().into()],
+        };
+
+        let full = tls_session_event(handshake.clone(), true, &cache);
+        let resumed = tls_session_event(
+            HandshakeDetails { peer_certificates: Vec::new(), ..handshake },
+            false,
+            &cache,
+        );
+
+        match (full, resumed) {
+            (
+                Some(HttpResponseEvent::TlsSession { version, alpn, certificates: full, .. }),
+                Some(HttpResponseEvent::TlsSession { alpn: resumed_alpn, certificates, .. }),
+            ) => {
+                assert_eq!(version, "TLSv1.3");
+                assert_eq!(alpn, "h2");
+                assert_eq!(resumed_alpn, "http/1.1");
+                assert_eq!(full.len(), 1);
+                assert_eq!(certificates, full);
+            }
+            events => panic!("Unexpected events {events:?}"),
+        }
+    }
+
+    #[test]
+    fn test_no_tls_session_without_handshake() {
+        let cache = CertificateCache::default();
+        assert!(tls_session_event(HandshakeDetails::default(), false, &cache).is_none());
+    }
+
+    struct TestConn;
+
+    impl Connection for TestConn {
+        fn connected(&self) -> hyper_util::client::legacy::connect::Connected {
+            hyper_util::client::legacy::connect::Connected::new()
+        }
+    }
+
+    /// Minimal `service_fn`, to avoid depending on tower for tests
+    #[derive(Clone)]
+    struct ServiceFn<F>(F);
+
+    impl<F, Fut, T, E> Service<()> for ServiceFn<F>
+    where
+        F: Fn(()) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        type Response = T;
+        type Error = E;
+        type Future = Fut;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), E>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: ()) -> Fut {
+            (self.0)(req)
+        }
+    }
+}
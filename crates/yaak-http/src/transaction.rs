@@ -1,33 +1,39 @@
 use crate::cookies::CookieStore;
 use crate::error::Result;
+use crate::retry::{backoff_delay, retry_reason};
 use crate::sender::{HttpResponse, HttpResponseEvent, HttpSender, RedirectBehavior};
-use crate::types::SendableHttpRequest;
+use crate::types::{SendableBody, SendableHttpRequest};
 use log::debug;
 use tokio::sync::mpsc;
 use tokio::sync::watch::Receiver;
 use url::Url;
+use yaak_models::models::{RedirectPolicy, RetryPolicy};
 
-/// HTTP Transaction that manages the lifecycle of a request, including redirect handling
+/// HTTP Transaction that manages the lifecycle of a request, including redirect handling and
+/// retries
 pub struct HttpTransaction<S: HttpSender> {
     sender: S,
     max_redirects: usize,
+    keep_cross_origin_credentials: bool,
+    convert_post_to_get: bool,
     cookie_store: Option<CookieStore>,
+    retry_policy: RetryPolicy,
 }
 
 impl<S: HttpSender> HttpTransaction<S> {
     /// Create a new transaction with default settings
     pub fn new(sender: S) -> Self {
-        Self { sender, max_redirects: 10, cookie_store: None }
+        Self::with_options(sender, 10, None)
     }
 
     /// Create a new transaction with custom max redirects
     pub fn with_max_redirects(sender: S, max_redirects: usize) -> Self {
-        Self { sender, max_redirects, cookie_store: None }
+        Self::with_options(sender, max_redirects, None)
     }
 
     /// Create a new transaction with a cookie store
     pub fn with_cookie_store(sender: S, cookie_store: CookieStore) -> Self {
-        Self { sender, max_redirects: 10, cookie_store: Some(cookie_store) }
+        Self::with_options(sender, 10, Some(cookie_store))
     }
 
     /// Create a new transaction with custom max redirects and a cookie store
@@ -36,7 +42,28 @@ impl<S: HttpSender> HttpTransaction<S> {
         max_redirects: usize,
         cookie_store: Option<CookieStore>,
     ) -> Self {
-        Self { sender, max_redirects, cookie_store }
+        Self {
+            sender,
+            max_redirects,
+            keep_cross_origin_credentials: false,
+            convert_post_to_get: true,
+            cookie_store,
+            retry_policy: Default::default(),
+        }
+    }
+
+    /// Follow redirects according to the policy, replacing the max redirects
+    pub fn with_redirect_policy(mut self, redirect_policy: RedirectPolicy) -> Self {
+        self.max_redirects = redirect_policy.max_redirects as usize;
+        self.keep_cross_origin_credentials = redirect_policy.keep_cross_origin_credentials;
+        self.convert_post_to_get = redirect_policy.convert_post_to_get;
+        self
+    }
+
+    /// Send failed requests again according to the policy. By default, they're sent once.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
     }
 
     /// Execute the request with cancellation support.
@@ -54,6 +81,10 @@ impl<S: HttpSender> HttpTransaction<S> {
         let mut current_headers = request.headers;
         let mut current_body = request.body;
 
+        // Cookies are sent as if the request was made from the site of the first URL, so
+        // redirects to other sites don't get SameSite cookies
+        let initiator = Url::parse(&current_url).ok();
+
         // Helper to send events (ignores errors if receiver is dropped or channel is full)
         let send_event = |event: HttpResponseEvent| {
             let _ = event_tx.try_send(event);
@@ -69,7 +100,10 @@ impl<S: HttpSender> HttpTransaction<S> {
             let headers_with_cookies = if let Some(cookie_store) = &self.cookie_store {
                 let mut headers = current_headers.clone();
                 if let Ok(url) = Url::parse(&current_url) {
-                    if let Some(cookie_header) = cookie_store.get_cookie_header(&url) {
+                    let initiator = initiator.as_ref().unwrap_or(&url);
+                    if let Some(cookie_header) =
+                        cookie_store.get_cookie_header_from(&url, initiator, &current_method)
+                    {
                         debug!("Injecting Cookie header: {}", cookie_header);
                         // Check if there's already a Cookie header and merge if so
                         if let Some(existing) =
@@ -86,26 +120,83 @@ impl<S: HttpSender> HttpTransaction<S> {
                 current_headers.clone()
             };
 
-            // Build request for this iteration
-            let req = SendableHttpRequest {
-                url: current_url.clone(),
-                method: current_method.clone(),
-                headers: headers_with_cookies,
-                body: current_body,
-                options: request.options.clone(),
-            };
+            // Streamed bodies are consumed by the first attempt, so they can't be retried
+            let replayable = !matches!(current_body, Some(SendableBody::Stream(_)));
 
-            // Send the request
             send_event(HttpResponseEvent::Setting(
                 "redirects".to_string(),
                 request.options.follow_redirects.to_string(),
             ));
 
-            // Execute with cancellation support
-            let response = tokio::select! {
-                result = self.sender.send(req, event_tx.clone()) => result?,
-                _ = cancelled_rx.changed() => {
-                    return Err(crate::error::Error::RequestCanceledError);
+            let mut attempt = 1;
+            let response = loop {
+                // Build request for this attempt, copying the body in case it's sent again
+                let body = match &current_body {
+                    Some(SendableBody::Bytes(bytes)) => Some(SendableBody::Bytes(bytes.clone())),
+                    _ => current_body.take(),
+                };
+                let req = SendableHttpRequest {
+                    url: current_url.clone(),
+                    method: current_method.clone(),
+                    headers: headers_with_cookies.clone(),
+                    body,
+                    options: request.options.clone(),
+                };
+
+                // Execute with cancellation support
+                let result = tokio::select! {
+                    result = self.sender.send(req, event_tx.clone()) => result,
+                    _ = cancelled_rx.changed() => {
+                        return Err(crate::error::Error::RequestCanceledError);
+                    }
+                };
+
+                let policy = &self.retry_policy;
+                if attempt >= policy.max_attempts {
+                    break result?;
+                }
+                let Some(reason) = retry_reason(policy, &result) else {
+                    break result?;
+                };
+                if !replayable {
+                    send_event(HttpResponseEvent::Info(format!(
+                        "Not retrying after {}, the streamed request body can't be sent again",
+                        reason.message
+                    )));
+                    break result?;
+                }
+
+                let delay = match reason.retry_after {
+                    Some(retry_after) if policy.respect_retry_after => retry_after,
+                    _ => backoff_delay(policy, attempt),
+                };
+                if delay.as_millis() > policy.max_delay as u128 {
+                    send_event(HttpResponseEvent::Info(format!(
+                        "Not retrying after {}, Retry-After of {}s is over the maximum delay",
+                        reason.message,
+                        delay.as_secs()
+                    )));
+                    break result?;
+                }
+
+                // Drain the failed response before sending the request again
+                if let Ok(response) = result {
+                    let _ = response.drain().await;
+                }
+
+                attempt += 1;
+                send_event(HttpResponseEvent::Retry {
+                    attempt,
+                    max_attempts: policy.max_attempts,
+                    delay: delay.as_millis() as u64,
+                    reason: reason.message,
+                });
+
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {},
+                    _ = cancelled_rx.changed() => {
+                        return Err(crate::error::Error::RequestCanceledError);
+                    }
                 }
             };
 
@@ -182,13 +273,18 @@ impl<S: HttpSender> HttpTransaction<S> {
                 format!("{}/{}", base_path, location)
             };
 
-            Self::remove_sensitive_headers(&mut current_headers, &previous_url, &current_url);
+            if !self.keep_cross_origin_credentials {
+                Self::remove_sensitive_headers(&mut current_headers, &previous_url, &current_url);
+            }
 
             // Determine redirect behavior based on status code and method
             let behavior = if status == 303 {
                 // 303 See Other always changes to GET
                 RedirectBehavior::DropBody
-            } else if (status == 301 || status == 302) && current_method == "POST" {
+            } else if (status == 301 || status == 302)
+                && current_method == "POST"
+                && self.convert_post_to_get
+            {
                 // For 301/302, change POST to GET (common browser behavior)
                 RedirectBehavior::DropBody
             } else {
@@ -213,12 +309,11 @@ impl<S: HttpSender> HttpTransaction<S> {
                     let name_lower = h.0.to_lowercase();
                     !name_lower.starts_with("content-") && name_lower != "transfer-encoding"
                 });
+                // Buffered bodies are kept for the other redirects, while streamed ones were
+                // consumed by the send call
+                current_body = None;
             }
 
-            // Reset body for next iteration (since it was moved in the send call)
-            // For redirects that change method to GET or for all redirects since body was consumed
-            current_body = None;
-
             redirect_count += 1;
         }
     }
@@ -291,7 +386,6 @@ impl<S: HttpSender> HttpTransaction<S> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::decompress::ContentEncoding;
     use crate::sender::{HttpResponseEvent, HttpSender};
     use async_trait::async_trait;
     use std::pin::Pin;
@@ -362,7 +456,7 @@ mod tests {
                     None,                              // remote_addr
                     Some("HTTP/1.1".to_string()),      // version
                     body_stream,
-                    ContentEncoding::Identity,
+                    Vec::new(),
                 ))
             }
         }
@@ -456,6 +550,103 @@ mod tests {
         }
     }
 
+    fn retry_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy { max_attempts, initial_delay: 0, jitter: false, ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn test_transaction_retries_retryable_status() {
+        let responses = vec![
+            MockResponse {
+                status: 503,
+                headers: vec![("Retry-After".to_string(), "0".to_string())],
+                body: vec![],
+            },
+            MockResponse { status: 502, headers: Vec::new(), body: vec![] },
+            MockResponse { status: 200, headers: Vec::new(), body: b"OK".to_vec() },
+        ];
+
+        let sender = MockSender::new(responses);
+        let captured = sender.captured_requests.clone();
+        let transaction = HttpTransaction::new(sender).with_retry_policy(retry_policy(3));
+
+        let request = SendableHttpRequest {
+            url: "https://example.com".to_string(),
+            method: "POST".to_string(),
+            body: Some(SendableBody::Bytes(bytes::Bytes::from("hello"))),
+            ..Default::default()
+        };
+
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let (event_tx, mut event_rx) = mpsc::channel(100);
+        let result = transaction.execute_with_cancellation(request, rx, event_tx).await.unwrap();
+        assert_eq!(result.status, 200);
+        assert_eq!(captured.lock().await.len(), 3);
+
+        let mut retries = Vec::new();
+        let mut redirect_settings = 0;
+        while let Ok(event) = event_rx.try_recv() {
+            match event {
+                HttpResponseEvent::Retry { attempt, reason, .. } => retries.push((attempt, reason)),
+                HttpResponseEvent::Setting(name, _) if name == "redirects" => {
+                    redirect_settings += 1
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(retries, vec![(2, "status 503".to_string()), (3, "status 502".to_string())]);
+        // Settings are reported once per hop, not per attempt
+        assert_eq!(redirect_settings, 1);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_retry_stops_at_max_attempts() {
+        let responses: Vec<MockResponse> = (0..3)
+            .map(|_| MockResponse { status: 503, headers: Vec::new(), body: vec![] })
+            .collect();
+
+        let sender = MockSender::new(responses);
+        let captured = sender.captured_requests.clone();
+        let transaction = HttpTransaction::new(sender).with_retry_policy(retry_policy(2));
+
+        let request = SendableHttpRequest {
+            url: "https://example.com".to_string(),
+            method: "GET".to_string(),
+            ..Default::default()
+        };
+
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let (event_tx, _event_rx) = mpsc::channel(100);
+        let result = transaction.execute_with_cancellation(request, rx, event_tx).await.unwrap();
+        assert_eq!(result.status, 503);
+        assert_eq!(captured.lock().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_does_not_retry_streamed_body() {
+        let responses = vec![
+            MockResponse { status: 503, headers: Vec::new(), body: vec![] },
+            MockResponse { status: 200, headers: Vec::new(), body: vec![] },
+        ];
+
+        let sender = MockSender::new(responses);
+        let captured = sender.captured_requests.clone();
+        let transaction = HttpTransaction::new(sender).with_retry_policy(retry_policy(3));
+
+        let request = SendableHttpRequest {
+            url: "https://example.com".to_string(),
+            method: "PUT".to_string(),
+            body: Some(SendableBody::Stream(Box::pin(std::io::Cursor::new(b"hello".to_vec())))),
+            ..Default::default()
+        };
+
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let (event_tx, _event_rx) = mpsc::channel(100);
+        let result = transaction.execute_with_cancellation(request, rx, event_tx).await.unwrap();
+        assert_eq!(result.status, 503);
+        assert_eq!(captured.lock().await.len(), 1);
+    }
+
     #[test]
     fn test_is_redirect() {
         assert!(HttpTransaction::<MockSender>::is_redirect(301));
@@ -531,19 +722,23 @@ mod tests {
                     None,
                     Some("HTTP/1.1".to_string()),
                     body_stream,
-                    ContentEncoding::Identity,
+                    Vec::new(),
                 ))
             }
         }
 
-        use yaak_models::models::{Cookie, CookieDomain, CookieExpires};
+        use yaak_models::models::{Cookie, CookieDomain, CookieExpires, CookieSameSite};
 
         // Create a cookie store with a test cookie
         let cookie = Cookie {
-            raw_cookie: "session=abc123".to_string(),
+            name: "session".to_string(),
+            value: "abc123".to_string(),
             domain: CookieDomain::HostOnly("example.com".to_string()),
             expires: CookieExpires::SessionEnd,
             path: ("/".to_string(), false),
+            secure: false,
+            http_only: false,
+            same_site: CookieSameSite::Unspecified,
         };
         let cookie_store = CookieStore::from_cookies(vec![cookie]);
 
@@ -593,7 +788,7 @@ mod tests {
                     None,
                     Some("HTTP/1.1".to_string()),
                     body_stream,
-                    ContentEncoding::Identity,
+                    Vec::new(),
                 ))
             }
         }
@@ -616,7 +811,7 @@ mod tests {
         // Verify the cookie was stored
         let cookies = cookie_store.get_all_cookies();
         assert_eq!(cookies.len(), 1);
-        assert!(cookies[0].raw_cookie.contains("session=xyz789"));
+        assert_eq!((cookies[0].name.as_str(), cookies[0].value.as_str()), ("session", "xyz789"));
     }
 
     #[tokio::test]
@@ -656,7 +851,7 @@ mod tests {
                     None,
                     Some("HTTP/1.1".to_string()),
                     body_stream,
-                    ContentEncoding::Identity,
+                    Vec::new(),
                 ))
             }
         }
@@ -680,7 +875,8 @@ mod tests {
         let cookies = cookie_store.get_all_cookies();
         assert_eq!(cookies.len(), 3, "All three Set-Cookie headers should be parsed and stored");
 
-        let cookie_values: Vec<&str> = cookies.iter().map(|c| c.raw_cookie.as_str()).collect();
+        let cookie_values: Vec<String> =
+            cookies.iter().map(|c| format!("{}={}", c.name, c.value)).collect();
         assert!(
             cookie_values.iter().any(|c| c.contains("session=abc123")),
             "session cookie should be stored"
@@ -752,7 +948,7 @@ mod tests {
                     None,
                     Some("HTTP/1.1".to_string()),
                     body_stream,
-                    ContentEncoding::Identity,
+                    Vec::new(),
                 ))
             }
         }
@@ -889,4 +1085,109 @@ mod tests {
             "Redirected request to same host should preserve Authorization header"
         );
     }
+
+    #[tokio::test]
+    async fn test_redirect_policy_keeps_cross_origin_credentials() {
+        let responses = vec![
+            MockResponse {
+                status: 302,
+                headers: vec![("Location".to_string(), "https://other.example.org/".to_string())],
+                body: vec![],
+            },
+            MockResponse { status: 200, headers: Vec::new(), body: vec![] },
+        ];
+
+        let sender = MockSender::new(responses);
+        let captured = sender.captured_requests.clone();
+        let policy = RedirectPolicy { keep_cross_origin_credentials: true, ..Default::default() };
+        let transaction = HttpTransaction::new(sender).with_redirect_policy(policy);
+
+        let request = SendableHttpRequest {
+            url: "https://api.example.com/".to_string(),
+            method: "GET".to_string(),
+            headers: vec![("Authorization".to_string(), "Bearer token".to_string())],
+            options: crate::types::SendableHttpRequestOptions {
+                follow_redirects: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let (event_tx, _event_rx) = mpsc::channel(100);
+        let result = transaction.execute_with_cancellation(request, rx, event_tx).await.unwrap();
+        assert_eq!(result.status, 200);
+
+        let requests = captured.lock().await;
+        assert!(requests[1].headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("authorization")));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_policy_preserves_post() {
+        let responses = vec![
+            MockResponse {
+                status: 301,
+                headers: vec![("Location".to_string(), "/moved".to_string())],
+                body: vec![],
+            },
+            MockResponse { status: 200, headers: Vec::new(), body: vec![] },
+        ];
+
+        let sender = MockSender::new(responses);
+        let captured = sender.captured_requests.clone();
+        let policy = RedirectPolicy { convert_post_to_get: false, ..Default::default() };
+        let transaction = HttpTransaction::new(sender).with_redirect_policy(policy);
+
+        let request = SendableHttpRequest {
+            url: "https://example.com/form".to_string(),
+            method: "POST".to_string(),
+            headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+            body: Some(SendableBody::Bytes(bytes::Bytes::from("hello"))),
+            options: crate::types::SendableHttpRequestOptions {
+                follow_redirects: true,
+                ..Default::default()
+            },
+        };
+
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let (event_tx, _event_rx) = mpsc::channel(100);
+        transaction.execute_with_cancellation(request, rx, event_tx).await.unwrap();
+
+        let requests = captured.lock().await;
+        assert_eq!(requests[1].url, "https://example.com/moved");
+        assert_eq!(requests[1].method, "POST");
+        assert!(requests[1].headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("content-type")));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_policy_max_redirects() {
+        let responses: Vec<MockResponse> = (0..3)
+            .map(|_| MockResponse {
+                status: 302,
+                headers: vec![("Location".to_string(), "https://example.com/loop".to_string())],
+                body: vec![],
+            })
+            .collect();
+
+        let sender = MockSender::new(responses);
+        let policy = RedirectPolicy { max_redirects: 1, ..Default::default() };
+        let transaction = HttpTransaction::new(sender).with_redirect_policy(policy);
+
+        let request = SendableHttpRequest {
+            url: "https://example.com/start".to_string(),
+            method: "GET".to_string(),
+            options: crate::types::SendableHttpRequestOptions {
+                follow_redirects: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let (event_tx, _event_rx) = mpsc::channel(100);
+        let result = transaction.execute_with_cancellation(request, rx, event_tx).await;
+        assert!(
+            matches!(result, Err(crate::error::Error::RequestError(msg)) if msg.contains("(1)"))
+        );
+    }
 }
@@ -0,0 +1,202 @@
+//! TCP connections through the configured proxy, for protocols that don't go through reqwest
+//! (WebSockets and gRPC). HTTP proxies are tunnelled through with `CONNECT`, and SOCKS5 proxies
+//! with the SOCKS handshake. The system proxy comes from the `HTTPS_PROXY`, `HTTP_PROXY`,
+//! `ALL_PROXY` and `NO_PROXY` environment variables, like reqwest's, but proxies configured in
+//! the OS settings aren't detected. Connections to the host or proxy are made with the
+//! workspace's network settings.
+
+use crate::error::{Error, Result};
+use crate::net::connect_tcp;
+use crate::proxy::ProxySelector;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use log::info;
+use std::borrow::Cow;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_socks::TargetAddr;
+use tokio_socks::tcp::Socks5Stream;
+use url::Url;
+use yaak_models::models::NetworkSettings;
+
+/// Proxies answer `CONNECT` with a short response head, so anything longer is an error
+const MAX_CONNECT_RESPONSE_SIZE: usize = 16 * 1024;
+
+/// Open a TCP connection to the host of the URL, through the proxy chosen for it
+pub async fn connect(
+    proxy: &ProxySelector,
+    url: &Url,
+    network: &NetworkSettings,
+) -> Result<TcpStream> {
+    let host = url.host_str().unwrap_or_default().trim_matches(['[', ']']);
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| Error::ProxyError(format!("Missing port for {url}")))?;
+
+    let (proxy_url, description) = proxy.select(url).await;
+    info!("Connecting to {host}:{port} with proxy {description}");
+
+    let Some(proxy_url) = proxy_url else {
+        return connect_tcp(host, port, network).await;
+    };
+    match proxy_url.scheme() {
+        "http" => http_connect(&proxy_url, host, port, network).await,
+        "socks5" | "socks5h" => socks5_connect(&proxy_url, host, port, network).await,
+        scheme => {
+            Err(Error::ProxyError(format!("{scheme} proxies aren't supported for this connection")))
+        }
+    }
+}
+
+/// Tunnel through an HTTP proxy with a `CONNECT` request
+async fn http_connect(
+    proxy: &Url,
+    host: &str,
+    port: u16,
+    network: &NetworkSettings,
+) -> Result<TcpStream> {
+    let proxy_host = proxy.host_str().unwrap_or_default().trim_matches(['[', ']']);
+    let mut stream = connect_tcp(proxy_host, proxy.port().unwrap_or(80), network).await?;
+
+    let authority =
+        if host.contains(':') { format!("[{host}]:{port}") } else { format!("{host}:{port}") };
+    let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+    if let Some((user, password)) = credentials(proxy) {
+        let token = BASE64_STANDARD.encode(format!("{user}:{password}"));
+        request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response head a byte at a time, so nothing after it is consumed
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if stream.read(&mut byte).await? == 0 {
+            return Err(Error::ProxyError("Proxy closed the connection".to_string()));
+        }
+        head.push(byte[0]);
+        if head.len() > MAX_CONNECT_RESPONSE_SIZE {
+            return Err(Error::ProxyError("Proxy response is too large".to_string()));
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let status_line = head.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(stream),
+        _ => Err(Error::ProxyError(format!(
+            "Proxy refused to connect to {authority}: {status_line}"
+        ))),
+    }
+}
+
+/// Connect through a SOCKS5 proxy. With socks5h, the proxy resolves the hostname.
+async fn socks5_connect(
+    proxy: &Url,
+    host: &str,
+    port: u16,
+    network: &NetworkSettings,
+) -> Result<TcpStream> {
+    let proxy_host = proxy.host_str().unwrap_or_default().trim_matches(['[', ']']);
+    let socket = connect_tcp(proxy_host, proxy.port().unwrap_or(1080), network).await?;
+
+    let target = match proxy.scheme() {
+        "socks5h" => TargetAddr::Domain(Cow::Borrowed(host), port),
+        _ => TargetAddr::Ip(
+            tokio::net::lookup_host((host, port))
+                .await?
+                .find(|a| network.ip_family.allows(&a.ip()))
+                .ok_or_else(|| Error::ProxyError(format!("Failed to resolve {host}")))?,
+        ),
+    };
+
+    let stream = match credentials(proxy) {
+        Some((user, password)) => {
+            Socks5Stream::connect_with_password_and_socket(socket, target, &user, &password).await
+        }
+        None => Socks5Stream::connect_with_socket(socket, target).await,
+    }
+    .map_err(|e| Error::ProxyError(format!("SOCKS5 proxy failed to connect to {host}: {e}")))?;
+
+    Ok(stream.into_inner())
+}
+
+/// The decoded user and password of a proxy URL
+fn credentials(proxy: &Url) -> Option<(String, String)> {
+    if proxy.username().is_empty() {
+        return None;
+    }
+    let decode = |v: &str| urlencoding::decode(v).map(|v| v.into_owned()).unwrap_or(v.to_string());
+    Some((decode(proxy.username()), decode(proxy.password().unwrap_or_default())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{HttpConnectionProxySetting, HttpConnectionProxySettingAuth};
+    use tokio::net::TcpListener;
+
+    /// A proxy that accepts one CONNECT request, replies with the status and then echoes
+    async fn start_connect_proxy(status: &'static str) -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut head = Vec::new();
+            let mut byte = [0u8; 1];
+            while !head.ends_with(b"\r\n\r\n") {
+                stream.read_exact(&mut byte).await.unwrap();
+                head.push(byte[0]);
+            }
+            stream.write_all(format!("HTTP/1.1 {status}\r\n\r\n").as_bytes()).await.unwrap();
+            let mut buf = [0u8; 4];
+            if stream.read_exact(&mut buf).await.is_ok() {
+                stream.write_all(&buf).await.unwrap();
+            }
+            String::from_utf8(head).unwrap()
+        });
+        (port, handle)
+    }
+
+    async fn selector(port: u16) -> ProxySelector {
+        ProxySelector::new(&HttpConnectionProxySetting::Enabled {
+            http: format!("127.0.0.1:{port}"),
+            https: format!("127.0.0.1:{port}"),
+            auth: Some(HttpConnectionProxySettingAuth {
+                user: "user".to_string(),
+                password: "p@ss".to_string(),
+            }),
+            bypass: "".to_string(),
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_http_connect() {
+        let (port, handle) = start_connect_proxy("200 Connection established").await;
+        let url = Url::parse("wss://example.com/socket").unwrap();
+        let mut stream =
+            connect(&selector(port).await, &url, &NetworkSettings::default()).await.unwrap();
+
+        stream.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        let head = handle.await.unwrap();
+        assert!(head.starts_with("CONNECT example.com:443 HTTP/1.1\r\n"));
+        // Base64 of user:p@ss
+        assert!(head.contains("Proxy-Authorization: Basic dXNlcjpwQHNz\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_http_connect_refused() {
+        let (port, _handle) = start_connect_proxy("407 Proxy Authentication Required").await;
+        let url = Url::parse("ws://example.com/socket").unwrap();
+        let err =
+            connect(&selector(port).await, &url, &NetworkSettings::default()).await.unwrap_err();
+        assert!(err.to_string().contains("407 Proxy Authentication Required"), "{err}");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::chained_reader::{ChainedReader, ReaderType};
+use crate::compress::{compress, streaming_encoder};
 use crate::error::Error::RequestError;
 use crate::error::Result;
 use crate::path_placeholders::apply_path_placeholders;
@@ -8,9 +9,9 @@ use log::warn;
 use std::collections::BTreeMap;
 use std::pin::Pin;
 use std::time::Duration;
-use tokio::io::AsyncRead;
+use tokio::io::{AsyncRead, BufReader};
 use yaak_common::serde::{get_bool, get_str, get_str_map};
-use yaak_models::models::HttpRequest;
+use yaak_models::models::{BodyCompression, HttpRequest};
 
 pub(crate) const MULTIPART_BOUNDARY: &str = "------YaakFormBoundary";
 
@@ -47,7 +48,12 @@ pub struct SendableHttpRequest {
 
 #[derive(Default, Clone)]
 pub struct SendableHttpRequestOptions {
+    /// Deadline for the whole request, from sending it to reading the end of the body
     pub timeout: Option<Duration>,
+    /// How long to wait for a new connection to be established
+    pub connect_timeout: Option<Duration>,
+    /// How long to wait for the next chunk of the response body
+    pub read_timeout: Option<Duration>,
     pub follow_redirects: bool,
 }
 
@@ -57,7 +63,9 @@ impl SendableHttpRequest {
         options: SendableHttpRequestOptions,
     ) -> Result<Self> {
         let initial_headers = build_headers(r);
-        let (body, headers) = build_body(&r.method, &r.body_type, &r.body, initial_headers).await?;
+        let (body, headers) =
+            build_body(&r.method, &r.body_type, &r.body, r.body_compression, initial_headers)
+                .await?;
 
         Ok(Self {
             url: build_url(r),
@@ -155,6 +163,7 @@ async fn build_body(
     method: &str,
     body_type: &Option<String>,
     body: &BTreeMap<String, serde_json::Value>,
+    compression: Option<BodyCompression>,
     headers: Vec<(String, String)>,
 ) -> Result<(Option<SendableBody>, Vec<(String, String)>)> {
     let body_type = match &body_type {
@@ -186,6 +195,16 @@ async fn build_body(
         }
     }
 
+    // Compress the body and replace any Content-Encoding header with the one applied
+    let body = match (body, compression) {
+        (Some(body), Some(compression)) => {
+            headers.retain(|h| !h.0.eq_ignore_ascii_case("content-encoding"));
+            headers.push(("Content-Encoding".to_string(), compression.to_string()));
+            Some(compress_body(body, compression)?)
+        }
+        (body, _) => body,
+    };
+
     // Check if Transfer-Encoding: chunked is already set
     let has_chunked_encoding = headers.iter().any(|h| {
         h.0.to_lowercase() == "transfer-encoding" && h.1.to_lowercase().contains("chunked")
@@ -207,6 +226,21 @@ async fn build_body(
     Ok((body.map(|b| b.into()), headers))
 }
 
+fn compress_body(
+    body: SendableBodyWithMeta,
+    compression: BodyCompression,
+) -> Result<SendableBodyWithMeta> {
+    Ok(match body {
+        SendableBodyWithMeta::Bytes(bytes) => {
+            SendableBodyWithMeta::Bytes(Bytes::from(compress(&bytes, compression)?))
+        }
+        SendableBodyWithMeta::Stream { data, .. } => SendableBodyWithMeta::Stream {
+            data: streaming_encoder(BufReader::new(data), compression),
+            content_length: None,
+        },
+    })
+}
+
 fn build_form_body(body: &BTreeMap<String, serde_json::Value>) -> Option<SendableBodyWithMeta> {
     let form_params = match body.get("form").map(|f| f.as_array()) {
         Some(Some(f)) => f,
@@ -422,6 +456,7 @@ fn extract_boundary_from_headers(headers: &Vec<(String, String)>) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::decompress::{ContentEncoding, decompress};
     use bytes::Bytes;
     use serde_json::json;
     use std::collections::BTreeMap;
@@ -936,7 +971,7 @@ mod tests {
         let headers = vec![("Transfer-Encoding".to_string(), "chunked".to_string())];
 
         let (_, result_headers) =
-            build_body("POST", &Some("text/plain".to_string()), &body, headers).await?;
+            build_body("POST", &Some("text/plain".to_string()), &body, None, headers).await?;
 
         // Verify that Content-Length is NOT present when Transfer-Encoding: chunked is set
         let has_content_length =
@@ -961,7 +996,7 @@ mod tests {
         let headers = vec![];
 
         let (_, result_headers) =
-            build_body("POST", &Some("text/plain".to_string()), &body, headers).await?;
+            build_body("POST", &Some("text/plain".to_string()), &body, None, headers).await?;
 
         // Verify that Content-Length IS present when Transfer-Encoding: chunked is NOT set
         let content_length_header =
@@ -978,4 +1013,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_compressed_body() -> Result<()> {
+        let mut body = BTreeMap::new();
+        body.insert("text".to_string(), json!("Hello, World!"));
+
+        let headers = vec![("content-encoding".to_string(), "identity".to_string())];
+
+        let (result_body, result_headers) = build_body(
+            "POST",
+            &Some("text/plain".to_string()),
+            &body,
+            Some(BodyCompression::Gzip),
+            headers,
+        )
+        .await?;
+
+        let compressed = match result_body {
+            Some(SendableBody::Bytes(b)) => b,
+            _ => panic!("Expected a bytes body"),
+        };
+        let decompressed = decompress(compressed.to_vec(), &[ContentEncoding::Gzip])?;
+        assert_eq!(decompressed.data, b"Hello, World!");
+
+        // The existing Content-Encoding header is replaced
+        let encodings: Vec<_> = result_headers
+            .iter()
+            .filter(|h| h.0.eq_ignore_ascii_case("content-encoding"))
+            .map(|h| h.1.as_str())
+            .collect();
+        assert_eq!(encodings, vec!["gzip"]);
+
+        // Content-Length is the compressed size
+        let content_length = result_headers.iter().find(|h| h.0 == "Content-Length").unwrap();
+        assert_eq!(content_length.1, compressed.len().to_string());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,4 @@
+-- Add HTTP version preference. Folders and requests inherit from their parent when NULL
+ALTER TABLE workspaces ADD COLUMN setting_http_version TEXT DEFAULT 'auto' NOT NULL;
+ALTER TABLE folders ADD COLUMN setting_http_version TEXT;
+ALTER TABLE http_requests ADD COLUMN setting_http_version TEXT;
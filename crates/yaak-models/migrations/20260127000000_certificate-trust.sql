@@ -0,0 +1,3 @@
+-- Add extra CA certificates and certificate pins to workspaces
+ALTER TABLE workspaces ADD COLUMN setting_ca_certificates TEXT DEFAULT '[]' NOT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_certificate_pins TEXT DEFAULT '[]' NOT NULL;
@@ -0,0 +1,6 @@
+-- Add TLS handshake constraints to workspaces
+ALTER TABLE workspaces ADD COLUMN setting_tls_min_version TEXT;
+ALTER TABLE workspaces ADD COLUMN setting_tls_max_version TEXT;
+ALTER TABLE workspaces ADD COLUMN setting_tls_cipher_suites TEXT DEFAULT '[]' NOT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_tls_kx_groups TEXT DEFAULT '[]' NOT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_tls_send_sni BOOLEAN DEFAULT TRUE NOT NULL;
@@ -0,0 +1,3 @@
+-- Client certificates defined on workspaces and folders, on top of the global settings
+ALTER TABLE workspaces ADD COLUMN setting_client_certificates TEXT DEFAULT '[]' NOT NULL;
+ALTER TABLE folders ADD COLUMN setting_client_certificates TEXT DEFAULT '[]' NOT NULL;
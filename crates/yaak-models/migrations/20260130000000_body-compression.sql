@@ -0,0 +1,2 @@
+-- Optional Content-Encoding applied to outgoing request bodies
+ALTER TABLE http_requests ADD COLUMN body_compression TEXT;
@@ -0,0 +1,4 @@
+-- Redirect policies, inherited from the parent folder or workspace when null
+ALTER TABLE workspaces ADD COLUMN setting_redirect_policy TEXT DEFAULT '{}' NOT NULL;
+ALTER TABLE folders ADD COLUMN setting_redirect_policy TEXT;
+ALTER TABLE http_requests ADD COLUMN setting_redirect_policy TEXT;
@@ -0,0 +1,33 @@
+-- Cookies store their name, value and attributes instead of the raw Set-Cookie string, which
+-- was either "name=value" or the full header with its attributes
+UPDATE cookie_jars
+SET cookies = (
+    SELECT json_group_array(
+        json_remove(
+            json_set(
+                value,
+                '$.name', trim(substr(pair, 1, instr(pair || '=', '=') - 1)),
+                '$.value', trim(substr(pair, instr(pair || '=', '=') + 1)),
+                '$.secure', json(CASE WHEN attrs LIKE '%;secure%' THEN 'true' ELSE 'false' END),
+                '$.http_only', json(CASE WHEN attrs LIKE '%;httponly%' THEN 'true' ELSE 'false' END),
+                '$.same_site', CASE
+                    WHEN attrs LIKE '%;samesite=strict%' THEN 'Strict'
+                    WHEN attrs LIKE '%;samesite=lax%' THEN 'Lax'
+                    WHEN attrs LIKE '%;samesite=none%' THEN 'None'
+                    ELSE 'Unspecified'
+                END
+            ),
+            '$.raw_cookie'
+        )
+    )
+    FROM (
+        SELECT value,
+               substr(raw, 1, instr(raw || ';', ';') - 1) AS pair,
+               replace(lower(substr(raw, instr(raw || ';', ';'))), ' ', '') AS attrs
+        FROM (
+            SELECT value, json_extract(value, '$.raw_cookie') AS raw
+            FROM json_each(cookie_jars.cookies)
+        )
+    )
+)
+WHERE json_valid(cookies);
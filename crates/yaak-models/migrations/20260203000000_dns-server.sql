@@ -0,0 +1,2 @@
+-- DNS server to resolve hostnames with instead of the system resolver
+ALTER TABLE workspaces ADD COLUMN setting_dns_server TEXT DEFAULT '' NOT NULL;
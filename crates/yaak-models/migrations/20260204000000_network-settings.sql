@@ -0,0 +1,4 @@
+-- Network settings (IP family, local address and interface), inherited when null
+ALTER TABLE workspaces ADD COLUMN setting_network TEXT DEFAULT '{}' NOT NULL;
+ALTER TABLE folders ADD COLUMN setting_network TEXT;
+ALTER TABLE http_requests ADD COLUMN setting_network TEXT;
@@ -0,0 +1,3 @@
+-- Connect and read timeouts, in milliseconds, alongside the overall request timeout
+ALTER TABLE workspaces ADD COLUMN setting_connect_timeout INTEGER DEFAULT '0' NOT NULL;
+ALTER TABLE workspaces ADD COLUMN setting_read_timeout INTEGER DEFAULT '0' NOT NULL;
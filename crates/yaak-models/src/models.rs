@@ -1,7 +1,8 @@
 use crate::error::Result;
 use crate::models::HttpRequestIden::{
     Authentication, AuthenticationType, Body, BodyType, CreatedAt, Description, FolderId, Headers,
-    Method, Name, SortPriority, UpdatedAt, Url, UrlParameters, WorkspaceId,
+    Method, Name, SettingHttpVersion, SettingNetwork, SettingRedirectPolicy, SettingRetryPolicy,
+    SortPriority, UpdatedAt, Url, UrlParameters, WorkspaceId,
 };
 use crate::util::{UpdateSource, generate_prefixed_id};
 use chrono::{NaiveDateTime, Utc};
@@ -13,6 +14,7 @@ use serde_json::Value;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display};
+use std::net::IpAddr;
 use std::str::FromStr;
 use ts_rs::TS;
 
@@ -43,6 +45,25 @@ pub enum ProxySetting {
         disabled: bool,
     },
     Disabled,
+    Socks {
+        /// Host and port of the SOCKS5 proxy
+        host: String,
+        /// Resolve hostnames on the proxy (socks5h) instead of locally
+        #[serde(default = "default_true", rename = "remoteDns")]
+        remote_dns: bool,
+        auth: Option<ProxySettingAuth>,
+        #[serde(default)]
+        bypass: String,
+        #[serde(default)]
+        disabled: bool,
+    },
+    /// Proxy auto-config (PAC) script, evaluated for every request
+    Pac {
+        /// URL or file path of the script
+        url: String,
+        #[serde(default)]
+        disabled: bool,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -53,7 +74,7 @@ pub struct ProxySettingAuth {
     pub password: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "gen_models.ts")]
 pub struct ClientCertificate {
@@ -73,20 +94,247 @@ pub struct ClientCertificate {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "gen_models.ts")]
 pub struct DnsOverride {
+    /// A hostname, or `*.example.com` for all subdomains of example.com
     pub hostname: String,
+    /// Only override connections to this port
+    #[serde(default)]
+    #[ts(optional)]
+    pub port: Option<u16>,
     #[serde(default)]
     pub ipv4: Vec<String>,
     #[serde(default)]
     pub ipv6: Vec<String>,
+    /// Connect to this port instead, like curl's `--connect-to`
+    #[serde(default)]
+    #[ts(optional)]
+    pub target_port: Option<u16>,
     #[serde(default = "default_true")]
     #[ts(optional, as = "Option<bool>")]
     pub enabled: bool,
 }
 
+/// When and how failed HTTP requests are sent again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct RetryPolicy {
+    // Including the first attempt, so 1 disables retries
+    pub max_attempts: u32,
+    pub statuses: Vec<u16>,
+    pub errors: Vec<RetryErrorKind>,
+    // Exponential backoff in milliseconds, doubling from the initial delay
+    pub initial_delay: u32,
+    pub max_delay: u32,
+    pub jitter: bool,
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            statuses: vec![408, 429, 502, 503, 504],
+            errors: vec![RetryErrorKind::Connect, RetryErrorKind::Timeout],
+            initial_delay: 500,
+            max_delay: 30_000,
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+/// How redirects are followed, to match the behavior of browsers or other HTTP clients
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct RedirectPolicy {
+    pub max_redirects: u32,
+    // Keep Authorization and Cookie headers when redirected to another origin
+    pub keep_cross_origin_credentials: bool,
+    // Send a GET without the body when a POST is redirected with 301 or 302
+    pub convert_post_to_get: bool,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self { max_redirects: 10, keep_cross_origin_credentials: false, convert_post_to_get: true }
+    }
+}
+
+/// Where connections are made from, and over which IP versions
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct NetworkSettings {
+    pub ip_family: IpFamily,
+    // Local IP address to send from, empty for any
+    pub local_address: String,
+    // Network interface to send from, like `eth0` or `utun3`, empty for any
+    pub interface: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum IpFamily {
+    /// Try IPv6 and IPv4 addresses, racing them with happy eyeballs
+    #[default]
+    Auto,
+    /// Only connect over IPv4
+    Ipv4,
+    /// Only connect over IPv6
+    Ipv6,
+}
+
+impl IpFamily {
+    /// Whether connections to an address are allowed
+    pub fn allows(&self, ip: &IpAddr) -> bool {
+        match self {
+            IpFamily::Auto => true,
+            IpFamily::Ipv4 => ip.is_ipv4(),
+            IpFamily::Ipv6 => ip.is_ipv6(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum RetryErrorKind {
+    /// The connection couldn't be established
+    Connect,
+    /// The request timed out
+    Timeout,
+    /// The connection failed after it was established
+    Network,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct CertificatePin {
+    pub host: String,
+    // Either `sha256/<base64>` of the public key (SPKI), or the hex SHA-256 fingerprint of the
+    // server's certificate
+    pub pin: String,
+    #[serde(default = "default_true")]
+    #[ts(optional, as = "Option<bool>")]
+    pub enabled: bool,
+}
+
+/// Which HTTP version to use when sending requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum HttpVersion {
+    // Negotiate HTTP/2 or HTTP/1.1 with ALPN, and use HTTP/1.1 without TLS
+    #[default]
+    Auto,
+    // Only use HTTP/1.1
+    Http1,
+    // Only offer HTTP/2 with ALPN
+    Http2,
+    // Use HTTP/2 without negotiating it first, including h2c without TLS
+    Http2PriorKnowledge,
+}
+
+impl FromStr for HttpVersion {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "http1" => Ok(Self::Http1),
+            "http2" => Ok(Self::Http2),
+            "http2_prior_knowledge" => Ok(Self::Http2PriorKnowledge),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+impl Display for HttpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            HttpVersion::Auto => "auto",
+            HttpVersion::Http1 => "http1",
+            HttpVersion::Http2 => "http2",
+            HttpVersion::Http2PriorKnowledge => "http2_prior_knowledge",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// TLS protocol version, used to constrain the versions offered in the handshake
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum TlsVersion {
+    Tls12,
+    Tls13,
+}
+
+impl FromStr for TlsVersion {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "tls12" => Ok(Self::Tls12),
+            "tls13" => Ok(Self::Tls13),
+            _ => Err(crate::error::Error::GenericError(format!("Invalid TLS version {s}"))),
+        }
+    }
+}
+
+impl Display for TlsVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            TlsVersion::Tls12 => "tls12",
+            TlsVersion::Tls13 => "tls13",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// Compression applied to request bodies, named after the Content-Encoding it sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum BodyCompression {
+    Gzip,
+    Deflate,
+    Br,
+    Zstd,
+}
+
+impl FromStr for BodyCompression {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "gzip" => Ok(Self::Gzip),
+            "deflate" => Ok(Self::Deflate),
+            "br" => Ok(Self::Br),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(crate::error::Error::GenericError(format!("Invalid body compression {s}"))),
+        }
+    }
+}
+
+impl Display for BodyCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            BodyCompression::Gzip => "gzip",
+            BodyCompression::Deflate => "deflate",
+            BodyCompression::Br => "br",
+            BodyCompression::Zstd => "zstd",
+        };
+        write!(f, "{}", str)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(export, export_to = "gen_models.ts")]
@@ -297,8 +545,42 @@ pub struct Workspace {
     #[serde(default = "default_true")]
     pub setting_follow_redirects: bool,
     pub setting_request_timeout: i32,
+    // Milliseconds to wait for a connection, and for the next chunk of the response body, 0
+    // to wait forever. The request timeout above is the deadline for the whole request.
+    #[serde(default)]
+    pub setting_connect_timeout: i32,
+    #[serde(default)]
+    pub setting_read_timeout: i32,
     #[serde(default)]
     pub setting_dns_overrides: Vec<DnsOverride>,
+    // DNS server to use instead of the system resolver, empty for the system one
+    #[serde(default)]
+    pub setting_dns_server: String,
+    #[serde(default)]
+    pub setting_http_version: HttpVersion,
+    #[serde(default)]
+    pub setting_ca_certificates: Vec<String>,
+    #[serde(default)]
+    pub setting_certificate_pins: Vec<CertificatePin>,
+    // TLS handshake constraints, using the defaults when empty
+    #[serde(default)]
+    pub setting_tls_min_version: Option<TlsVersion>,
+    #[serde(default)]
+    pub setting_tls_max_version: Option<TlsVersion>,
+    #[serde(default)]
+    pub setting_tls_cipher_suites: Vec<String>,
+    #[serde(default)]
+    pub setting_tls_kx_groups: Vec<String>,
+    #[serde(default = "default_true")]
+    pub setting_tls_send_sni: bool,
+    #[serde(default)]
+    pub setting_client_certificates: Vec<ClientCertificate>,
+    #[serde(default)]
+    pub setting_retry_policy: RetryPolicy,
+    #[serde(default)]
+    pub setting_redirect_policy: RedirectPolicy,
+    #[serde(default)]
+    pub setting_network: NetworkSettings,
 }
 
 impl UpsertModelInfo for Workspace {
@@ -338,8 +620,29 @@ impl UpsertModelInfo for Workspace {
             (EncryptionKeyChallenge, self.encryption_key_challenge.into()),
             (SettingFollowRedirects, self.setting_follow_redirects.into()),
             (SettingRequestTimeout, self.setting_request_timeout.into()),
+            (SettingConnectTimeout, self.setting_connect_timeout.into()),
+            (SettingReadTimeout, self.setting_read_timeout.into()),
             (SettingValidateCertificates, self.setting_validate_certificates.into()),
             (SettingDnsOverrides, serde_json::to_string(&self.setting_dns_overrides)?.into()),
+            (SettingDnsServer, self.setting_dns_server.trim().into()),
+            (SettingHttpVersion, self.setting_http_version.to_string().into()),
+            (SettingCaCertificates, serde_json::to_string(&self.setting_ca_certificates)?.into()),
+            (SettingCertificatePins, serde_json::to_string(&self.setting_certificate_pins)?.into()),
+            (SettingTlsMinVersion, self.setting_tls_min_version.map(|v| v.to_string()).into()),
+            (SettingTlsMaxVersion, self.setting_tls_max_version.map(|v| v.to_string()).into()),
+            (
+                SettingTlsCipherSuites,
+                serde_json::to_string(&self.setting_tls_cipher_suites)?.into(),
+            ),
+            (SettingTlsKxGroups, serde_json::to_string(&self.setting_tls_kx_groups)?.into()),
+            (SettingTlsSendSni, self.setting_tls_send_sni.into()),
+            (
+                SettingClientCertificates,
+                serde_json::to_string(&self.setting_client_certificates)?.into(),
+            ),
+            (SettingRetryPolicy, serde_json::to_string(&self.setting_retry_policy)?.into()),
+            (SettingRedirectPolicy, serde_json::to_string(&self.setting_redirect_policy)?.into()),
+            (SettingNetwork, serde_json::to_string(&self.setting_network)?.into()),
         ])
     }
 
@@ -355,8 +658,23 @@ impl UpsertModelInfo for Workspace {
             WorkspaceIden::SettingRequestTimeout,
             WorkspaceIden::SettingFollowRedirects,
             WorkspaceIden::SettingRequestTimeout,
+            WorkspaceIden::SettingConnectTimeout,
+            WorkspaceIden::SettingReadTimeout,
             WorkspaceIden::SettingValidateCertificates,
             WorkspaceIden::SettingDnsOverrides,
+            WorkspaceIden::SettingDnsServer,
+            WorkspaceIden::SettingHttpVersion,
+            WorkspaceIden::SettingCaCertificates,
+            WorkspaceIden::SettingCertificatePins,
+            WorkspaceIden::SettingTlsMinVersion,
+            WorkspaceIden::SettingTlsMaxVersion,
+            WorkspaceIden::SettingTlsCipherSuites,
+            WorkspaceIden::SettingTlsKxGroups,
+            WorkspaceIden::SettingTlsSendSni,
+            WorkspaceIden::SettingClientCertificates,
+            WorkspaceIden::SettingRetryPolicy,
+            WorkspaceIden::SettingRedirectPolicy,
+            WorkspaceIden::SettingNetwork,
         ]
     }
 
@@ -367,6 +685,17 @@ impl UpsertModelInfo for Workspace {
         let headers: String = row.get("headers")?;
         let authentication: String = row.get("authentication")?;
         let setting_dns_overrides: String = row.get("setting_dns_overrides")?;
+        let setting_http_version: String = row.get("setting_http_version")?;
+        let setting_ca_certificates: String = row.get("setting_ca_certificates")?;
+        let setting_certificate_pins: String = row.get("setting_certificate_pins")?;
+        let setting_tls_min_version: Option<String> = row.get("setting_tls_min_version")?;
+        let setting_tls_max_version: Option<String> = row.get("setting_tls_max_version")?;
+        let setting_tls_cipher_suites: String = row.get("setting_tls_cipher_suites")?;
+        let setting_tls_kx_groups: String = row.get("setting_tls_kx_groups")?;
+        let setting_client_certificates: String = row.get("setting_client_certificates")?;
+        let setting_retry_policy: String = row.get("setting_retry_policy")?;
+        let setting_redirect_policy: String = row.get("setting_redirect_policy")?;
+        let setting_network: String = row.get("setting_network")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -380,8 +709,30 @@ impl UpsertModelInfo for Workspace {
             authentication_type: row.get("authentication_type")?,
             setting_follow_redirects: row.get("setting_follow_redirects")?,
             setting_request_timeout: row.get("setting_request_timeout")?,
+            setting_connect_timeout: row.get("setting_connect_timeout")?,
+            setting_read_timeout: row.get("setting_read_timeout")?,
             setting_validate_certificates: row.get("setting_validate_certificates")?,
             setting_dns_overrides: serde_json::from_str(&setting_dns_overrides).unwrap_or_default(),
+            setting_dns_server: row.get("setting_dns_server")?,
+            setting_http_version: HttpVersion::from_str(&setting_http_version).unwrap(),
+            setting_ca_certificates: serde_json::from_str(&setting_ca_certificates)
+                .unwrap_or_default(),
+            setting_certificate_pins: serde_json::from_str(&setting_certificate_pins)
+                .unwrap_or_default(),
+            setting_tls_min_version: setting_tls_min_version
+                .and_then(|v| TlsVersion::from_str(&v).ok()),
+            setting_tls_max_version: setting_tls_max_version
+                .and_then(|v| TlsVersion::from_str(&v).ok()),
+            setting_tls_cipher_suites: serde_json::from_str(&setting_tls_cipher_suites)
+                .unwrap_or_default(),
+            setting_tls_kx_groups: serde_json::from_str(&setting_tls_kx_groups).unwrap_or_default(),
+            setting_tls_send_sni: row.get("setting_tls_send_sni")?,
+            setting_client_certificates: serde_json::from_str(&setting_client_certificates)
+                .unwrap_or_default(),
+            setting_retry_policy: serde_json::from_str(&setting_retry_policy).unwrap_or_default(),
+            setting_redirect_policy: serde_json::from_str(&setting_redirect_policy)
+                .unwrap_or_default(),
+            setting_network: serde_json::from_str(&setting_network).unwrap_or_default(),
         })
     }
 }
@@ -484,13 +835,33 @@ pub enum CookieExpires {
     SessionEnd,
 }
 
+/// The SameSite attribute of a cookie. Cookies without one are treated like Lax
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, TS)]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum CookieSameSite {
+    Strict,
+    Lax,
+    None,
+    #[default]
+    Unspecified,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export, export_to = "gen_models.ts")]
 pub struct Cookie {
-    pub raw_cookie: String,
+    pub name: String,
+    pub value: String,
     pub domain: CookieDomain,
     pub expires: CookieExpires,
     pub path: (String, bool),
+    /// Only sent over secure connections
+    #[serde(default)]
+    pub secure: bool,
+    /// Hidden from scripts in browsers. Yaak only sends cookies over HTTP, so it's informational
+    #[serde(default)]
+    pub http_only: bool,
+    #[serde(default)]
+    pub same_site: CookieSameSite,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
@@ -726,6 +1097,15 @@ pub struct Folder {
     pub headers: Vec<HttpRequestHeader>,
     pub name: String,
     pub sort_priority: f64,
+
+    // Settings (inherited when None)
+    pub setting_http_version: Option<HttpVersion>,
+    // Matched before the ones of parent folders, the workspace and the app settings
+    #[serde(default)]
+    pub setting_client_certificates: Vec<ClientCertificate>,
+    pub setting_retry_policy: Option<RetryPolicy>,
+    pub setting_redirect_policy: Option<RedirectPolicy>,
+    pub setting_network: Option<NetworkSettings>,
 }
 
 impl UpsertModelInfo for Folder {
@@ -765,6 +1145,23 @@ impl UpsertModelInfo for Folder {
             (Description, self.description.into()),
             (Name, self.name.trim().into()),
             (SortPriority, self.sort_priority.into()),
+            (SettingHttpVersion, self.setting_http_version.map(|v| v.to_string()).into()),
+            (
+                SettingClientCertificates,
+                serde_json::to_string(&self.setting_client_certificates)?.into(),
+            ),
+            (
+                SettingRetryPolicy,
+                self.setting_retry_policy.map(|p| serde_json::to_string(&p)).transpose()?.into(),
+            ),
+            (
+                SettingRedirectPolicy,
+                self.setting_redirect_policy.map(|p| serde_json::to_string(&p)).transpose()?.into(),
+            ),
+            (
+                SettingNetwork,
+                self.setting_network.map(|n| serde_json::to_string(&n)).transpose()?.into(),
+            ),
         ])
     }
 
@@ -778,6 +1175,11 @@ impl UpsertModelInfo for Folder {
             FolderIden::Description,
             FolderIden::FolderId,
             FolderIden::SortPriority,
+            FolderIden::SettingHttpVersion,
+            FolderIden::SettingClientCertificates,
+            FolderIden::SettingRetryPolicy,
+            FolderIden::SettingRedirectPolicy,
+            FolderIden::SettingNetwork,
         ]
     }
 
@@ -787,6 +1189,11 @@ impl UpsertModelInfo for Folder {
     {
         let headers: String = row.get("headers")?;
         let authentication: String = row.get("authentication")?;
+        let setting_http_version: Option<String> = row.get("setting_http_version")?;
+        let setting_client_certificates: String = row.get("setting_client_certificates")?;
+        let setting_retry_policy: Option<String> = row.get("setting_retry_policy")?;
+        let setting_redirect_policy: Option<String> = row.get("setting_redirect_policy")?;
+        let setting_network: Option<String> = row.get("setting_network")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -800,6 +1207,14 @@ impl UpsertModelInfo for Folder {
             headers: serde_json::from_str(&headers).unwrap_or_default(),
             authentication_type: row.get("authentication_type")?,
             authentication: serde_json::from_str(&authentication).unwrap_or_default(),
+            setting_http_version: setting_http_version
+                .map(|v| HttpVersion::from_str(&v).unwrap_or_default()),
+            setting_client_certificates: serde_json::from_str(&setting_client_certificates)
+                .unwrap_or_default(),
+            setting_retry_policy: setting_retry_policy.and_then(|p| serde_json::from_str(&p).ok()),
+            setting_redirect_policy: setting_redirect_policy
+                .and_then(|p| serde_json::from_str(&p).ok()),
+            setting_network: setting_network.and_then(|n| serde_json::from_str(&n).ok()),
         })
     }
 }
@@ -849,6 +1264,8 @@ pub struct HttpRequest {
     #[ts(type = "Record<string, any>")]
     pub body: BTreeMap<String, Value>,
     pub body_type: Option<String>,
+    // Compresses the body and sets Content-Encoding when set
+    pub body_compression: Option<BodyCompression>,
     pub description: String,
     pub headers: Vec<HttpRequestHeader>,
     #[serde(default = "default_http_method")]
@@ -857,6 +1274,12 @@ pub struct HttpRequest {
     pub sort_priority: f64,
     pub url: String,
     pub url_parameters: Vec<HttpUrlParameter>,
+
+    // Settings (inherited when None)
+    pub setting_http_version: Option<HttpVersion>,
+    pub setting_retry_policy: Option<RetryPolicy>,
+    pub setting_redirect_policy: Option<RedirectPolicy>,
+    pub setting_network: Option<NetworkSettings>,
 }
 
 impl UpsertModelInfo for HttpRequest {
@@ -896,10 +1319,27 @@ impl UpsertModelInfo for HttpRequest {
             (Method, self.method.into()),
             (Body, serde_json::to_string(&self.body)?.into()),
             (BodyType, self.body_type.into()),
+            (
+                HttpRequestIden::BodyCompression,
+                self.body_compression.map(|c| c.to_string()).into(),
+            ),
             (Authentication, serde_json::to_string(&self.authentication)?.into()),
             (AuthenticationType, self.authentication_type.into()),
             (Headers, serde_json::to_string(&self.headers)?.into()),
             (SortPriority, self.sort_priority.into()),
+            (SettingHttpVersion, self.setting_http_version.map(|v| v.to_string()).into()),
+            (
+                SettingRetryPolicy,
+                self.setting_retry_policy.map(|p| serde_json::to_string(&p)).transpose()?.into(),
+            ),
+            (
+                SettingRedirectPolicy,
+                self.setting_redirect_policy.map(|p| serde_json::to_string(&p)).transpose()?.into(),
+            ),
+            (
+                SettingNetwork,
+                self.setting_network.map(|n| serde_json::to_string(&n)).transpose()?.into(),
+            ),
         ])
     }
 
@@ -914,11 +1354,16 @@ impl UpsertModelInfo for HttpRequest {
             Headers,
             Body,
             BodyType,
+            HttpRequestIden::BodyCompression,
             Authentication,
             AuthenticationType,
             Url,
             UrlParameters,
             SortPriority,
+            SettingHttpVersion,
+            SettingRetryPolicy,
+            SettingRedirectPolicy,
+            SettingNetwork,
         ]
     }
 
@@ -927,6 +1372,11 @@ impl UpsertModelInfo for HttpRequest {
         let body: String = row.get("body")?;
         let authentication: String = row.get("authentication")?;
         let headers: String = row.get("headers")?;
+        let setting_http_version: Option<String> = row.get("setting_http_version")?;
+        let body_compression: Option<String> = row.get("body_compression")?;
+        let setting_retry_policy: Option<String> = row.get("setting_retry_policy")?;
+        let setting_redirect_policy: Option<String> = row.get("setting_redirect_policy")?;
+        let setting_network: Option<String> = row.get("setting_network")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -937,6 +1387,7 @@ impl UpsertModelInfo for HttpRequest {
             authentication_type: row.get("authentication_type")?,
             body: serde_json::from_str(body.as_str()).unwrap_or_default(),
             body_type: row.get("body_type")?,
+            body_compression: body_compression.and_then(|c| BodyCompression::from_str(&c).ok()),
             description: row.get("description")?,
             folder_id: row.get("folder_id")?,
             headers: serde_json::from_str(headers.as_str()).unwrap_or_default(),
@@ -945,6 +1396,12 @@ impl UpsertModelInfo for HttpRequest {
             sort_priority: row.get("sort_priority")?,
             url: row.get("url")?,
             url_parameters: serde_json::from_str(url_parameters.as_str()).unwrap_or_default(),
+            setting_http_version: setting_http_version
+                .map(|v| HttpVersion::from_str(&v).unwrap_or_default()),
+            setting_retry_policy: setting_retry_policy.and_then(|p| serde_json::from_str(&p).ok()),
+            setting_redirect_policy: setting_redirect_policy
+                .and_then(|p| serde_json::from_str(&p).ok()),
+            setting_network: setting_network.and_then(|n| serde_json::from_str(&n).ok()),
         })
     }
 }
@@ -1334,6 +1791,9 @@ pub struct HttpResponse {
     pub elapsed: i32,
     pub elapsed_headers: i32,
     pub elapsed_dns: i32,
+    pub elapsed_connect: i32,
+    pub elapsed_tls: i32,
+    pub elapsed_ttfb: i32,
     pub error: Option<String>,
     pub headers: Vec<HttpResponseHeader>,
     pub remote_addr: Option<String>,
@@ -1383,6 +1843,9 @@ impl UpsertModelInfo for HttpResponse {
             (Elapsed, self.elapsed.into()),
             (ElapsedHeaders, self.elapsed_headers.into()),
             (ElapsedDns, self.elapsed_dns.into()),
+            (ElapsedConnect, self.elapsed_connect.into()),
+            (ElapsedTls, self.elapsed_tls.into()),
+            (ElapsedTtfb, self.elapsed_ttfb.into()),
             (Error, self.error.into()),
             (Headers, serde_json::to_string(&self.headers)?.into()),
             (RemoteAddr, self.remote_addr.into()),
@@ -1405,6 +1868,9 @@ impl UpsertModelInfo for HttpResponse {
             HttpResponseIden::Elapsed,
             HttpResponseIden::ElapsedHeaders,
             HttpResponseIden::ElapsedDns,
+            HttpResponseIden::ElapsedConnect,
+            HttpResponseIden::ElapsedTls,
+            HttpResponseIden::ElapsedTtfb,
             HttpResponseIden::Error,
             HttpResponseIden::Headers,
             HttpResponseIden::RemoteAddr,
@@ -1439,6 +1905,9 @@ impl UpsertModelInfo for HttpResponse {
             elapsed: r.get("elapsed")?,
             elapsed_headers: r.get("elapsed_headers")?,
             elapsed_dns: r.get("elapsed_dns").unwrap_or_default(),
+            elapsed_connect: r.get("elapsed_connect").unwrap_or_default(),
+            elapsed_tls: r.get("elapsed_tls").unwrap_or_default(),
+            elapsed_ttfb: r.get("elapsed_ttfb").unwrap_or_default(),
             remote_addr: r.get("remote_addr")?,
             status: r.get("status")?,
             status_reason: r.get("status_reason")?,
@@ -1514,7 +1983,46 @@ pub enum HttpResponseEventData {
         addresses: Vec<String>,
         duration: u64,
         overridden: bool,
+        #[serde(default)]
+        source: String,
+        #[serde(default)]
+        ttl: Option<u64>,
+    },
+    Connected {
+        duration: u64,
+    },
+    TlsHandshake {
+        duration: u64,
     },
+    FirstByte {
+        duration: u64,
+    },
+    TlsSession {
+        version: String,
+        cipher_suite: String,
+        alpn: String,
+        server_name: String,
+        certificates: Vec<TlsCertificate>,
+    },
+    Retry {
+        attempt: u32,
+        max_attempts: u32,
+        delay: u64,
+        reason: String,
+    },
+}
+
+/// A certificate from the chain presented by the server, leaf first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct TlsCertificate {
+    pub subject: String,
+    pub issuer: String,
+    pub sans: Vec<String>,
+    pub not_before: NaiveDateTime,
+    pub not_after: NaiveDateTime,
+    pub sha256_fingerprint: String,
 }
 
 impl Default for HttpResponseEventData {
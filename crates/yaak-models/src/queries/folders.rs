@@ -2,8 +2,9 @@ use crate::connection_or_tx::ConnectionOrTx;
 use crate::db_context::DbContext;
 use crate::error::Result;
 use crate::models::{
-    Environment, EnvironmentIden, Folder, FolderIden, GrpcRequest, GrpcRequestIden, HttpRequest,
-    HttpRequestHeader, HttpRequestIden, WebsocketRequest, WebsocketRequestIden,
+    ClientCertificate, Environment, EnvironmentIden, Folder, FolderIden, GrpcRequest,
+    GrpcRequestIden, HttpRequest, HttpRequestHeader, HttpRequestIden, HttpVersion, NetworkSettings,
+    RedirectPolicy, RetryPolicy, WebsocketRequest, WebsocketRequestIden,
 };
 use crate::util::UpdateSource;
 use serde_json::Value;
@@ -123,6 +124,67 @@ impl<'a> DbContext<'a> {
         Ok(self.resolve_auth_for_workspace(&workspace))
     }
 
+    pub fn resolve_http_version_for_folder(&self, folder: &Folder) -> Result<HttpVersion> {
+        if let Some(v) = folder.setting_http_version {
+            return Ok(v);
+        }
+
+        if let Some(folder_id) = folder.folder_id.clone() {
+            let folder = self.get_folder(&folder_id)?;
+            return self.resolve_http_version_for_folder(&folder);
+        }
+
+        let workspace = self.get_workspace(&folder.workspace_id)?;
+        Ok(workspace.setting_http_version)
+    }
+
+    pub fn resolve_retry_policy_for_folder(&self, folder: &Folder) -> Result<RetryPolicy> {
+        if let Some(p) = &folder.setting_retry_policy {
+            return Ok(p.clone());
+        }
+
+        if let Some(folder_id) = folder.folder_id.clone() {
+            let folder = self.get_folder(&folder_id)?;
+            return self.resolve_retry_policy_for_folder(&folder);
+        }
+
+        let workspace = self.get_workspace(&folder.workspace_id)?;
+        Ok(workspace.setting_retry_policy)
+    }
+
+    pub fn resolve_redirect_policy_for_folder(&self, folder: &Folder) -> Result<RedirectPolicy> {
+        if let Some(p) = &folder.setting_redirect_policy {
+            return Ok(p.clone());
+        }
+
+        if let Some(folder_id) = folder.folder_id.clone() {
+            let folder = self.get_folder(&folder_id)?;
+            return self.resolve_redirect_policy_for_folder(&folder);
+        }
+
+        let workspace = self.get_workspace(&folder.workspace_id)?;
+        Ok(workspace.setting_redirect_policy)
+    }
+
+    /// The network settings of the closest folder that has them, or else the workspace's
+    pub fn resolve_network_settings(
+        &self,
+        workspace_id: &str,
+        folder_id: Option<&str>,
+    ) -> Result<NetworkSettings> {
+        let mut next_folder_id = folder_id.map(|id| id.to_string());
+        while let Some(id) = next_folder_id {
+            let folder = self.get_folder(&id)?;
+            if let Some(n) = folder.setting_network {
+                return Ok(n);
+            }
+            next_folder_id = folder.folder_id;
+        }
+
+        let workspace = self.get_workspace(workspace_id)?;
+        Ok(workspace.setting_network)
+    }
+
     pub fn resolve_headers_for_folder(&self, folder: &Folder) -> Result<Vec<HttpRequestHeader>> {
         let mut headers = Vec::new();
 
@@ -141,4 +203,27 @@ impl<'a> DbContext<'a> {
 
         Ok(headers)
     }
+
+    /// Client certificates available to a request in `folder_id`, closest first: the folder
+    /// and its ancestors, then the workspace, then the app settings. The first match wins.
+    pub fn resolve_client_certificates(
+        &self,
+        workspace_id: &str,
+        folder_id: Option<&str>,
+    ) -> Result<Vec<ClientCertificate>> {
+        let mut certificates = Vec::new();
+
+        let mut next_folder_id = folder_id.map(|id| id.to_string());
+        while let Some(id) = next_folder_id {
+            let folder = self.get_folder(&id)?;
+            certificates.extend(folder.setting_client_certificates);
+            next_folder_id = folder.folder_id;
+        }
+
+        let workspace = self.get_workspace(workspace_id)?;
+        certificates.extend(workspace.setting_client_certificates);
+        certificates.extend(self.get_settings().client_certificates);
+
+        Ok(certificates)
+    }
 }
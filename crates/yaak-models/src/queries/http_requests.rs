@@ -1,7 +1,10 @@
 use super::dedupe_headers;
 use crate::db_context::DbContext;
 use crate::error::Result;
-use crate::models::{Folder, FolderIden, HttpRequest, HttpRequestHeader, HttpRequestIden};
+use crate::models::{
+    Folder, FolderIden, HttpRequest, HttpRequestHeader, HttpRequestIden, HttpVersion,
+    NetworkSettings, RedirectPolicy, RetryPolicy,
+};
 use crate::util::UpdateSource;
 use serde_json::Value;
 use std::collections::BTreeMap;
@@ -69,6 +72,68 @@ impl<'a> DbContext<'a> {
         Ok(self.resolve_auth_for_workspace(&workspace))
     }
 
+    pub fn resolve_http_version_for_http_request(
+        &self,
+        http_request: &HttpRequest,
+    ) -> Result<HttpVersion> {
+        if let Some(v) = http_request.setting_http_version {
+            return Ok(v);
+        }
+
+        if let Some(folder_id) = http_request.folder_id.clone() {
+            let folder = self.get_folder(&folder_id)?;
+            return self.resolve_http_version_for_folder(&folder);
+        }
+
+        let workspace = self.get_workspace(&http_request.workspace_id)?;
+        Ok(workspace.setting_http_version)
+    }
+
+    pub fn resolve_retry_policy_for_http_request(
+        &self,
+        http_request: &HttpRequest,
+    ) -> Result<RetryPolicy> {
+        if let Some(p) = &http_request.setting_retry_policy {
+            return Ok(p.clone());
+        }
+
+        if let Some(folder_id) = http_request.folder_id.clone() {
+            let folder = self.get_folder(&folder_id)?;
+            return self.resolve_retry_policy_for_folder(&folder);
+        }
+
+        let workspace = self.get_workspace(&http_request.workspace_id)?;
+        Ok(workspace.setting_retry_policy)
+    }
+
+    pub fn resolve_redirect_policy_for_http_request(
+        &self,
+        http_request: &HttpRequest,
+    ) -> Result<RedirectPolicy> {
+        if let Some(p) = &http_request.setting_redirect_policy {
+            return Ok(p.clone());
+        }
+
+        if let Some(folder_id) = http_request.folder_id.clone() {
+            let folder = self.get_folder(&folder_id)?;
+            return self.resolve_redirect_policy_for_folder(&folder);
+        }
+
+        let workspace = self.get_workspace(&http_request.workspace_id)?;
+        Ok(workspace.setting_redirect_policy)
+    }
+
+    pub fn resolve_network_settings_for_http_request(
+        &self,
+        http_request: &HttpRequest,
+    ) -> Result<NetworkSettings> {
+        if let Some(n) = &http_request.setting_network {
+            return Ok(n.clone());
+        }
+
+        self.resolve_network_settings(&http_request.workspace_id, http_request.folder_id.as_deref())
+    }
+
     pub fn resolve_headers_for_http_request(
         &self,
         http_request: &HttpRequest,
@@ -22,6 +22,7 @@ impl<'a> DbContext<'a> {
                     name: "Yapi".to_string(),
                     setting_follow_redirects: true,
                     setting_validate_certificates: true,
+                    setting_tls_send_sni: true,
                     ..Default::default()
                 },
                 &UpdateSource::Background,
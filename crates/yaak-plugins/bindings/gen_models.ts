@@ -2,9 +2,21 @@
 
 export type AnyModel = CookieJar | Environment | Folder | GraphQlIntrospection | GrpcConnection | GrpcEvent | GrpcRequest | HttpRequest | HttpResponse | HttpResponseEvent | KeyValue | Plugin | Settings | SyncState | WebsocketConnection | WebsocketEvent | WebsocketRequest | Workspace | WorkspaceMeta;
 
+export type BodyCompression = "gzip" | "deflate" | "br" | "zstd";
+
+export type CertificatePin = { host: string, pin: string, enabled?: boolean, };
+
 export type ClientCertificate = { host: string, port: number | null, crtFile: string | null, keyFile: string | null, pfxFile: string | null, passphrase: string | null, enabled?: boolean, };
 
-export type Cookie = { raw_cookie: string, domain: CookieDomain, expires: CookieExpires, path: [string, boolean], };
+export type Cookie = { name: string, value: string, domain: CookieDomain, expires: CookieExpires, path: [string, boolean], 
+/**
+ * Only sent over secure connections
+ */
+secure: boolean, 
+/**
+ * Hidden from scripts in browsers. Yaak only sends cookies over HTTP, so it's informational
+ */
+http_only: boolean, same_site: CookieSameSite, };
 
 export type CookieDomain = { "HostOnly": string } | { "Suffix": string } | "NotPresent" | "Empty";
 
@@ -12,7 +24,24 @@ export type CookieExpires = { "AtUtc": string } | "SessionEnd";
 
 export type CookieJar = { model: "cookie_jar", id: string, createdAt: string, updatedAt: string, workspaceId: string, cookies: Array<Cookie>, name: string, };
 
-export type DnsOverride = { hostname: string, ipv4: Array<string>, ipv6: Array<string>, enabled?: boolean, };
+/**
+ * The SameSite attribute of a cookie. Cookies without one are treated like Lax
+ */
+export type CookieSameSite = "Strict" | "Lax" | "None" | "Unspecified";
+
+export type DnsOverride = { 
+/**
+ * A hostname, or `*.example.com` for all subdomains of example.com
+ */
+hostname: string, 
+/**
+ * Only override connections to this port
+ */
+port?: number, ipv4: Array<string>, ipv6: Array<string>, 
+/**
+ * Connect to this port instead, like curl's `--connect-to`
+ */
+targetPort?: number, enabled?: boolean, };
 
 export type EditorKeymap = "default" | "vim" | "vscode" | "emacs";
 
@@ -22,7 +51,7 @@ export type Environment = { model: "environment", id: string, workspaceId: strin
 
 export type EnvironmentVariable = { enabled?: boolean, name: string, value: string, id?: string, };
 
-export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, sortPriority: number, };
+export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, sortPriority: number, settingHttpVersion: HttpVersion | null, settingClientCertificates: Array<ClientCertificate>, settingRetryPolicy: RetryPolicy | null, settingRedirectPolicy: RedirectPolicy | null, settingNetwork: NetworkSettings | null, };
 
 export type GraphQlIntrospection = { model: "graphql_introspection", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, content: string | null, };
 
@@ -36,11 +65,11 @@ export type GrpcEventType = "info" | "error" | "client_message" | "server_messag
 
 export type GrpcRequest = { model: "grpc_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authenticationType: string | null, authentication: Record<string, any>, description: string, message: string, metadata: Array<HttpRequestHeader>, method: string | null, name: string, service: string | null, sortPriority: number, url: string, };
 
-export type HttpRequest = { model: "http_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, body: Record<string, any>, bodyType: string | null, description: string, headers: Array<HttpRequestHeader>, method: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, };
+export type HttpRequest = { model: "http_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, body: Record<string, any>, bodyType: string | null, bodyCompression: BodyCompression | null, description: string, headers: Array<HttpRequestHeader>, method: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, settingHttpVersion: HttpVersion | null, settingRetryPolicy: RetryPolicy | null, settingRedirectPolicy: RedirectPolicy | null, settingNetwork: NetworkSettings | null, };
 
 export type HttpRequestHeader = { enabled?: boolean, name: string, value: string, id?: string, };
 
-export type HttpResponse = { model: "http_response", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, bodyPath: string | null, contentLength: number | null, contentLengthCompressed: number | null, elapsed: number, elapsedHeaders: number, elapsedDns: number, error: string | null, headers: Array<HttpResponseHeader>, remoteAddr: string | null, requestContentLength: number | null, requestHeaders: Array<HttpResponseHeader>, status: number, statusReason: string | null, state: HttpResponseState, url: string, version: string | null, };
+export type HttpResponse = { model: "http_response", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, bodyPath: string | null, contentLength: number | null, contentLengthCompressed: number | null, elapsed: number, elapsedHeaders: number, elapsedDns: number, elapsedConnect: number, elapsedTls: number, elapsedTtfb: number, error: string | null, headers: Array<HttpResponseHeader>, remoteAddr: string | null, requestContentLength: number | null, requestHeaders: Array<HttpResponseHeader>, status: number, statusReason: string | null, state: HttpResponseState, url: string, version: string | null, };
 
 export type HttpResponseEvent = { model: "http_response_event", id: string, createdAt: string, updatedAt: string, workspaceId: string, responseId: string, event: HttpResponseEventData, };
 
@@ -49,26 +78,66 @@ export type HttpResponseEvent = { model: "http_response_event", id: string, crea
  * This mirrors `yaak_http::sender::HttpResponseEvent` but with serde support.
  * The `From` impl is in yaak-http to avoid circular dependencies.
  */
-export type HttpResponseEventData = { "type": "setting", name: string, value: string, } | { "type": "info", message: string, } | { "type": "redirect", url: string, status: number, behavior: string, } | { "type": "send_url", method: string, path: string, } | { "type": "receive_url", version: string, status: string, } | { "type": "header_up", name: string, value: string, } | { "type": "header_down", name: string, value: string, } | { "type": "chunk_sent", bytes: number, } | { "type": "chunk_received", bytes: number, } | { "type": "dns_resolved", hostname: string, addresses: Array<string>, duration: bigint, overridden: boolean, };
+export type HttpResponseEventData = { "type": "setting", name: string, value: string, } | { "type": "info", message: string, } | { "type": "redirect", url: string, status: number, behavior: string, } | { "type": "send_url", method: string, path: string, } | { "type": "receive_url", version: string, status: string, } | { "type": "header_up", name: string, value: string, } | { "type": "header_down", name: string, value: string, } | { "type": "chunk_sent", bytes: number, } | { "type": "chunk_received", bytes: number, } | { "type": "dns_resolved", hostname: string, addresses: Array<string>, duration: bigint, overridden: boolean, source: string, ttl: bigint | null, } | { "type": "connected", duration: bigint, } | { "type": "tls_handshake", duration: bigint, } | { "type": "first_byte", duration: bigint, } | { "type": "tls_session", version: string, cipher_suite: string, alpn: string, server_name: string, certificates: Array<TlsCertificate>, } | { "type": "retry", attempt: number, max_attempts: number, delay: bigint, reason: string, };
 
 export type HttpResponseHeader = { name: string, value: string, };
 
 export type HttpResponseState = "initialized" | "connected" | "closed";
 
+/**
+ * Which HTTP version to use when sending requests
+ */
+export type HttpVersion = "auto" | "http1" | "http2" | "http2_prior_knowledge";
+
 export type HttpUrlParameter = { enabled?: boolean, name: string, value: string, id?: string, };
 
+export type IpFamily = "auto" | "ipv4" | "ipv6";
+
 export type KeyValue = { model: "key_value", id: string, createdAt: string, updatedAt: string, key: string, namespace: string, value: string, };
 
+/**
+ * Where connections are made from, and over which IP versions
+ */
+export type NetworkSettings = { ipFamily: IpFamily, localAddress: string, interface: string, };
+
 export type Plugin = { model: "plugin", id: string, createdAt: string, updatedAt: string, checkedAt: string | null, directory: string, enabled: boolean, url: string | null, };
 
-export type ProxySetting = { "type": "enabled", http: string, https: string, auth: ProxySettingAuth | null, bypass: string, disabled: boolean, } | { "type": "disabled" };
+export type ProxySetting = { "type": "enabled", http: string, https: string, auth: ProxySettingAuth | null, bypass: string, disabled: boolean, } | { "type": "disabled" } | { "type": "socks", 
+/**
+ * Host and port of the SOCKS5 proxy
+ */
+host: string, 
+/**
+ * Resolve hostnames on the proxy (socks5h) instead of locally
+ */
+remoteDns: boolean, auth: ProxySettingAuth | null, bypass: string, disabled: boolean, } | { "type": "pac", 
+/**
+ * URL or file path of the script
+ */
+url: string, disabled: boolean, };
 
 export type ProxySettingAuth = { user: string, password: string, };
 
+export type RedirectPolicy = { maxRedirects: number, keepCrossOriginCredentials: boolean, convertPostToGet: boolean, };
+
+export type RetryErrorKind = "connect" | "timeout" | "network";
+
+export type RetryPolicy = { maxAttempts: number, statuses: Array<number>, errors: Array<RetryErrorKind>, initialDelay: number, maxDelay: number, jitter: boolean, respectRetryAfter: boolean, };
+
 export type Settings = { model: "settings", id: string, createdAt: string, updatedAt: string, appearance: string, clientCertificates: Array<ClientCertificate>, coloredMethods: boolean, editorFont: string | null, editorFontSize: number, editorKeymap: EditorKeymap, editorSoftWrap: boolean, hideWindowControls: boolean, useNativeTitlebar: boolean, interfaceFont: string | null, interfaceFontSize: number, interfaceScale: number, openWorkspaceNewWindow: boolean | null, proxy: ProxySetting | null, themeDark: string, themeLight: string, updateChannel: string, hideLicenseBadge: boolean, autoupdate: boolean, autoDownloadUpdates: boolean, checkNotifications: boolean, hotkeys: { [key in string]?: Array<string> }, };
 
 export type SyncState = { model: "sync_state", id: string, workspaceId: string, createdAt: string, updatedAt: string, flushedAt: string, modelId: string, checksum: string, relPath: string, syncDir: string, };
 
+/**
+ * A certificate from the chain presented by the server, leaf first
+ */
+export type TlsCertificate = { subject: string, issuer: string, sans: Array<string>, notBefore: string, notAfter: string, sha256Fingerprint: string, };
+
+/**
+ * TLS protocol version, used to constrain the versions offered in the handshake
+ */
+export type TlsVersion = "tls12" | "tls13";
+
 export type WebsocketConnection = { model: "websocket_connection", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, elapsed: number, error: string | null, headers: Array<HttpResponseHeader>, state: WebsocketConnectionState, status: number, url: string, };
 
 export type WebsocketConnectionState = "initialized" | "connected" | "closing" | "closed";
@@ -77,8 +146,8 @@ export type WebsocketEvent = { model: "websocket_event", id: string, createdAt:
 
 export type WebsocketEventType = "binary" | "close" | "frame" | "open" | "ping" | "pong" | "text";
 
-export type WebsocketRequest = { model: "websocket_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, message: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, };
+export type WebsocketRequest = { model: "websocket_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, message: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, settingHttpVersion: HttpVersion | null, };
 
-export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, encryptionKeyChallenge: string | null, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingDnsOverrides: Array<DnsOverride>, };
+export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, encryptionKeyChallenge: string | null, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingConnectTimeout: number, settingReadTimeout: number, settingDnsOverrides: Array<DnsOverride>, settingDnsServer: string, settingHttpVersion: HttpVersion, settingCaCertificates: Array<string>, settingCertificatePins: Array<CertificatePin>, settingTlsMinVersion: TlsVersion | null, settingTlsMaxVersion: TlsVersion | null, settingTlsCipherSuites: Array<string>, settingTlsKxGroups: Array<string>, settingTlsSendSni: boolean, settingClientCertificates: Array<ClientCertificate>, settingRetryPolicy: RetryPolicy, settingRedirectPolicy: RedirectPolicy, settingNetwork: NetworkSettings, };
 
 export type WorkspaceMeta = { model: "workspace_meta", id: string, workspaceId: string, createdAt: string, updatedAt: string, encryptionKey: EncryptedKey | null, settingSyncDir: string | null, };
@@ -80,6 +80,10 @@ pub enum InternalEventPayload {
     ListCookieNamesResponse(ListCookieNamesResponse),
     GetCookieValueRequest(GetCookieValueRequest),
     GetCookieValueResponse(GetCookieValueResponse),
+    ImportCookiesRequest(ImportCookiesRequest),
+    ImportCookiesResponse(ImportCookiesResponse),
+    ExportCookiesRequest(ExportCookiesRequest),
+    ExportCookiesResponse(ExportCookiesResponse),
 
     // HTTP Request Actions
     GetHttpRequestActionsRequest(EmptyPayload),
@@ -316,6 +320,45 @@ pub struct GetCookieValueResponse {
     pub value: Option<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct ImportCookiesRequest {
+    /// A Netscape cookie file, like curl's cookies.txt, or a JSON dump of browser cookies
+    pub contents: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct ImportCookiesResponse {
+    /// Number of cookies added to the cookie jar
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct ExportCookiesRequest {
+    pub format: CookieFileFormat,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct ExportCookiesResponse {
+    pub contents: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_events.ts")]
+pub enum CookieFileFormat {
+    #[default]
+    Netscape,
+    Json,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "gen_events.ts")]
@@ -0,0 +1,115 @@
+use crate::error::Result;
+use crate::events::ImportResources;
+use log::info;
+use std::collections::BTreeMap;
+use yaak_core::WorkspaceContext;
+use yaak_models::models::{
+    Environment, Folder, GrpcRequest, HttpRequest, WebsocketRequest, Workspace,
+};
+use yaak_models::query_manager::QueryManager;
+use yaak_models::util::{BatchUpsertResult, UpdateSource, maybe_gen_id, maybe_gen_id_opt};
+
+/// Upsert the resources returned by an importer plugin in a single transaction.
+///
+/// Importers use placeholder IDs (`GENERATE_ID::<key>` and `CURRENT_WORKSPACE`), which are
+/// replaced with real IDs here, consistently across every resource that references them.
+pub fn upsert_import_resources(
+    query_manager: &QueryManager,
+    ctx: &WorkspaceContext,
+    resources: ImportResources,
+    source: &UpdateSource,
+) -> Result<BatchUpsertResult> {
+    let mut id_map: BTreeMap<String, String> = BTreeMap::new();
+
+    let workspaces: Vec<Workspace> = resources
+        .workspaces
+        .into_iter()
+        .map(|mut v| {
+            v.id = maybe_gen_id::<Workspace>(ctx, v.id.as_str(), &mut id_map);
+            v
+        })
+        .collect();
+
+    let environments: Vec<Environment> = resources
+        .environments
+        .into_iter()
+        .map(|mut v| {
+            v.id = maybe_gen_id::<Environment>(ctx, v.id.as_str(), &mut id_map);
+            v.workspace_id = maybe_gen_id::<Workspace>(ctx, v.workspace_id.as_str(), &mut id_map);
+            match (v.parent_model.as_str(), v.parent_id.clone().as_deref()) {
+                ("folder", Some(parent_id)) => {
+                    v.parent_id = Some(maybe_gen_id::<Folder>(ctx, &parent_id, &mut id_map));
+                }
+                ("", _) => {
+                    // Fix any empty ones
+                    v.parent_model = "workspace".to_string();
+                }
+                _ => {
+                    // Parent ID only required for the folder case
+                    v.parent_id = None;
+                }
+            };
+            v
+        })
+        .collect();
+
+    let folders: Vec<Folder> = resources
+        .folders
+        .into_iter()
+        .map(|mut v| {
+            v.id = maybe_gen_id::<Folder>(ctx, v.id.as_str(), &mut id_map);
+            v.workspace_id = maybe_gen_id::<Workspace>(ctx, v.workspace_id.as_str(), &mut id_map);
+            v.folder_id = maybe_gen_id_opt::<Folder>(ctx, v.folder_id, &mut id_map);
+            v
+        })
+        .collect();
+
+    let http_requests: Vec<HttpRequest> = resources
+        .http_requests
+        .into_iter()
+        .map(|mut v| {
+            v.id = maybe_gen_id::<HttpRequest>(ctx, v.id.as_str(), &mut id_map);
+            v.workspace_id = maybe_gen_id::<Workspace>(ctx, v.workspace_id.as_str(), &mut id_map);
+            v.folder_id = maybe_gen_id_opt::<Folder>(ctx, v.folder_id, &mut id_map);
+            v
+        })
+        .collect();
+
+    let grpc_requests: Vec<GrpcRequest> = resources
+        .grpc_requests
+        .into_iter()
+        .map(|mut v| {
+            v.id = maybe_gen_id::<GrpcRequest>(ctx, v.id.as_str(), &mut id_map);
+            v.workspace_id = maybe_gen_id::<Workspace>(ctx, v.workspace_id.as_str(), &mut id_map);
+            v.folder_id = maybe_gen_id_opt::<Folder>(ctx, v.folder_id, &mut id_map);
+            v
+        })
+        .collect();
+
+    let websocket_requests: Vec<WebsocketRequest> = resources
+        .websocket_requests
+        .into_iter()
+        .map(|mut v| {
+            v.id = maybe_gen_id::<WebsocketRequest>(ctx, v.id.as_str(), &mut id_map);
+            v.workspace_id = maybe_gen_id::<Workspace>(ctx, v.workspace_id.as_str(), &mut id_map);
+            v.folder_id = maybe_gen_id_opt::<Folder>(ctx, v.folder_id, &mut id_map);
+            v
+        })
+        .collect();
+
+    info!("Importing data");
+
+    let upserted = query_manager.with_tx(|tx| {
+        tx.batch_upsert(
+            workspaces,
+            environments,
+            folders,
+            http_requests,
+            grpc_requests,
+            websocket_requests,
+            source,
+        )
+    })?;
+
+    Ok(upserted)
+}
@@ -10,6 +10,7 @@ pub mod api;
 mod checksum;
 pub mod error;
 pub mod events;
+pub mod import;
 pub mod install;
 pub mod manager;
 pub mod native_template_functions;
@@ -0,0 +1,38 @@
+use thiserror::Error;
+use yaak_models::models::HttpResponse;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    TemplateError(#[from] yaak_templates::error::Error),
+
+    #[error(transparent)]
+    ModelError(#[from] yaak_models::error::Error),
+
+    #[error(transparent)]
+    HttpError(#[from] yaak_http::error::Error),
+
+    #[error(transparent)]
+    PluginError(#[from] yaak_plugins::error::Error),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("I/O error: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("{0}")]
+    GenericError(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A send that failed part-way. The error has already been recorded on `response`
+/// (and in the database, for persisted responses).
+#[derive(Error, Debug)]
+#[error("{error}")]
+pub struct SendError {
+    pub response: Box<HttpResponse>,
+    #[source]
+    pub error: Error,
+}
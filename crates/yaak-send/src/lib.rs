@@ -0,0 +1,9 @@
+//! Sending stored HTTP requests without Tauri.
+//!
+//! This crate renders a stored `HttpRequest`, applies inherited auth, headers, cookies and
+//! workspace connection settings, and records the same `HttpResponse` the app produces.
+//! It is shared by the desktop app and the CLI.
+
+pub mod error;
+pub mod render;
+pub mod send;
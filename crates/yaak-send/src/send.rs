@@ -0,0 +1,791 @@
+use crate::error::Error::GenericError;
+use crate::error::{Result, SendError};
+use crate::render::render_http_request;
+use log::{debug, warn};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::{Duration, Instant};
+use tokio::fs::{File, create_dir_all};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::sync::watch::Receiver;
+use tokio::task::JoinHandle;
+use yaak_core::AppContext;
+use yaak_crypto::manager::EncryptionManager;
+use yaak_http::client::{HttpConnectionOptions, HttpConnectionProxySetting};
+use yaak_http::cookies::CookieStore;
+use yaak_http::manager::{CachedClient, HttpConnectionManager};
+use yaak_http::sender::ReqwestSender;
+use yaak_http::tee_reader::TeeReader;
+use yaak_http::transaction::HttpTransaction;
+use yaak_http::types::{
+    SendableBody, SendableHttpRequest, SendableHttpRequestOptions, append_query_params,
+};
+use yaak_models::blob_manager::{BlobManager, BodyChunk};
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{
+    CookieJar, Environment, EnvironmentVariable, HttpRequest, HttpResponse, HttpResponseEvent,
+    HttpResponseHeader, HttpResponseState, RedirectPolicy, RetryPolicy,
+};
+use yaak_models::query_manager::QueryManager;
+use yaak_models::util::{UpdateSource, generate_id};
+use yaak_plugins::events::{
+    CallHttpAuthenticationRequest, HttpHeader, PluginContext, RenderPurpose,
+};
+use yaak_plugins::manager::PluginManager;
+use yaak_plugins::template_callback::PluginTemplateCallback;
+use yaak_templates::RenderOptions;
+use yaak_tls::render::render_client_certificate;
+use yaak_tls::{TlsSettings, find_client_certificate};
+
+/// Chunk size for storing request bodies (1MB)
+const REQUEST_BODY_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Everything a send needs from the host application
+pub struct HttpSendContext<'a, C: AppContext> {
+    pub app: &'a C,
+    pub query_manager: &'a QueryManager,
+    pub blob_manager: &'a BlobManager,
+    pub plugin_manager: Arc<PluginManager>,
+    pub encryption_manager: Arc<EncryptionManager>,
+    pub connection_manager: &'a HttpConnectionManager,
+    pub plugin_context: &'a PluginContext,
+    pub update_source: UpdateSource,
+    /// Optional observer that receives every transaction event as it happens
+    pub event_tx: Option<mpsc::Sender<yaak_http::sender::HttpResponseEvent>>,
+    /// Variables that take precedence over every environment in the chain
+    pub variable_overrides: Vec<EnvironmentVariable>,
+    /// Cookies to use instead of the cookie jar, like those of a cookie file. They're shared by
+    /// every send with this context and aren't saved to the database.
+    pub cookie_store: Option<CookieStore>,
+}
+
+/// Context for managing response state during HTTP transactions.
+/// Handles both persisted responses (stored in DB) and ephemeral responses (in-memory only).
+struct ResponseContext {
+    query_manager: QueryManager,
+    response: HttpResponse,
+    update_source: UpdateSource,
+}
+
+impl ResponseContext {
+    fn new(
+        query_manager: QueryManager,
+        response: HttpResponse,
+        update_source: UpdateSource,
+    ) -> Self {
+        Self { query_manager, response, update_source }
+    }
+
+    /// Whether this response is persisted (has a non-empty ID)
+    fn is_persisted(&self) -> bool {
+        !self.response.id.is_empty()
+    }
+
+    /// Update the response state. For persisted responses, fetches from DB, applies the
+    /// closure, and updates the DB. For ephemeral responses, just applies the closure
+    /// to the in-memory response.
+    fn update<F>(&mut self, func: F) -> Result<()>
+    where
+        F: FnOnce(&mut HttpResponse),
+    {
+        if self.is_persisted() {
+            let r = self.query_manager.with_tx(|tx| {
+                let mut r = tx.get_http_response(&self.response.id)?;
+                func(&mut r);
+                tx.update_http_response_if_id(&r, &self.update_source)
+            })?;
+            self.response = r;
+            Ok(())
+        } else {
+            func(&mut self.response);
+            Ok(())
+        }
+    }
+
+    /// Get the current response state
+    fn response(&self) -> &HttpResponse {
+        &self.response
+    }
+}
+
+/// Render and send a stored HTTP request, recording the result on `og_response`.
+///
+/// Pass a response with an empty ID for an ephemeral send. Otherwise the response, its
+/// events, and the request body are written to the database as the request progresses.
+/// On failure, the error is recorded on the response, which is returned in the error.
+pub async fn send_http_request<C: AppContext>(
+    ctx: &HttpSendContext<'_, C>,
+    unrendered_request: &HttpRequest,
+    og_response: &HttpResponse,
+    environment: Option<Environment>,
+    cookie_jar: Option<CookieJar>,
+    cancelled_rx: &Receiver<bool>,
+) -> std::result::Result<HttpResponse, SendError> {
+    let mut response_ctx = ResponseContext::new(
+        ctx.query_manager.clone(),
+        og_response.clone(),
+        ctx.update_source.clone(),
+    );
+
+    // Execute the inner send logic and handle errors consistently
+    let start = Instant::now();
+    let result = send_http_request_inner(
+        ctx,
+        unrendered_request,
+        environment,
+        cookie_jar,
+        cancelled_rx,
+        &mut response_ctx,
+    )
+    .await;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => {
+            let error = e.to_string();
+            let elapsed = start.elapsed().as_millis() as i32;
+            let _ = response_ctx.update(|r| {
+                r.state = HttpResponseState::Closed;
+                r.elapsed = elapsed;
+                if r.elapsed_headers == 0 {
+                    r.elapsed_headers = elapsed;
+                }
+                r.error = Some(error);
+            });
+            Err(SendError { response: Box::new(response_ctx.response().clone()), error: e })
+        }
+    }
+}
+
+async fn send_http_request_inner<C: AppContext>(
+    ctx: &HttpSendContext<'_, C>,
+    unrendered_request: &HttpRequest,
+    environment: Option<Environment>,
+    cookie_jar: Option<CookieJar>,
+    cancelled_rx: &Receiver<bool>,
+    response_ctx: &mut ResponseContext,
+) -> Result<HttpResponse> {
+    let plugin_context = ctx.plugin_context;
+    let folder_id = unrendered_request.folder_id.as_deref();
+    let environment_id = environment.map(|e| e.id);
+    let (
+        settings,
+        workspace,
+        http_version,
+        retry_policy,
+        redirect_policy,
+        network,
+        resolved,
+        auth_context_id,
+        env_chain,
+        certificates,
+    ) = {
+        let db = ctx.query_manager.connect();
+        let workspace = db.get_workspace(&unrendered_request.workspace_id)?;
+        let http_version = db.resolve_http_version_for_http_request(unrendered_request)?;
+        let retry_policy = db.resolve_retry_policy_for_http_request(unrendered_request)?;
+        let redirect_policy = db.resolve_redirect_policy_for_http_request(unrendered_request)?;
+        let network = db.resolve_network_settings_for_http_request(unrendered_request)?;
+        let certificates = db.resolve_client_certificates(&workspace.id, folder_id)?;
+        let (resolved, auth_context_id) = resolve_http_request(&db, unrendered_request)?;
+        let mut env_chain =
+            db.resolve_environments(&workspace.id, folder_id, environment_id.as_deref())?;
+        if !ctx.variable_overrides.is_empty() {
+            // The chain is ordered most-specific first, so overrides go in front
+            let overrides =
+                Environment { variables: ctx.variable_overrides.clone(), ..Default::default() };
+            env_chain.insert(0, overrides);
+        }
+        (
+            db.get_settings(),
+            workspace,
+            http_version,
+            retry_policy,
+            redirect_policy,
+            network,
+            resolved,
+            auth_context_id,
+            env_chain,
+            certificates,
+        )
+    };
+    let cb = PluginTemplateCallback::new(
+        ctx.plugin_manager.clone(),
+        ctx.encryption_manager.clone(),
+        plugin_context,
+        RenderPurpose::Send,
+    );
+    let mut cancel_rx = cancelled_rx.clone();
+    let render_options = RenderOptions::throw();
+    let request = tokio::select! {
+        result = render_http_request(&resolved, env_chain.clone(), &cb, &render_options) => result?,
+        _ = cancel_rx.changed() => {
+            return Err(GenericError("Request canceled".to_string()));
+        }
+    };
+
+    // Build the sendable request using the new SendableHttpRequest type
+    let options = SendableHttpRequestOptions {
+        follow_redirects: workspace.setting_follow_redirects,
+        timeout: timeout_setting(workspace.setting_request_timeout),
+        connect_timeout: timeout_setting(workspace.setting_connect_timeout),
+        read_timeout: timeout_setting(workspace.setting_read_timeout),
+    };
+    let mut sendable_request = SendableHttpRequest::from_http_request(&request, options).await?;
+
+    debug!("Sending request to {} {}", sendable_request.method, sendable_request.url);
+
+    let proxy_setting = HttpConnectionProxySetting::from(settings.proxy);
+
+    let client_certificate = match find_client_certificate(&sendable_request.url, &certificates) {
+        Some(c) => Some(render_client_certificate(c, env_chain, &cb, &render_options).await?),
+        None => None,
+    };
+
+    // Create cookie store if a cookie jar is specified
+    let maybe_cookie_store = match (&ctx.cookie_store, cookie_jar) {
+        (Some(cookie_store), _) => Some((cookie_store.clone(), None)),
+        (None, Some(CookieJar { id, .. })) => {
+            // NOTE: We need to refetch the cookie jar because a chained request might have
+            //  updated cookies when we rendered the request.
+            let cj = ctx.query_manager.connect().get_cookie_jar(&id)?;
+            let cookie_store = CookieStore::from_cookies(cj.cookies.clone());
+            Some((cookie_store, Some(cj)))
+        }
+        (None, None) => None,
+    };
+
+    // Certificates are matched per URL, so requests with a different certificate
+    // can't share a client
+    let client_id = match &client_certificate {
+        Some(c) => {
+            let cert_file = c.crt_file.as_ref().or(c.pfx_file.as_ref());
+            format!("{}.{}", plugin_context.id, cert_file.map(String::as_str).unwrap_or_default())
+        }
+        None => plugin_context.id.clone(),
+    };
+
+    let tls_settings = TlsSettings::for_workspace(&workspace);
+    let tls_constraints = tls_settings.constraint_settings();
+    let cached_client = ctx
+        .connection_manager
+        .get_client(&HttpConnectionOptions {
+            id: client_id,
+            tls_settings,
+            proxy: proxy_setting,
+            client_certificate,
+            dns_overrides: workspace.setting_dns_overrides.clone(),
+            dns_server: workspace.setting_dns_server.clone(),
+            network,
+            http_version,
+        })
+        .await?;
+
+    // Apply authentication to the request, racing against cancellation since
+    // auth plugins (e.g. OAuth2) can block indefinitely waiting for user action.
+    let mut cancel_rx = cancelled_rx.clone();
+    tokio::select! {
+        result = apply_authentication(
+            &mut sendable_request,
+            &request,
+            auth_context_id,
+            &ctx.plugin_manager,
+            plugin_context,
+        ) => result?,
+        _ = cancel_rx.changed() => {
+            return Err(GenericError("Request canceled".to_string()));
+        }
+    };
+
+    let resolver = cached_client.resolver.clone();
+    let cookie_store = maybe_cookie_store.as_ref().map(|(cs, _)| cs.clone());
+    let result = execute_transaction(
+        ctx,
+        cached_client,
+        sendable_request,
+        response_ctx,
+        cancelled_rx.clone(),
+        cookie_store,
+        tls_constraints,
+        retry_policy,
+        redirect_policy,
+    )
+    .await;
+
+    // Clear the event sender from the resolver since this request is done. This also
+    // happens on failure, so observers waiting for the event channel to close can finish.
+    resolver.set_event_sender(None).await;
+
+    // Wait for blob writing to complete and check for errors
+    let final_result = match result {
+        Ok(maybe_blob_write_handle) => {
+            // Check if blob writing failed
+            if let Some(handle) = maybe_blob_write_handle {
+                if let Ok(Err(e)) = handle.await {
+                    // Update response with the storage error
+                    let _ = response_ctx.update(|r| {
+                        let error_msg =
+                            format!("Request succeeded but failed to store request body: {}", e);
+                        r.error = Some(match &r.error {
+                            Some(existing) => format!("{}; {}", existing, error_msg),
+                            None => error_msg,
+                        });
+                    });
+                }
+            }
+            Ok(response_ctx.response().clone())
+        }
+        Err(e) => Err(e),
+    };
+
+    // Persist cookies back to the database after the request completes
+    if let Some((cookie_store, Some(mut cj))) = maybe_cookie_store {
+        let cookies = cookie_store.get_all_cookies();
+        cj.cookies = cookies;
+        if let Err(e) =
+            ctx.query_manager.connect().upsert_cookie_jar(&cj, &UpdateSource::Background)
+        {
+            warn!("Failed to persist cookies to database: {}", e);
+        }
+    }
+
+    final_result
+}
+
+/// Resolve the authentication and headers a request inherits from its folders and workspace.
+/// Returns the resolved request along with the ID of the model the authentication came from.
+pub fn resolve_http_request(
+    db: &DbContext,
+    request: &HttpRequest,
+) -> Result<(HttpRequest, String)> {
+    let mut new_request = request.clone();
+
+    let (authentication_type, authentication, authentication_context_id) =
+        db.resolve_auth_for_http_request(request)?;
+    new_request.authentication_type = authentication_type;
+    new_request.authentication = authentication;
+
+    let headers = db.resolve_headers_for_http_request(request)?;
+    new_request.headers = headers;
+
+    Ok((new_request, authentication_context_id))
+}
+
+/// A timeout setting of the workspace, in milliseconds, where zero means no timeout
+fn timeout_setting(millis: i32) -> Option<Duration> {
+    if millis > 0 { Some(Duration::from_millis(millis.unsigned_abs() as u64)) } else { None }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execute_transaction<C: AppContext>(
+    ctx: &HttpSendContext<'_, C>,
+    cached_client: CachedClient,
+    mut sendable_request: SendableHttpRequest,
+    response_ctx: &mut ResponseContext,
+    mut cancelled_rx: Receiver<bool>,
+    cookie_store: Option<CookieStore>,
+    tls_constraints: Vec<(String, String)>,
+    retry_policy: RetryPolicy,
+    redirect_policy: RedirectPolicy,
+) -> Result<Option<JoinHandle<Result<()>>>> {
+    let response_id = response_ctx.response().id.clone();
+    let workspace_id = response_ctx.response().workspace_id.clone();
+    let is_persisted = response_ctx.is_persisted();
+
+    // Keep a reference to the resolver for DNS timing events
+    let resolver = cached_client.resolver.clone();
+
+    let sender = ReqwestSender::with_client(cached_client.client)
+        .with_proxy(cached_client.proxy)
+        .with_resolver(cached_client.resolver);
+    let transaction = match cookie_store {
+        Some(cs) => HttpTransaction::with_cookie_store(sender, cs),
+        None => HttpTransaction::new(sender),
+    }
+    .with_retry_policy(retry_policy)
+    .with_redirect_policy(redirect_policy);
+    let start = Instant::now();
+
+    // Capture request headers before sending
+    let request_headers: Vec<HttpResponseHeader> = sendable_request
+        .headers
+        .iter()
+        .map(|(name, value)| HttpResponseHeader { name: name.clone(), value: value.clone() })
+        .collect();
+
+    // Update response with headers info
+    response_ctx.update(|r| {
+        r.url = sendable_request.url.clone();
+        r.request_headers = request_headers;
+    })?;
+
+    // Create bounded channel for receiving events and spawn a task to store them in DB
+    // Buffer size of 100 events provides back pressure if DB writes are slow
+    let (event_tx, mut event_rx) =
+        tokio::sync::mpsc::channel::<yaak_http::sender::HttpResponseEvent>(100);
+
+    // Set the event sender on the DNS resolver so it can emit DNS timing events
+    resolver.set_event_sender(Some(event_tx.clone())).await;
+
+    // Shared state to capture timing from the event processing task
+    let timings = Arc::new(PhaseTimings::default());
+
+    // Write events to DB (only for persisted responses) and forward them to the observer
+    {
+        let response_id = response_id.clone();
+        let query_manager = ctx.query_manager.clone();
+        let update_source = response_ctx.update_source.clone();
+        let workspace_id = workspace_id.clone();
+        let timings = timings.clone();
+        let observer_tx = ctx.event_tx.clone();
+        tokio::spawn(async move {
+            while let Some(event) = event_rx.recv().await {
+                timings.record(&event);
+                if let Some(tx) = &observer_tx {
+                    let _ = tx.send(event.clone()).await;
+                }
+                if is_persisted {
+                    let db_event =
+                        HttpResponseEvent::new(&response_id, &workspace_id, event.into());
+                    let _ = query_manager
+                        .connect()
+                        .upsert_http_response_event(&db_event, &update_source);
+                }
+            }
+        });
+    }
+
+    // Record non-default TLS handshake constraints, so responses can be told apart
+    for (name, value) in tls_constraints {
+        let _ = event_tx.send(yaak_http::sender::HttpResponseEvent::Setting(name, value)).await;
+    }
+
+    // Capture request body as it's sent (only for persisted responses)
+    let body_id = format!("{}.request", response_id);
+    let maybe_blob_write_handle = match sendable_request.body {
+        Some(SendableBody::Bytes(bytes)) => {
+            if is_persisted {
+                write_bytes_to_db_sync(response_ctx, ctx.blob_manager, &body_id, &bytes)?;
+            }
+            sendable_request.body = Some(SendableBody::Bytes(bytes));
+            None
+        }
+        Some(SendableBody::Stream(stream)) => {
+            // Wrap stream with TeeReader to capture data as it's read
+            // Use unbounded channel to ensure all data is captured without blocking the HTTP request
+            let (body_chunk_tx, body_chunk_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+            let tee_reader = TeeReader::new(stream, body_chunk_tx);
+            let pinned: Pin<Box<dyn AsyncRead + Send + 'static>> = Box::pin(tee_reader);
+
+            let handle = if is_persisted {
+                // Spawn task to write request body chunks to blob DB
+                let query_manager = ctx.query_manager.clone();
+                let blob_manager = ctx.blob_manager.clone();
+                let response_id = response_id.clone();
+                let workspace_id = workspace_id.clone();
+                let body_id = body_id.clone();
+                let update_source = response_ctx.update_source.clone();
+                Some(tokio::spawn(async move {
+                    write_stream_chunks_to_db(
+                        query_manager,
+                        blob_manager,
+                        &body_id,
+                        &workspace_id,
+                        &response_id,
+                        &update_source,
+                        body_chunk_rx,
+                    )
+                    .await
+                }))
+            } else {
+                // For ephemeral responses, just drain the body chunks
+                tokio::spawn(async move {
+                    let mut rx = body_chunk_rx;
+                    while rx.recv().await.is_some() {}
+                });
+                None
+            };
+
+            sendable_request.body = Some(SendableBody::Stream(pinned));
+            handle
+        }
+        None => {
+            sendable_request.body = None;
+            None
+        }
+    };
+
+    // Execute the transaction with cancellation support
+    // This returns the response with headers, but body is not yet consumed
+    // Events (headers, settings, chunks) are sent through the channel
+    let mut http_response = transaction
+        .execute_with_cancellation(sendable_request, cancelled_rx.clone(), event_tx)
+        .await?;
+
+    // Prepare the response path before consuming the body
+    let body_path = if response_id.is_empty() {
+        // Ephemeral responses: use OS temp directory for automatic cleanup
+        let temp_dir = std::env::temp_dir().join("yaak-ephemeral-responses");
+        create_dir_all(&temp_dir).await?;
+        temp_dir.join(generate_id())
+    } else {
+        // Persisted responses: use app data directory
+        let base_dir = ctx.app.app_data_dir().join("responses");
+        create_dir_all(&base_dir).await?;
+        base_dir.join(&response_id)
+    };
+
+    // Extract metadata before consuming the body (headers are available immediately)
+    // Url might change, so update again
+    response_ctx.update(|r| {
+        r.body_path = Some(body_path.to_string_lossy().to_string());
+        r.elapsed_headers = start.elapsed().as_millis() as i32;
+        r.status = http_response.status as i32;
+        r.status_reason = http_response.status_reason.clone();
+        r.url = http_response.url.clone();
+        r.remote_addr = http_response.remote_addr.clone();
+        r.version = http_response.version.clone();
+        r.headers = http_response
+            .headers
+            .iter()
+            .map(|(name, value)| HttpResponseHeader { name: name.clone(), value: value.clone() })
+            .collect();
+        r.content_length = http_response.content_length.map(|l| l as i32);
+        r.state = HttpResponseState::Connected;
+        r.request_headers = http_response
+            .request_headers
+            .iter()
+            .map(|(n, v)| HttpResponseHeader { name: n.clone(), value: v.clone() })
+            .collect();
+    })?;
+
+    // Get the body stream for manual consumption
+    let mut body_stream = http_response.into_body_stream()?;
+
+    // Open file for writing
+    let mut file = File::options()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(&body_path)
+        .await
+        .map_err(|e| GenericError(format!("Failed to open file: {}", e)))?;
+
+    // Stream body to file, with throttled DB updates to avoid excessive writes
+    let mut written_bytes: usize = 0;
+    let mut last_update_time = start;
+    let mut buf = [0u8; 8192];
+
+    // Throttle settings: update DB at most every 100ms
+    const UPDATE_INTERVAL_MS: u128 = 100;
+
+    loop {
+        // Check for cancellation. If we already have headers/body, just close cleanly without error
+        if *cancelled_rx.borrow() {
+            break;
+        }
+
+        // Use select! to race between reading and cancellation, so cancellation is immediate
+        let read_result = tokio::select! {
+            biased;
+            _ = cancelled_rx.changed() => {
+                break;
+            }
+            result = body_stream.read(&mut buf) => result,
+        };
+
+        match read_result {
+            Ok(0) => break, // EOF
+            Ok(n) => {
+                file.write_all(&buf[..n])
+                    .await
+                    .map_err(|e| GenericError(format!("Failed to write to file: {}", e)))?;
+                file.flush()
+                    .await
+                    .map_err(|e| GenericError(format!("Failed to flush file: {}", e)))?;
+                written_bytes += n;
+
+                // Throttle DB updates: only update if enough time has passed
+                let now = Instant::now();
+                let elapsed_since_update = now.duration_since(last_update_time).as_millis();
+
+                if elapsed_since_update >= UPDATE_INTERVAL_MS {
+                    response_ctx.update(|r| {
+                        r.elapsed = start.elapsed().as_millis() as i32;
+                        r.content_length = Some(written_bytes as i32);
+                    })?;
+                    last_update_time = now;
+                }
+            }
+            Err(e) => {
+                return Err(yaak_http::error::Error::from_body_read(e).into());
+            }
+        }
+    }
+
+    // Final update with closed state and accurate byte count
+    response_ctx.update(|r| {
+        r.elapsed = start.elapsed().as_millis() as i32;
+        r.elapsed_dns = timings.dns.load(Ordering::SeqCst);
+        r.elapsed_connect = timings.connect.load(Ordering::SeqCst);
+        r.elapsed_tls = timings.tls.load(Ordering::SeqCst);
+        r.elapsed_ttfb = timings.ttfb.load(Ordering::SeqCst);
+        r.content_length = Some(written_bytes as i32);
+        r.state = HttpResponseState::Closed;
+    })?;
+
+    Ok(maybe_blob_write_handle)
+}
+
+/// Durations of the phases of a request, taken from its timing events. When there are
+/// redirects, the last hop wins.
+#[derive(Default)]
+struct PhaseTimings {
+    dns: AtomicI32,
+    connect: AtomicI32,
+    tls: AtomicI32,
+    ttfb: AtomicI32,
+}
+
+impl PhaseTimings {
+    fn record(&self, event: &yaak_http::sender::HttpResponseEvent) {
+        use yaak_http::sender::HttpResponseEvent as E;
+        let (phase, duration) = match event {
+            E::DnsResolved { duration, .. } => (&self.dns, duration),
+            E::Connected { duration } => (&self.connect, duration),
+            E::TlsHandshake { duration } => (&self.tls, duration),
+            E::FirstByte { duration } => (&self.ttfb, duration),
+            _ => return,
+        };
+        phase.store(*duration as i32, Ordering::SeqCst);
+    }
+}
+
+fn write_bytes_to_db_sync(
+    response_ctx: &mut ResponseContext,
+    blob_manager: &BlobManager,
+    body_id: &str,
+    data: &[u8],
+) -> Result<()> {
+    if data.is_empty() {
+        return Ok(());
+    }
+
+    // Write in chunks if data is large
+    let blobs = blob_manager.connect();
+    for (chunk_index, chunk_data) in data.chunks(REQUEST_BODY_CHUNK_SIZE).enumerate() {
+        let chunk = BodyChunk::new(body_id, chunk_index as i32, chunk_data.to_vec());
+        blobs.insert_chunk(&chunk)?;
+    }
+
+    // Update the response with the total request body size
+    response_ctx.update(|r| {
+        r.request_content_length = Some(data.len() as i32);
+    })?;
+
+    Ok(())
+}
+
+async fn write_stream_chunks_to_db(
+    query_manager: QueryManager,
+    blob_manager: BlobManager,
+    body_id: &str,
+    workspace_id: &str,
+    response_id: &str,
+    update_source: &UpdateSource,
+    mut rx: tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>,
+) -> Result<()> {
+    let mut buffer = Vec::with_capacity(REQUEST_BODY_CHUNK_SIZE);
+    let mut chunk_index = 0;
+    let mut total_bytes: usize = 0;
+
+    let write_chunk = |chunk: BodyChunk| -> Result<()> {
+        blob_manager.connect().insert_chunk(&chunk)?;
+        query_manager.connect().upsert_http_response_event(
+            &HttpResponseEvent::new(
+                response_id,
+                workspace_id,
+                yaak_http::sender::HttpResponseEvent::ChunkSent { bytes: chunk.data.len() }.into(),
+            ),
+            update_source,
+        )?;
+        Ok(())
+    };
+
+    while let Some(data) = rx.recv().await {
+        total_bytes += data.len();
+        buffer.extend_from_slice(&data);
+
+        // Flush when buffer reaches chunk size
+        while buffer.len() >= REQUEST_BODY_CHUNK_SIZE {
+            debug!("Writing chunk {chunk_index} to DB");
+            let chunk_data: Vec<u8> = buffer.drain(..REQUEST_BODY_CHUNK_SIZE).collect();
+            write_chunk(BodyChunk::new(body_id, chunk_index, chunk_data))?;
+            chunk_index += 1;
+        }
+    }
+
+    // Flush remaining data
+    if !buffer.is_empty() {
+        debug!("Flushing remaining data {chunk_index} {}", buffer.len());
+        write_chunk(BodyChunk::new(body_id, chunk_index, buffer))?;
+    }
+
+    // Update the response with the total request body size
+    query_manager.with_tx(|tx| {
+        debug!("Updating final body length {total_bytes}");
+        if let Ok(mut response) = tx.get_http_response(response_id) {
+            response.request_content_length = Some(total_bytes as i32);
+            tx.update_http_response_if_id(&response, update_source)?;
+        }
+        Ok(())
+    })
+}
+
+async fn apply_authentication(
+    sendable_request: &mut SendableHttpRequest,
+    request: &HttpRequest,
+    auth_context_id: String,
+    plugin_manager: &PluginManager,
+    plugin_context: &PluginContext,
+) -> Result<()> {
+    match &request.authentication_type {
+        None => {
+            // No authentication found. Not even inherited
+        }
+        Some(authentication_type) if authentication_type == "none" => {
+            // Explicitly no authentication
+        }
+        Some(authentication_type) => {
+            let req = CallHttpAuthenticationRequest {
+                context_id: format!("{:x}", md5::compute(auth_context_id)),
+                values: serde_json::from_value(serde_json::to_value(&request.authentication)?)?,
+                url: sendable_request.url.clone(),
+                method: sendable_request.method.clone(),
+                headers: sendable_request
+                    .headers
+                    .iter()
+                    .map(|(name, value)| HttpHeader {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            };
+            let plugin_result = plugin_manager
+                .call_http_authentication(plugin_context, authentication_type, req)
+                .await?;
+
+            for header in plugin_result.set_headers.unwrap_or_default() {
+                sendable_request.insert_header((header.name, header.value));
+            }
+
+            if let Some(params) = plugin_result.set_query_parameters {
+                let params = params.into_iter().map(|p| (p.name, p.value)).collect::<Vec<_>>();
+                sendable_request.url = append_query_params(&sendable_request.url, params);
+            }
+        }
+    }
+    Ok(())
+}
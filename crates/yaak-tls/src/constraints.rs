@@ -0,0 +1,144 @@
+//! Constraints on what's offered in the handshake, for testing how servers handle specific
+//! protocol versions, cipher suites and key exchange groups.
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use rustls::crypto::CryptoProvider;
+use rustls::{SupportedCipherSuite, SupportedProtocolVersion};
+use yaak_models::models::TlsVersion;
+
+/// Protocol versions between `min` and `max`, inclusive
+pub(crate) fn protocol_versions(
+    min: Option<TlsVersion>,
+    max: Option<TlsVersion>,
+) -> Result<Vec<&'static SupportedProtocolVersion>> {
+    let min = min.unwrap_or(TlsVersion::Tls12);
+    let max = max.unwrap_or(TlsVersion::Tls13);
+    if min > max {
+        return Err(GenericError(format!(
+            "Minimum TLS version {} is above the maximum {}",
+            version_name(min),
+            version_name(max)
+        )));
+    }
+
+    let versions = [
+        (TlsVersion::Tls12, &rustls::version::TLS12),
+        (TlsVersion::Tls13, &rustls::version::TLS13),
+    ];
+    Ok(versions.into_iter().filter(|(v, _)| (min..=max).contains(v)).map(|(_, s)| s).collect())
+}
+
+/// Name of a version as shown on the timeline, formatted like OpenSSL does
+pub fn version_name(version: TlsVersion) -> &'static str {
+    match version {
+        TlsVersion::Tls12 => "TLSv1.2",
+        TlsVersion::Tls13 => "TLSv1.3",
+    }
+}
+
+/// Restrict the provider's cipher suites and key exchange groups to the ones named, keeping
+/// the provider's preference order. Empty lists leave the defaults.
+pub(crate) fn constrain_provider(
+    mut provider: CryptoProvider,
+    cipher_suites: &[String],
+    kx_groups: &[String],
+) -> Result<CryptoProvider> {
+    if !cipher_suites.is_empty() {
+        for name in cipher_suites {
+            if !provider.cipher_suites.iter().any(|s| cipher_suite_matches(s, name)) {
+                return Err(GenericError(format!(
+                    "Unsupported cipher suite {name}. Available: {}",
+                    provider
+                        .cipher_suites
+                        .iter()
+                        .map(|s| format!("{:?}", s.suite()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        }
+        provider
+            .cipher_suites
+            .retain(|s| cipher_suites.iter().any(|name| cipher_suite_matches(s, name)));
+    }
+
+    if !kx_groups.is_empty() {
+        for name in kx_groups {
+            if !provider
+                .kx_groups
+                .iter()
+                .any(|g| kx_group_name(g.name()).eq_ignore_ascii_case(name))
+            {
+                return Err(GenericError(format!(
+                    "Unsupported key exchange group {name}. Available: {}",
+                    provider
+                        .kx_groups
+                        .iter()
+                        .map(|g| kx_group_name(g.name()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        }
+        provider.kx_groups.retain(|g| {
+            kx_groups.iter().any(|name| kx_group_name(g.name()).eq_ignore_ascii_case(name))
+        });
+    }
+
+    Ok(provider)
+}
+
+/// Match the rustls name of a suite (e.g. `TLS13_AES_128_GCM_SHA256`) or the IANA one, which
+/// has no version for TLS 1.3 suites (e.g. `TLS_AES_128_GCM_SHA256`)
+fn cipher_suite_matches(suite: &SupportedCipherSuite, name: &str) -> bool {
+    let rustls_name = format!("{:?}", suite.suite());
+    let iana_name = rustls_name.replacen("TLS13_", "TLS_", 1);
+    let name = name.trim();
+    rustls_name.eq_ignore_ascii_case(name) || iana_name.eq_ignore_ascii_case(name)
+}
+
+fn kx_group_name(group: rustls::NamedGroup) -> String {
+    format!("{group:?}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustls::crypto::ring;
+
+    #[test]
+    fn test_protocol_versions() {
+        assert_eq!(protocol_versions(None, None).unwrap().len(), 2);
+
+        let versions = protocol_versions(Some(TlsVersion::Tls13), None).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, rustls::ProtocolVersion::TLSv1_3);
+
+        let versions = protocol_versions(None, Some(TlsVersion::Tls12)).unwrap();
+        assert_eq!(versions[0].version, rustls::ProtocolVersion::TLSv1_2);
+
+        assert!(protocol_versions(Some(TlsVersion::Tls13), Some(TlsVersion::Tls12)).is_err());
+    }
+
+    #[test]
+    fn test_constrain_provider() {
+        let provider = constrain_provider(
+            ring::default_provider(),
+            &[
+                "TLS_AES_256_GCM_SHA384".to_string(),
+                "tls13_chacha20_poly1305_sha256".to_string(),
+            ],
+            &["x25519".to_string()],
+        )
+        .unwrap();
+        assert_eq!(provider.cipher_suites.len(), 2);
+        assert_eq!(provider.kx_groups.len(), 1);
+
+        let provider = constrain_provider(ring::default_provider(), &[], &[]).unwrap();
+        assert_eq!(provider.cipher_suites.len(), ring::default_provider().cipher_suites.len());
+
+        assert!(constrain_provider(ring::default_provider(), &["nope".to_string()], &[]).is_err());
+        assert!(constrain_provider(ring::default_provider(), &[], &["nope".to_string()]).is_err());
+    }
+}
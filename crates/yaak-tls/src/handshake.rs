@@ -0,0 +1,314 @@
+//! Recording of TLS handshake details (start time, SNI, negotiated cipher suite and the peer
+//! certificate chain).
+//!
+//! rustls doesn't expose the connection to code above the connector, so the details are
+//! recorded from the hooks rustls calls during the handshake: the session store (read before
+//! the ClientHello is sent), the certificate verifier, and the hash of the negotiated cipher
+//! suite (started once the ServerHello picks it). Everything is written into a task-local
+//! scoped by [`record_handshake`]; outside of it the hooks do nothing.
+
+use chrono::DateTime;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::{
+    ClientSessionMemoryCache, ClientSessionStore, Tls12ClientSessionValue, Tls13ClientSessionValue,
+};
+use rustls::crypto::hash::{Context, Hash, HashAlgorithm, Output};
+use rustls::crypto::{CryptoProvider, ring};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CipherSuiteCommon, DigitallySignedStruct, NamedGroup, ProtocolVersion, SignatureScheme,
+    SupportedCipherSuite, Tls12CipherSuite, Tls13CipherSuite,
+};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::future::Future;
+use std::sync::{Arc, LazyLock};
+use std::time::Instant;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, X509Certificate};
+use yaak_models::models::TlsCertificate;
+
+/// Same as the size of the default rustls session cache
+const SESSION_CACHE_SIZE: usize = 256;
+
+/// What was seen of a TLS handshake. Fields stay empty when no handshake happened, and the
+/// certificates are missing when a session was resumed, because the server doesn't send them.
+#[derive(Debug, Clone, Default)]
+pub struct HandshakeDetails {
+    pub started_at: Option<Instant>,
+    pub server_name: Option<String>,
+    pub cipher_suite: Option<SupportedCipherSuite>,
+    pub peer_certificates: Vec<CertificateDer<'static>>,
+}
+
+impl HandshakeDetails {
+    /// Protocol version, formatted like OpenSSL does (e.g. `TLSv1.3`)
+    pub fn version(&self) -> Option<String> {
+        let version = match self.cipher_suite?.version().version {
+            ProtocolVersion::TLSv1_2 => "TLSv1.2".to_string(),
+            ProtocolVersion::TLSv1_3 => "TLSv1.3".to_string(),
+            v => format!("{v:?}"),
+        };
+        Some(version)
+    }
+
+    /// IANA name of the cipher suite (e.g. `TLS13_AES_128_GCM_SHA256`)
+    pub fn cipher_suite_name(&self) -> Option<String> {
+        self.cipher_suite.map(|s| format!("{:?}", s.suite()))
+    }
+}
+
+tokio::task_local! {
+    static HANDSHAKE: RefCell<HandshakeDetails>;
+}
+
+/// Run a connection future, returning its output along with the details of the TLS handshake
+/// it performed, if any
+pub async fn record_handshake<F: Future>(f: F) -> (F::Output, HandshakeDetails) {
+    HANDSHAKE
+        .scope(RefCell::new(HandshakeDetails::default()), async move {
+            let output = f.await;
+            (output, HANDSHAKE.with(|h| h.borrow().clone()))
+        })
+        .await
+}
+
+fn record(f: impl FnOnce(&mut HandshakeDetails)) {
+    let _ = HANDSHAKE.try_with(|h| f(&mut h.borrow_mut()));
+}
+
+fn mark_started(server_name: &ServerName<'_>) {
+    record(|h| {
+        if h.started_at.is_none() {
+            h.started_at = Some(Instant::now());
+            h.server_name = Some(server_name.to_str().to_string());
+        }
+    });
+}
+
+/// Parse a DER certificate into what's shown to the user
+pub fn describe_certificate(der: &CertificateDer<'_>) -> TlsCertificate {
+    let sha256_fingerprint = Sha256::digest(der.as_ref())
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(":");
+
+    let cert = match X509Certificate::from_der(der.as_ref()) {
+        Ok((_, cert)) => cert,
+        Err(_) => return TlsCertificate { sha256_fingerprint, ..Default::default() },
+    };
+
+    let sans = match cert.subject_alternative_name() {
+        Ok(Some(ext)) => ext
+            .value
+            .general_names
+            .iter()
+            .map(|name| match name {
+                GeneralName::DNSName(n) => n.to_string(),
+                GeneralName::IPAddress(ip) => format_ip(ip),
+                n => format!("{n:?}"),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let validity = cert.validity();
+    TlsCertificate {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        sans,
+        not_before: timestamp_to_datetime(validity.not_before.timestamp()),
+        not_after: timestamp_to_datetime(validity.not_after.timestamp()),
+        sha256_fingerprint,
+    }
+}
+
+fn format_ip(bytes: &[u8]) -> String {
+    if let Ok(octets) = <[u8; 4]>::try_from(bytes) {
+        return std::net::Ipv4Addr::from(octets).to_string();
+    }
+    if let Ok(octets) = <[u8; 16]>::try_from(bytes) {
+        return std::net::Ipv6Addr::from(octets).to_string();
+    }
+    hex::encode(bytes)
+}
+
+fn timestamp_to_datetime(ts: i64) -> chrono::NaiveDateTime {
+    DateTime::from_timestamp(ts, 0).unwrap_or_default().naive_utc()
+}
+
+/// Crypto provider whose cipher suites record themselves once negotiated
+pub(crate) fn recording_provider() -> CryptoProvider {
+    CryptoProvider { cipher_suites: RECORDING_CIPHER_SUITES.clone(), ..ring::default_provider() }
+}
+
+/// The ring cipher suites, with their hash swapped for one that records the suite. Suites
+/// are referenced as `&'static` by rustls, so they're built once and leaked.
+static RECORDING_CIPHER_SUITES: LazyLock<Vec<SupportedCipherSuite>> = LazyLock::new(|| {
+    ring::default_provider().cipher_suites.into_iter().map(recording_cipher_suite).collect()
+});
+
+fn recording_cipher_suite(suite: SupportedCipherSuite) -> SupportedCipherSuite {
+    match suite {
+        SupportedCipherSuite::Tls13(s) => {
+            let hash_provider = recording_hash(suite, s.common.hash_provider);
+            let common = CipherSuiteCommon { hash_provider, ..s.common };
+            SupportedCipherSuite::Tls13(Box::leak(Box::new(Tls13CipherSuite { common, ..*s })))
+        }
+        SupportedCipherSuite::Tls12(s) => {
+            let hash_provider = recording_hash(suite, s.common.hash_provider);
+            let common = CipherSuiteCommon { hash_provider, ..s.common };
+            SupportedCipherSuite::Tls12(Box::leak(Box::new(Tls12CipherSuite { common, ..*s })))
+        }
+    }
+}
+
+fn recording_hash(suite: SupportedCipherSuite, inner: &'static dyn Hash) -> &'static dyn Hash {
+    Box::leak(Box::new(RecordingHash { suite, inner }))
+}
+
+/// Hash that records its cipher suite when a transcript is started with it. rustls starts
+/// the transcript hash after the ServerHello, so the last suite recorded is the negotiated one.
+struct RecordingHash {
+    suite: SupportedCipherSuite,
+    inner: &'static dyn Hash,
+}
+
+impl Hash for RecordingHash {
+    fn start(&self) -> Box<dyn Context> {
+        record(|h| h.cipher_suite = Some(self.suite));
+        self.inner.start()
+    }
+
+    fn hash(&self, data: &[u8]) -> Output {
+        self.inner.hash(data)
+    }
+
+    fn output_len(&self) -> usize {
+        self.inner.output_len()
+    }
+
+    fn algorithm(&self) -> HashAlgorithm {
+        self.inner.algorithm()
+    }
+
+    fn fips(&self) -> bool {
+        self.inner.fips()
+    }
+}
+
+/// Certificate verifier that records the chain presented by the server before delegating
+#[derive(Debug)]
+pub(crate) struct RecordingVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+}
+
+impl RecordingVerifier {
+    pub(crate) fn new(inner: Arc<dyn ServerCertVerifier>) -> Self {
+        Self { inner }
+    }
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer,
+        intermediates: &[CertificateDer],
+        server_name: &ServerName,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        record(|h| {
+            h.peer_certificates = std::iter::once(end_entity)
+                .chain(intermediates)
+                .map(|c| c.clone().into_owned())
+                .collect();
+        });
+        self.inner.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+
+    fn requires_raw_public_keys(&self) -> bool {
+        self.inner.requires_raw_public_keys()
+    }
+
+    fn root_hint_subjects(&self) -> Option<&[rustls::DistinguishedName]> {
+        self.inner.root_hint_subjects()
+    }
+}
+
+/// Session store that marks the start of the handshake and the server name, because rustls
+/// reads it to build the ClientHello. Storage is delegated to the default in-memory cache, so
+/// session resumption works as before.
+#[derive(Debug)]
+pub(crate) struct RecordingSessionStore {
+    inner: ClientSessionMemoryCache,
+}
+
+impl RecordingSessionStore {
+    pub(crate) fn new() -> Self {
+        Self { inner: ClientSessionMemoryCache::new(SESSION_CACHE_SIZE) }
+    }
+}
+
+impl ClientSessionStore for RecordingSessionStore {
+    fn set_kx_hint(&self, server_name: ServerName<'static>, group: NamedGroup) {
+        self.inner.set_kx_hint(server_name, group)
+    }
+
+    fn kx_hint(&self, server_name: &ServerName<'_>) -> Option<NamedGroup> {
+        mark_started(server_name);
+        self.inner.kx_hint(server_name)
+    }
+
+    fn set_tls12_session(&self, server_name: ServerName<'static>, value: Tls12ClientSessionValue) {
+        self.inner.set_tls12_session(server_name, value)
+    }
+
+    fn tls12_session(&self, server_name: &ServerName<'_>) -> Option<Tls12ClientSessionValue> {
+        mark_started(server_name);
+        self.inner.tls12_session(server_name)
+    }
+
+    fn remove_tls12_session(&self, server_name: &ServerName<'static>) {
+        self.inner.remove_tls12_session(server_name)
+    }
+
+    fn insert_tls13_ticket(
+        &self,
+        server_name: ServerName<'static>,
+        value: Tls13ClientSessionValue,
+    ) {
+        self.inner.insert_tls13_ticket(server_name, value)
+    }
+
+    fn take_tls13_ticket(
+        &self,
+        server_name: &ServerName<'static>,
+    ) -> Option<Tls13ClientSessionValue> {
+        mark_started(server_name);
+        self.inner.take_tls13_ticket(server_name)
+    }
+}
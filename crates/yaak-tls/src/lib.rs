@@ -1,18 +1,26 @@
+use crate::constraints::{constrain_provider, protocol_versions, version_name};
 use crate::error::Error::GenericError;
 use crate::error::Result;
+use crate::handshake::{RecordingSessionStore, RecordingVerifier, recording_provider};
+use crate::pinning::PinningVerifier;
 use log::debug;
+use rustls::client::Resumption;
 use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
-use rustls::crypto::ring;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
 use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
-use rustls_platform_verifier::BuilderVerifierExt;
+use rustls_platform_verifier::Verifier;
 use std::fs;
 use std::io::BufReader;
 use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
+use yaak_models::models::{CertificatePin, TlsVersion, Workspace};
 
+mod constraints;
 pub mod error;
+pub mod handshake;
+mod pinning;
+pub mod render;
 
 #[derive(Clone, Default)]
 pub struct ClientCertificateConfig {
@@ -22,55 +30,165 @@ pub struct ClientCertificateConfig {
     pub passphrase: Option<String>,
 }
 
+/// How the server's certificate is verified, and what's offered in the handshake
+#[derive(Clone, Debug, Hash)]
+pub struct TlsSettings {
+    pub validate_certificates: bool,
+    /// PEM files, or directories of them, with CA certificates trusted on top of the platform
+    /// roots. Ignored when certificates aren't validated.
+    pub ca_certificates: Vec<String>,
+    /// Checked even when certificates aren't validated
+    pub pins: Vec<CertificatePin>,
+    pub min_version: Option<TlsVersion>,
+    pub max_version: Option<TlsVersion>,
+    /// Cipher suite names, in rustls or IANA form. All supported suites when empty.
+    pub cipher_suites: Vec<String>,
+    /// Key exchange group names (e.g. `X25519`). All supported groups when empty.
+    pub kx_groups: Vec<String>,
+    pub send_sni: bool,
+}
+
+impl Default for TlsSettings {
+    /// Certificates are validated against the platform roots and SNI is sent, with no other
+    /// constraints
+    fn default() -> Self {
+        Self {
+            validate_certificates: true,
+            ca_certificates: Vec::new(),
+            pins: Vec::new(),
+            min_version: None,
+            max_version: None,
+            cipher_suites: Vec::new(),
+            kx_groups: Vec::new(),
+            send_sni: true,
+        }
+    }
+}
+
+impl TlsSettings {
+    pub fn for_workspace(workspace: &Workspace) -> Self {
+        Self {
+            validate_certificates: workspace.setting_validate_certificates,
+            ca_certificates: workspace.setting_ca_certificates.clone(),
+            pins: workspace.setting_certificate_pins.clone(),
+            min_version: workspace.setting_tls_min_version,
+            max_version: workspace.setting_tls_max_version,
+            cipher_suites: workspace.setting_tls_cipher_suites.clone(),
+            kx_groups: workspace.setting_tls_kx_groups.clone(),
+            send_sni: workspace.setting_tls_send_sni,
+        }
+    }
+
+    /// Handshake constraints that differ from the defaults, as name/value pairs for the
+    /// response timeline
+    pub fn constraint_settings(&self) -> Vec<(String, String)> {
+        let mut settings = Vec::new();
+        if let Some(v) = self.min_version {
+            settings.push(("tls_min_version".to_string(), version_name(v).to_string()));
+        }
+        if let Some(v) = self.max_version {
+            settings.push(("tls_max_version".to_string(), version_name(v).to_string()));
+        }
+        if !self.cipher_suites.is_empty() {
+            settings.push(("tls_cipher_suites".to_string(), self.cipher_suites.join(",")));
+        }
+        if !self.kx_groups.is_empty() {
+            settings.push(("tls_kx_groups".to_string(), self.kx_groups.join(",")));
+        }
+        if !self.send_sni {
+            settings.push(("tls_sni".to_string(), "disabled".to_string()));
+        }
+        settings
+    }
+}
+
+/// Build a TLS config that offers `alpn_protocols` (e.g. `h2`, `http/1.1`), most preferred
+/// first. ALPN is left out of the handshake when the list is empty. Handshakes made with the
+/// config can be inspected with [`handshake::record_handshake`].
 pub fn get_tls_config(
-    validate_certificates: bool,
-    with_alpn: bool,
+    tls_settings: &TlsSettings,
+    alpn_protocols: &[&str],
     client_cert: Option<ClientCertificateConfig>,
 ) -> Result<ClientConfig> {
     let maybe_client_cert = load_client_cert(client_cert)?;
-
-    let mut client = if validate_certificates {
-        build_with_validation(maybe_client_cert)
+    let crypto_provider = Arc::new(constrain_provider(
+        recording_provider(),
+        &tls_settings.cipher_suites,
+        &tls_settings.kx_groups,
+    )?);
+    let versions = protocol_versions(tls_settings.min_version, tls_settings.max_version)?;
+
+    let verifier: Arc<dyn ServerCertVerifier> = if tls_settings.validate_certificates {
+        let extra_roots = load_ca_certificates(&tls_settings.ca_certificates)?;
+        if extra_roots.is_empty() {
+            Arc::new(Verifier::new(crypto_provider.clone())?)
+        } else {
+            Arc::new(Verifier::new_with_extra_roots(extra_roots, crypto_provider.clone())?)
+        }
     } else {
-        build_without_validation(maybe_client_cert)
-    }?;
+        Arc::new(NoVerifier)
+    };
+    let verifier = PinningVerifier::wrap(verifier, &tls_settings.pins, &crypto_provider);
 
-    if with_alpn {
-        client.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
-    }
+    let builder = ClientConfig::builder_with_provider(crypto_provider)
+        .with_protocol_versions(&versions)?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(RecordingVerifier::new(verifier)));
+
+    let mut client = match maybe_client_cert {
+        Some((certs, key)) => builder.with_client_auth_cert(certs, key)?,
+        None => builder.with_no_client_auth(),
+    };
+
+    client.resumption = Resumption::store(Arc::new(RecordingSessionStore::new()));
+    client.enable_sni = tls_settings.send_sni;
+    client.alpn_protocols = alpn_protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
 
     Ok(client)
 }
 
-fn build_with_validation(
-    client_cert: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
-) -> Result<ClientConfig> {
-    let arc_crypto_provider = Arc::new(ring::default_provider());
-    let builder = ClientConfig::builder_with_provider(arc_crypto_provider)
-        .with_safe_default_protocol_versions()?
-        .with_platform_verifier()?;
+fn load_ca_certificates(paths: &[String]) -> Result<Vec<CertificateDer<'static>>> {
+    let mut certs = Vec::new();
+    for path in paths.iter().map(|p| Path::new(p.trim())) {
+        if path.as_os_str().is_empty() {
+            continue;
+        }
 
-    if let Some((certs, key)) = client_cert {
-        return Ok(builder.with_client_auth_cert(certs, key)?);
+        if !path.is_dir() {
+            let found = load_pem_certs(path)?;
+            if found.is_empty() {
+                return Err(GenericError(format!(
+                    "No certificates found in CA file {}",
+                    path.display()
+                )));
+            }
+            certs.extend(found);
+            continue;
+        }
+
+        // Only load files that look like certificates, so a directory can also hold keys
+        let mut entries: Vec<_> = fs::read_dir(path)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.is_file()
+                    && p.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+                        ["pem", "crt", "cer"].iter().any(|x| e.eq_ignore_ascii_case(x))
+                    })
+            })
+            .collect();
+        entries.sort();
+        for entry in entries {
+            certs.extend(load_pem_certs(&entry)?);
+        }
     }
 
-    Ok(builder.with_no_client_auth())
+    debug!("Loaded {} extra CA certificates", certs.len());
+    Ok(certs)
 }
 
-fn build_without_validation(
-    client_cert: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
-) -> Result<ClientConfig> {
-    let arc_crypto_provider = Arc::new(ring::default_provider());
-    let builder = ClientConfig::builder_with_provider(arc_crypto_provider)
-        .with_safe_default_protocol_versions()?
-        .dangerous()
-        .with_custom_certificate_verifier(Arc::new(NoVerifier));
-
-    if let Some((certs, key)) = client_cert {
-        return Ok(builder.with_client_auth_cert(certs, key)?);
-    }
-
-    Ok(builder.with_no_client_auth())
+fn load_pem_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    Ok(rustls_pemfile::certs(&mut reader).filter_map(|r| r.ok()).collect())
 }
 
 fn load_client_cert(
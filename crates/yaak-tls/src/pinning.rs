@@ -0,0 +1,214 @@
+//! Certificate pinning. A pin is either `sha256/<base64>` of a certificate's public key
+//! (SubjectPublicKeyInfo), like curl's `--pinnedpubkey`, or the hex SHA-256 fingerprint of
+//! the certificate itself, with or without colons. A host's pins are satisfied when any of
+//! them matches the server's own (end-entity) certificate. Intermediates aren't matched, since
+//! anyone can append a public certificate to their chain, while the handshake signature proves
+//! the server holds the key of its end-entity certificate. The signature is verified here even
+//! when certificate validation is disabled, so a pin can't be passed without that key.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use log::warn;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{CryptoProvider, WebPkiSupportedAlgorithms};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use x509_parser::prelude::{FromDer, X509Certificate};
+use yaak_models::models::CertificatePin;
+
+#[derive(Debug, PartialEq)]
+enum Pin {
+    PublicKey(Vec<u8>),
+    Certificate(Vec<u8>),
+}
+
+fn parse_pin(pin: &str) -> Option<Pin> {
+    let pin = pin.trim();
+    if let Some(b64) = pin.strip_prefix("sha256/") {
+        return STANDARD.decode(b64).ok().filter(|h| h.len() == 32).map(Pin::PublicKey);
+    }
+    hex::decode(pin.replace(':', "")).ok().filter(|h| h.len() == 32).map(Pin::Certificate)
+}
+
+/// Match a pin's host against the server name. `*.example.com` matches a single label.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest.eq_ignore_ascii_case(suffix)),
+        None => pattern.eq_ignore_ascii_case(host),
+    }
+}
+
+fn pin_matches(pin: &Pin, cert: &CertificateDer<'_>) -> bool {
+    match pin {
+        Pin::Certificate(hash) => Sha256::digest(cert.as_ref()).as_slice() == hash.as_slice(),
+        Pin::PublicKey(hash) => match X509Certificate::from_der(cert.as_ref()) {
+            Ok((_, c)) => Sha256::digest(c.public_key().raw).as_slice() == hash.as_slice(),
+            Err(_) => false,
+        },
+    }
+}
+
+/// Certificate verifier that checks the pins for the server's host after the inner verifier
+/// accepted the chain, and always checks handshake signatures
+#[derive(Debug)]
+pub(crate) struct PinningVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    pins: Vec<CertificatePin>,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl PinningVerifier {
+    /// Wrap `inner` if any of `pins` is enabled, otherwise return it as is
+    pub(crate) fn wrap(
+        inner: Arc<dyn ServerCertVerifier>,
+        pins: &[CertificatePin],
+        provider: &CryptoProvider,
+    ) -> Arc<dyn ServerCertVerifier> {
+        let pins: Vec<CertificatePin> = pins.iter().filter(|p| p.enabled).cloned().collect();
+        if pins.is_empty() {
+            return inner;
+        }
+        Arc::new(Self { inner, pins, algorithms: provider.signature_verification_algorithms })
+    }
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer,
+        intermediates: &[CertificateDer],
+        server_name: &ServerName,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+
+        let host = server_name.to_str();
+        let pins: Vec<Pin> = self
+            .pins
+            .iter()
+            .filter(|p| host_matches(&p.host, &host))
+            .filter_map(|p| {
+                let parsed = parse_pin(&p.pin);
+                if parsed.is_none() {
+                    warn!("Ignoring invalid certificate pin for {}: {}", p.host, p.pin);
+                }
+                parsed
+            })
+            .collect();
+
+        if pins.is_empty() {
+            return Ok(verified);
+        }
+
+        if pins.iter().any(|pin| pin_matches(pin, end_entity)) {
+            Ok(verified)
+        } else {
+            Err(rustls::Error::General(format!(
+                "The certificate of {host} doesn't match its pinned keys or fingerprints"
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+
+    fn requires_raw_public_keys(&self) -> bool {
+        self.inner.requires_raw_public_keys()
+    }
+
+    fn root_hint_subjects(&self) -> Option<&[rustls::DistinguishedName]> {
+        self.inner.root_hint_subjects()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pin() {
+        let spki = format!("sha256/{}", STANDARD.encode([7u8; 32]));
+        assert_eq!(parse_pin(&spki), Some(Pin::PublicKey(vec![7; 32])));
+
+        let fingerprint = vec!["AB"; 32].join(":");
+        assert_eq!(parse_pin(&fingerprint), Some(Pin::Certificate(vec![0xab; 32])));
+        assert_eq!(parse_pin(&"ab".repeat(32)), Some(Pin::Certificate(vec![0xab; 32])));
+
+        assert_eq!(parse_pin("sha256/not-base64"), None);
+        assert_eq!(parse_pin("abcd"), None);
+    }
+
+    #[test]
+    fn test_host_matches() {
+        assert!(host_matches("api.example.com", "API.example.com"));
+        assert!(!host_matches("api.example.com", "example.com"));
+        assert!(host_matches("*.example.com", "api.example.com"));
+        assert!(!host_matches("*.example.com", "example.com"));
+        assert!(!host_matches("*.example.com", "a.b.example.com"));
+    }
+
+    #[test]
+    fn test_certificate_pin_matches() {
+        let cert = CertificateDer::from(b"not a certificate".to_vec());
+        let fingerprint = Sha256::digest(cert.as_ref()).to_vec();
+        assert!(pin_matches(&Pin::Certificate(fingerprint), &cert));
+        assert!(!pin_matches(&Pin::Certificate(vec![0; 32]), &cert));
+        assert!(!pin_matches(&Pin::PublicKey(vec![0; 32]), &cert));
+    }
+
+    #[test]
+    fn test_pin_ignores_intermediates() {
+        let pinned = CertificateDer::from(b"pinned certificate".to_vec());
+        let other = CertificateDer::from(b"other certificate".to_vec());
+        let pin = CertificatePin {
+            host: "example.com".to_string(),
+            pin: hex::encode(Sha256::digest(pinned.as_ref())),
+            enabled: true,
+        };
+        let provider = rustls::crypto::ring::default_provider();
+        let verifier = PinningVerifier::wrap(Arc::new(crate::NoVerifier), &[pin], &provider);
+        let server_name = ServerName::try_from("example.com").unwrap();
+        let verify = |end_entity: &CertificateDer, intermediates: &[CertificateDer]| {
+            verifier.verify_server_cert(
+                end_entity,
+                intermediates,
+                &server_name,
+                &[],
+                UnixTime::now(),
+            )
+        };
+
+        assert!(verify(&pinned, &[]).is_ok());
+        assert!(verify(&other, std::slice::from_ref(&pinned)).is_err());
+    }
+}
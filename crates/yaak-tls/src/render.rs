@@ -0,0 +1,34 @@
+use crate::ClientCertificateConfig;
+use std::collections::HashMap;
+use yaak_models::models::Environment;
+use yaak_models::render::make_vars_hashmap;
+use yaak_templates::{RenderOptions, TemplateCallback, parse_and_render};
+
+/// Render the file paths and passphrase of a client certificate, so they can come from
+/// environment variables or encrypted values
+pub async fn render_client_certificate<T: TemplateCallback>(
+    cert: ClientCertificateConfig,
+    environment_chain: Vec<Environment>,
+    cb: &T,
+    opt: &RenderOptions,
+) -> yaak_templates::error::Result<ClientCertificateConfig> {
+    let vars = &make_vars_hashmap(environment_chain);
+    Ok(ClientCertificateConfig {
+        crt_file: render_optional(cert.crt_file, vars, cb, opt).await?,
+        key_file: render_optional(cert.key_file, vars, cb, opt).await?,
+        pfx_file: render_optional(cert.pfx_file, vars, cb, opt).await?,
+        passphrase: render_optional(cert.passphrase, vars, cb, opt).await?,
+    })
+}
+
+async fn render_optional<T: TemplateCallback>(
+    value: Option<String>,
+    vars: &HashMap<String, String>,
+    cb: &T,
+    opt: &RenderOptions,
+) -> yaak_templates::error::Result<Option<String>> {
+    match value {
+        Some(v) => Ok(Some(parse_and_render(&v, vars, cb, opt).await?)),
+        None => Ok(None),
+    }
+}
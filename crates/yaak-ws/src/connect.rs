@@ -7,22 +7,27 @@ use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 use tokio_tungstenite::tungstenite::handshake::client::Response;
 use tokio_tungstenite::tungstenite::http::HeaderValue;
 use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
-use tokio_tungstenite::{
-    Connector, MaybeTlsStream, WebSocketStream, connect_async_tls_with_config,
-};
-use yaak_tls::{ClientCertificateConfig, get_tls_config};
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream, client_async_tls_with_config};
+use url::Url;
+use yaak_http::client::HttpConnectionProxySetting;
+use yaak_http::proxy::ProxySelector;
+use yaak_http::tunnel;
+use yaak_models::models::NetworkSettings;
+use yaak_tls::{ClientCertificateConfig, TlsSettings, get_tls_config};
 
 // Enabling ALPN breaks websocket requests
-const WITH_ALPN: bool = false;
+const ALPN_PROTOCOLS: &[&str] = &[];
 
 pub async fn ws_connect(
     url: &str,
     headers: HeaderMap<HeaderValue>,
-    validate_certificates: bool,
+    tls_settings: TlsSettings,
     client_cert: Option<ClientCertificateConfig>,
+    proxy: HttpConnectionProxySetting,
+    network: NetworkSettings,
 ) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response)> {
     info!("Connecting to WS {url}");
-    let tls_config = get_tls_config(validate_certificates, WITH_ALPN, client_cert.clone())?;
+    let tls_config = get_tls_config(&tls_settings, ALPN_PROTOCOLS, client_cert.clone())?;
 
     let mut req = url.into_client_request()?;
     let req_headers = req.headers_mut();
@@ -32,17 +37,21 @@ pub async fn ws_connect(
         }
     }
 
-    let (stream, response) = connect_async_tls_with_config(
+    // Connect through the proxy ourselves, since tungstenite only opens direct connections
+    let proxy = ProxySelector::new(&proxy).await?;
+    let stream = tunnel::connect(&proxy, &Url::parse(url)?, &network).await?;
+
+    let (stream, response) = client_async_tls_with_config(
         req,
+        stream,
         Some(WebSocketConfig::default()),
-        false,
         Some(Connector::Rustls(Arc::new(tls_config))),
     )
     .await?;
 
     info!(
         "Connected to WS {url} validate_certificates={} client_cert={}",
-        validate_certificates,
+        tls_settings.validate_certificates,
         client_cert.is_some()
     );
 
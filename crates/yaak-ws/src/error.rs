@@ -16,6 +16,12 @@ pub enum Error {
     #[error(transparent)]
     TlsError(#[from] yaak_tls::error::Error),
 
+    #[error(transparent)]
+    HttpError(#[from] yaak_http::error::Error),
+
+    #[error("Invalid URL: {0}")]
+    UrlError(#[from] url::ParseError),
+
     #[error("WebSocket error: {0}")]
     GenericError(String),
 }
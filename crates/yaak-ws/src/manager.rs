@@ -13,7 +13,9 @@ use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::tungstenite::handshake::client::Response;
 use tokio_tungstenite::tungstenite::http::HeaderValue;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
-use yaak_tls::ClientCertificateConfig;
+use yaak_http::client::HttpConnectionProxySetting;
+use yaak_models::models::NetworkSettings;
+use yaak_tls::{ClientCertificateConfig, TlsSettings};
 
 #[derive(Clone)]
 pub struct WebsocketManager {
@@ -27,19 +29,22 @@ impl WebsocketManager {
         WebsocketManager { connections: Default::default(), read_tasks: Default::default() }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn connect(
         &mut self,
         id: &str,
         url: &str,
         headers: HeaderMap<HeaderValue>,
         receive_tx: mpsc::Sender<Message>,
-        validate_certificates: bool,
+        tls_settings: TlsSettings,
         client_cert: Option<ClientCertificateConfig>,
+        proxy: HttpConnectionProxySetting,
+        network: NetworkSettings,
     ) -> Result<Response> {
         let tx = receive_tx.clone();
 
         let (stream, response) =
-            ws_connect(url, headers, validate_certificates, client_cert).await?;
+            ws_connect(url, headers, tls_settings, client_cert, proxy, network).await?;
         let (write, mut read) = stream.split();
 
         self.connections.lock().await.insert(id.to_string(), write);
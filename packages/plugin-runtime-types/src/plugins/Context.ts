@@ -1,10 +1,14 @@
 import type {
+  ExportCookiesRequest,
+  ExportCookiesResponse,
   FindHttpResponsesRequest,
   FindHttpResponsesResponse,
   GetCookieValueRequest,
   GetCookieValueResponse,
   GetHttpRequestByIdRequest,
   GetHttpRequestByIdResponse,
+  ImportCookiesRequest,
+  ImportCookiesResponse,
   ListCookieNamesResponse,
   ListFoldersRequest,
   ListFoldersResponse,
@@ -61,6 +65,8 @@ export interface Context {
   cookies: {
     listNames(): Promise<ListCookieNamesResponse['names']>;
     getValue(args: GetCookieValueRequest): Promise<GetCookieValueResponse['value']>;
+    import(args: ImportCookiesRequest): Promise<ImportCookiesResponse['count']>;
+    export(args: ExportCookiesRequest): Promise<ExportCookiesResponse['contents']>;
   };
   grpcRequest: {
     render(args: RenderGrpcRequestRequest): Promise<RenderGrpcRequestResponse['grpcRequest']>;
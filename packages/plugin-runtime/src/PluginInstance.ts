@@ -10,6 +10,8 @@ import type {
   BootRequest,
   DeleteKeyValueResponse,
   DeleteModelResponse,
+  ExportCookiesRequest,
+  ExportCookiesResponse,
   FindHttpResponsesResponse,
   Folder,
   GetCookieValueRequest,
@@ -20,6 +22,8 @@ import type {
   HttpAuthenticationAction,
   HttpRequest,
   HttpRequestAction,
+  ImportCookiesRequest,
+  ImportCookiesResponse,
   ImportResources,
   InternalEvent,
   InternalEventPayload,
@@ -836,6 +840,16 @@ export class PluginInstance {
           const { names } = await this.#sendForReply<ListCookieNamesResponse>(context, payload);
           return names;
         },
+        import: async (args: ImportCookiesRequest) => {
+          const payload = { type: 'import_cookies_request', ...args } as const;
+          const { count } = await this.#sendForReply<ImportCookiesResponse>(context, payload);
+          return count;
+        },
+        export: async (args: ExportCookiesRequest) => {
+          const payload = { type: 'export_cookies_request', ...args } as const;
+          const { contents } = await this.#sendForReply<ExportCookiesResponse>(context, payload);
+          return contents;
+        },
       },
       templates: {
         /**
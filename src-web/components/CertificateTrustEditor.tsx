@@ -0,0 +1,186 @@
+import type { CertificatePin, Workspace } from '@yaakapp-internal/models';
+import { patchModel } from '@yaakapp-internal/models';
+import { useCallback, useId, useMemo } from 'react';
+import { Button } from './core/Button';
+import { Checkbox } from './core/Checkbox';
+import { Heading } from './core/Heading';
+import { IconButton } from './core/IconButton';
+import { PlainInput } from './core/PlainInput';
+import { HStack, VStack } from './core/Stacks';
+import { Table, TableBody, TableCell, TableHead, TableHeaderCell, TableRow } from './core/Table';
+
+interface Props {
+  workspace: Workspace;
+}
+
+export function CertificateTrustEditor({ workspace }: Props) {
+  const reactId = useId();
+
+  // Keys are only stable until an entry is deleted, which is fine for uncontrolled inputs
+  const caCertificatesWithIds = useMemo(
+    () => workspace.settingCaCertificates.map((path, i) => ({ path, _id: `${reactId}-ca-${i}` })),
+    [workspace.settingCaCertificates, reactId],
+  );
+  const pinsWithIds = useMemo(
+    () => workspace.settingCertificatePins.map((pin, i) => ({ ...pin, _id: `${reactId}-${i}` })),
+    [workspace.settingCertificatePins, reactId],
+  );
+
+  const handleCaChange = useCallback(
+    (settingCaCertificates: string[]) => patchModel(workspace, { settingCaCertificates }),
+    [workspace],
+  );
+
+  const handlePinsChange = useCallback(
+    (settingCertificatePins: CertificatePin[]) => patchModel(workspace, { settingCertificatePins }),
+    [workspace],
+  );
+
+  return (
+    <VStack space={3} className="pb-3">
+      <Heading level={3}>CA Certificates</Heading>
+      <div className="text-text-subtle text-sm">
+        PEM files, or directories of them, with CA certificates to trust on top of the system
+        roots. Only used when certificate validation is enabled.
+      </div>
+      {caCertificatesWithIds.map(({ path, _id }, index) => (
+        <HStack key={_id} space={1}>
+          <PlainInput
+            size="sm"
+            hideLabel
+            label="CA certificate path"
+            placeholder="/etc/ssl/private-ca.pem"
+            defaultValue={path}
+            onChange={(value) =>
+              handleCaChange(
+                workspace.settingCaCertificates.map((p, i) => (i === index ? value : p)),
+              )
+            }
+          />
+          <IconButton
+            size="xs"
+            iconSize="sm"
+            icon="trash"
+            title="Remove CA certificate"
+            onClick={() =>
+              handleCaChange(workspace.settingCaCertificates.filter((_, i) => i !== index))
+            }
+          />
+        </HStack>
+      ))}
+      <HStack>
+        <Button
+          size="xs"
+          color="secondary"
+          variant="border"
+          onClick={() => handleCaChange([...workspace.settingCaCertificates, ''])}
+        >
+          Add CA Certificate
+        </Button>
+      </HStack>
+
+      <Heading level={3} className="mt-3">
+        Certificate Pins
+      </Heading>
+      <div className="text-text-subtle text-sm">
+        Fail the connection unless the server's own certificate matches a pin. Use{' '}
+        <code className="text-text-subtlest bg-surface-highlight px-1 rounded">
+          sha256/&lt;base64&gt;
+        </code>{' '}
+        for a public key, or a SHA-256 certificate fingerprint. Pins are checked even when
+        certificate validation is disabled.
+      </div>
+
+      {pinsWithIds.length > 0 && (
+        <Table>
+          <TableHead>
+            <TableRow>
+              <TableHeaderCell className="w-8" />
+              <TableHeaderCell>Hostname</TableHeaderCell>
+              <TableHeaderCell>Pin</TableHeaderCell>
+              <TableHeaderCell className="w-10" />
+            </TableRow>
+          </TableHead>
+          <TableBody>
+            {pinsWithIds.map((pin, index) => (
+              <CertificatePinRow
+                key={pin._id}
+                pin={pin}
+                onUpdate={(update) =>
+                  handlePinsChange(
+                    workspace.settingCertificatePins.map((p, i) =>
+                      i === index ? { ...p, ...update } : p,
+                    ),
+                  )
+                }
+                onDelete={() =>
+                  handlePinsChange(workspace.settingCertificatePins.filter((_, i) => i !== index))
+                }
+              />
+            ))}
+          </TableBody>
+        </Table>
+      )}
+
+      <HStack>
+        <Button
+          size="xs"
+          color="secondary"
+          variant="border"
+          onClick={() =>
+            handlePinsChange([
+              ...workspace.settingCertificatePins,
+              { host: '', pin: '', enabled: true },
+            ])
+          }
+        >
+          Add Certificate Pin
+        </Button>
+      </HStack>
+    </VStack>
+  );
+}
+
+interface CertificatePinRowProps {
+  pin: CertificatePin;
+  onUpdate: (update: Partial<CertificatePin>) => void;
+  onDelete: () => void;
+}
+
+function CertificatePinRow({ pin, onUpdate, onDelete }: CertificatePinRowProps) {
+  return (
+    <TableRow>
+      <TableCell>
+        <Checkbox
+          hideLabel
+          title={pin.enabled ? 'Disable pin' : 'Enable pin'}
+          checked={pin.enabled ?? true}
+          onChange={(enabled) => onUpdate({ enabled })}
+        />
+      </TableCell>
+      <TableCell>
+        <PlainInput
+          size="sm"
+          hideLabel
+          label="Hostname"
+          placeholder="*.example.com"
+          defaultValue={pin.host}
+          onChange={(host) => onUpdate({ host: host.trim() })}
+        />
+      </TableCell>
+      <TableCell>
+        <PlainInput
+          size="sm"
+          hideLabel
+          label="Pin"
+          placeholder="sha256/AAAA..."
+          defaultValue={pin.pin}
+          onChange={(value) => onUpdate({ pin: value.trim() })}
+        />
+      </TableCell>
+      <TableCell>
+        <IconButton size="xs" iconSize="sm" icon="trash" title="Delete pin" onClick={onDelete} />
+      </TableCell>
+    </TableRow>
+  );
+}
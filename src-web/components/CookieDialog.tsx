@@ -43,7 +43,7 @@ export const CookieDialog = ({ cookieJarId }: Props) => {
                 {cookieDomain(c)}
               </td>
               <td className="py-2 pl-4 select-text cursor-text font-mono text-text-subtle whitespace-nowrap overflow-x-auto max-w-[200px] hide-scrollbars">
-                {c.raw_cookie}
+                {c.name}={c.value}
               </td>
               <td className="max-w-0 w-10">
                 <IconButton
@@ -67,7 +67,10 @@ export function DnsOverridesEditor({ workspace }: Props) {
       <div className="text-text-subtle text-sm">
         Override DNS resolution for specific hostnames. This works like{' '}
         <code className="text-text-subtlest bg-surface-highlight px-1 rounded">/etc/hosts</code>{' '}
-        but only for requests made from this workspace.
+        but only for requests made from this workspace. Use{' '}
+        <code className="text-text-subtlest bg-surface-highlight px-1 rounded">*.example.com</code>{' '}
+        to match all subdomains, and set a target port to connect somewhere else, like curl's{' '}
+        <code className="text-text-subtlest bg-surface-highlight px-1 rounded">--connect-to</code>.
       </div>
 
       {overridesWithIds.length > 0 && (
@@ -76,8 +79,10 @@ export function DnsOverridesEditor({ workspace }: Props) {
             <TableRow>
               <TableHeaderCell className="w-8" />
               <TableHeaderCell>Hostname</TableHeaderCell>
+              <TableHeaderCell className="w-20">Port</TableHeaderCell>
               <TableHeaderCell>IPv4 Address</TableHeaderCell>
               <TableHeaderCell>IPv6 Address</TableHeaderCell>
+              <TableHeaderCell className="w-24">Target Port</TableHeaderCell>
               <TableHeaderCell className="w-10" />
             </TableRow>
           </TableHead>
@@ -128,11 +133,21 @@ function DnsOverrideRow({ override, onUpdate, onDelete }: DnsOverrideRowProps) {
           size="sm"
           hideLabel
           label="Hostname"
-          placeholder="api.example.com"
+          placeholder="*.example.com"
           defaultValue={override.hostname}
           onChange={(hostname) => onUpdate({ hostname })}
         />
       </TableCell>
+      <TableCell>
+        <PlainInput
+          size="sm"
+          hideLabel
+          label="Port"
+          placeholder="Any"
+          defaultValue={override.port == null ? '' : String(override.port)}
+          onChange={(value) => onUpdate({ port: parsePort(value) })}
+        />
+      </TableCell>
       <TableCell>
         <PlainInput
           size="sm"
@@ -167,6 +182,16 @@ function DnsOverrideRow({ override, onUpdate, onDelete }: DnsOverrideRowProps) {
           }
         />
       </TableCell>
+      <TableCell>
+        <PlainInput
+          size="sm"
+          hideLabel
+          label="Target port"
+          placeholder="Same"
+          defaultValue={override.targetPort == null ? '' : String(override.targetPort)}
+          onChange={(value) => onUpdate({ targetPort: parsePort(value) })}
+        />
+      </TableCell>
       <TableCell>
         <IconButton
           size="xs"
@@ -179,3 +204,8 @@ function DnsOverrideRow({ override, onUpdate, onDelete }: DnsOverrideRowProps) {
     </TableRow>
   );
 }
+
+function parsePort(value: string): number | undefined {
+  const port = Number.parseInt(value.trim(), 10);
+  return port > 0 && port <= 65535 ? port : undefined;
+}
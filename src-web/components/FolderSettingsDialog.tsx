@@ -5,6 +5,7 @@ import { useAuthTab } from '../hooks/useAuthTab';
 import { useEnvironmentsBreakdown } from '../hooks/useEnvironmentsBreakdown';
 import { useHeadersTab } from '../hooks/useHeadersTab';
 import { useInheritedHeaders } from '../hooks/useInheritedHeaders';
+import { ClientCertificatesEditor } from './ClientCertificatesEditor';
 import { Button } from './core/Button';
 import { CountBadge } from './core/CountBadge';
 import { Input } from './core/Input';
@@ -16,7 +17,11 @@ import { EmptyStateText } from './EmptyStateText';
 import { EnvironmentEditor } from './EnvironmentEditor';
 import { HeadersEditor } from './HeadersEditor';
 import { HttpAuthenticationEditor } from './HttpAuthenticationEditor';
+import { HttpVersionSelect } from './HttpVersionSelect';
 import { MarkdownEditor } from './MarkdownEditor';
+import { NetworkSettingsEditor } from './NetworkSettingsEditor';
+import { RedirectPolicyEditor } from './RedirectPolicyEditor';
+import { RetryPolicyEditor } from './RetryPolicyEditor';
 
 interface Props {
   folderId: string | null;
@@ -24,12 +29,14 @@ interface Props {
 }
 
 const TAB_AUTH = 'auth';
+const TAB_CLIENT_CERTIFICATES = 'client_certificates';
 const TAB_HEADERS = 'headers';
 const TAB_VARIABLES = 'variables';
 const TAB_GENERAL = 'general';
 
 export type FolderSettingsTab =
   | typeof TAB_AUTH
+  | typeof TAB_CLIENT_CERTIFICATES
   | typeof TAB_HEADERS
   | typeof TAB_GENERAL
   | typeof TAB_VARIABLES;
@@ -61,6 +68,14 @@ export function FolderSettingsDialog({ folderId, tab }: Props) {
         label: 'Variables',
         rightSlot: numVars > 0 ? <CountBadge count={numVars} /> : null,
       },
+      {
+        value: TAB_CLIENT_CERTIFICATES,
+        label: 'Client Certificates',
+        rightSlot:
+          folder.settingClientCertificates.length > 0 ? (
+            <CountBadge count={folder.settingClientCertificates.length} />
+          ) : null,
+      },
     ];
   }, [authTab, folder, headersTab, numVars]);
 
@@ -86,6 +101,31 @@ export function FolderSettingsDialog({ folderId, tab }: Props) {
             onChange={(name) => patchModel(folder, { name })}
             stateKey={`name.${folder.id}`}
           />
+          <HttpVersionSelect
+            inherit
+            name="httpVersion"
+            size="sm"
+            value={folder.settingHttpVersion}
+            onChange={(settingHttpVersion) => patchModel(folder, { settingHttpVersion })}
+          />
+          <RetryPolicyEditor
+            inherit
+            stateKey={`retryPolicy.${folder.id}`}
+            value={folder.settingRetryPolicy}
+            onChange={(settingRetryPolicy) => patchModel(folder, { settingRetryPolicy })}
+          />
+          <RedirectPolicyEditor
+            inherit
+            stateKey={`redirectPolicy.${folder.id}`}
+            value={folder.settingRedirectPolicy}
+            onChange={(settingRedirectPolicy) => patchModel(folder, { settingRedirectPolicy })}
+          />
+          <NetworkSettingsEditor
+            inherit
+            stateKey={`network.${folder.id}`}
+            value={folder.settingNetwork}
+            onChange={(settingNetwork) => patchModel(folder, { settingNetwork })}
+          />
           <MarkdownEditor
             name="folder-description"
             placeholder="Folder description"
@@ -96,6 +136,15 @@ export function FolderSettingsDialog({ folderId, tab }: Props) {
           />
         </VStack>
       </TabContent>
+      <TabContent value={TAB_CLIENT_CERTIFICATES} className="overflow-y-auto h-full px-4">
+        <ClientCertificatesEditor
+          title="Client Certificates"
+          description="Used for requests in this folder before the ones of parent folders, the workspace and app settings."
+          stateKey={`clientCertificates.${folder.id}`}
+          certificates={folder.settingClientCertificates}
+          onChange={(settingClientCertificates) => patchModel(folder, { settingClientCertificates })}
+        />
+      </TabContent>
       <TabContent value={TAB_HEADERS} className="overflow-y-auto h-full px-4">
         <HeadersEditor
           inheritedHeaders={inheritedHeaders}
@@ -1,4 +1,4 @@
-import type { HttpRequest } from '@yaakapp-internal/models';
+import type { BodyCompression, HttpRequest } from '@yaakapp-internal/models';
 import { patchModel } from '@yaakapp-internal/models';
 import type { GenericCompletionOption } from '@yaakapp-internal/plugins';
 import classNames from 'classnames';
@@ -41,6 +41,7 @@ import { Editor } from './core/Editor/LazyEditor';
 import { InlineCode } from './core/InlineCode';
 import type { Pair } from './core/PairEditor';
 import { PlainInput } from './core/PlainInput';
+import { Select } from './core/Select';
 import type { TabItem, TabsRef } from './core/Tabs/Tabs';
 import { setActiveTab, TabContent, Tabs } from './core/Tabs/Tabs';
 import { EmptyStateText } from './EmptyStateText';
@@ -48,8 +49,12 @@ import { FormMultipartEditor } from './FormMultipartEditor';
 import { FormUrlencodedEditor } from './FormUrlencodedEditor';
 import { HeadersEditor } from './HeadersEditor';
 import { HttpAuthenticationEditor } from './HttpAuthenticationEditor';
+import { HttpVersionSelect } from './HttpVersionSelect';
 import { MarkdownEditor } from './MarkdownEditor';
+import { NetworkSettingsEditor } from './NetworkSettingsEditor';
+import { RedirectPolicyEditor } from './RedirectPolicyEditor';
 import { RequestMethodDropdown } from './RequestMethodDropdown';
+import { RetryPolicyEditor } from './RetryPolicyEditor';
 import { UrlBar } from './UrlBar';
 import { UrlParametersEditor } from './UrlParameterEditor';
 
@@ -57,6 +62,16 @@ const GraphQLEditor = lazy(() =>
   import('./graphql/GraphQLEditor').then((m) => ({ default: m.GraphQLEditor })),
 );
 
+const NO_COMPRESSION = '__none__';
+
+const compressionOptions: { label: string; value: BodyCompression | typeof NO_COMPRESSION }[] = [
+  { label: 'None', value: NO_COMPRESSION },
+  { label: 'gzip', value: 'gzip' },
+  { label: 'deflate', value: 'deflate' },
+  { label: 'Brotli (br)', value: 'br' },
+  { label: 'Zstandard (zstd)', value: 'zstd' },
+];
+
 interface Props {
   style: CSSProperties;
   fullHeight: boolean;
@@ -440,7 +455,7 @@ export function HttpRequestPane({ style, fullHeight, className, activeRequest }:
               </ConfirmLargeRequestBody>
             </TabContent>
             <TabContent value={TAB_DESCRIPTION}>
-              <div className="grid grid-rows-[auto_minmax(0,1fr)] h-full">
+              <div className="grid grid-rows-[auto_auto_minmax(0,1fr)] gap-y-2 h-full">
                 <PlainInput
                   label="Request Name"
                   hideLabel
@@ -451,6 +466,50 @@ export function HttpRequestPane({ style, fullHeight, className, activeRequest }:
                   placeholder={resolvedModelName(activeRequest)}
                   onChange={(name) => patchModel(activeRequest, { name })}
                 />
+                <HttpVersionSelect
+                  inherit
+                  name="httpVersion"
+                  size="sm"
+                  labelPosition="left"
+                  value={activeRequest.settingHttpVersion}
+                  onChange={(settingHttpVersion) =>
+                    patchModel(activeRequest, { settingHttpVersion })
+                  }
+                />
+                <Select
+                  name="bodyCompression"
+                  size="sm"
+                  labelPosition="left"
+                  label="Body Compression"
+                  help="Compresses the request body before sending it and sets the Content-Encoding header."
+                  value={activeRequest.bodyCompression ?? NO_COMPRESSION}
+                  options={compressionOptions}
+                  onChange={(v) =>
+                    patchModel(activeRequest, { bodyCompression: v === NO_COMPRESSION ? null : v })
+                  }
+                />
+                <RetryPolicyEditor
+                  inherit
+                  stateKey={`retryPolicy.${activeRequest.id}`}
+                  value={activeRequest.settingRetryPolicy}
+                  onChange={(settingRetryPolicy) =>
+                    patchModel(activeRequest, { settingRetryPolicy })
+                  }
+                />
+                <RedirectPolicyEditor
+                  inherit
+                  stateKey={`redirectPolicy.${activeRequest.id}`}
+                  value={activeRequest.settingRedirectPolicy}
+                  onChange={(settingRedirectPolicy) =>
+                    patchModel(activeRequest, { settingRedirectPolicy })
+                  }
+                />
+                <NetworkSettingsEditor
+                  inherit
+                  stateKey={`network.${activeRequest.id}`}
+                  value={activeRequest.settingNetwork}
+                  onChange={(settingNetwork) => patchModel(activeRequest, { settingNetwork })}
+                />
                 <MarkdownEditor
                   name="request-description"
                   placeholder="Request description"
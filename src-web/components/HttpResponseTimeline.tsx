@@ -126,6 +126,16 @@ function EventDetails({
         return 'Data Received';
       case 'dns_resolved':
         return e.overridden ? 'DNS Override' : 'DNS Resolution';
+      case 'connected':
+        return 'Connection';
+      case 'tls_handshake':
+        return 'TLS Handshake';
+      case 'first_byte':
+        return 'Time to First Byte';
+      case 'tls_session':
+        return 'TLS Session';
+      case 'retry':
+        return 'Retry';
       default:
         return label;
     }
@@ -229,11 +239,43 @@ function EventDetails({
               `${String(e.duration)}ms`
             )}
           </KeyValueRow>
-          {e.overridden ? <KeyValueRow label="Source">Workspace Override</KeyValueRow> : null}
+          {e.overridden || e.source ? (
+            <KeyValueRow label="Source">{dnsSourceLabel(e.overridden, e.source)}</KeyValueRow>
+          ) : null}
+          {e.ttl != null ? <KeyValueRow label="TTL">{`${String(e.ttl)}s`}</KeyValueRow> : null}
         </KeyValueRows>
       );
     }
 
+    // TLS session - show negotiated parameters and the certificate chain
+    if (e.type === 'tls_session') {
+      return (
+        <div className="flex flex-col gap-4">
+          <KeyValueRows>
+            <KeyValueRow label="Version">{e.version}</KeyValueRow>
+            <KeyValueRow label="Cipher Suite">{e.cipher_suite}</KeyValueRow>
+            <KeyValueRow label="ALPN">{e.alpn}</KeyValueRow>
+            <KeyValueRow label="Server Name">{e.server_name}</KeyValueRow>
+          </KeyValueRows>
+          {e.certificates.map((cert, i) => (
+            <KeyValueRows key={cert.sha256Fingerprint}>
+              <KeyValueRow label={i === 0 ? 'Certificate' : `Issuer ${i}`}>
+                {cert.subject}
+              </KeyValueRow>
+              <KeyValueRow label="Issued By">{cert.issuer}</KeyValueRow>
+              {cert.sans.length > 0 ? (
+                <KeyValueRow label="SANs">{cert.sans.join(', ')}</KeyValueRow>
+              ) : null}
+              <KeyValueRow label="Valid">
+                {cert.notBefore} → {cert.notAfter}
+              </KeyValueRow>
+              <KeyValueRow label="SHA-256">{cert.sha256Fingerprint}</KeyValueRow>
+            </KeyValueRows>
+          ))}
+        </div>
+      );
+    }
+
     // Default - use summary
     const { summary } = getEventDisplay(event.event);
     return <div className="font-mono text-editor">{summary}</div>;
@@ -288,7 +330,23 @@ function getEventTextParts(event: HttpResponseEventData): EventTextParts {
       }
       return {
         prefix: '*',
-        text: `DNS resolved ${event.hostname} to ${event.addresses.join(', ')} (${event.duration}ms)`,
+        text: `DNS resolved ${event.hostname} to ${event.addresses.join(', ')}${event.source ? ` via ${event.source}` : ''} (${event.duration}ms)${event.ttl != null ? ` ttl=${event.ttl}s` : ''}`,
+      };
+    case 'connected':
+      return { prefix: '*', text: `Connected (${event.duration}ms)` };
+    case 'tls_handshake':
+      return { prefix: '*', text: `TLS handshake completed (${event.duration}ms)` };
+    case 'first_byte':
+      return { prefix: '*', text: `Waited ${event.duration}ms for the first byte` };
+    case 'tls_session':
+      return {
+        prefix: '*',
+        text: `TLS ${event.version} / ${event.cipher_suite}, ALPN ${event.alpn}, SNI ${event.server_name}`,
+      };
+    case 'retry':
+      return {
+        prefix: '*',
+        text: `Retrying in ${event.delay}ms, attempt ${event.attempt} of ${event.max_attempts} (${event.reason})`,
       };
     default:
       return { prefix: '*', text: '[unknown event]' };
@@ -301,6 +359,14 @@ function formatEventText(event: HttpResponseEventData, includePrefix: boolean):
   return includePrefix ? `${prefix} ${text}` : text;
 }
 
+/** Describe where resolved addresses came from, which is a DNS server unless it's a known source */
+function dnsSourceLabel(overridden: boolean, source: string): string {
+  if (overridden || source === 'override') return 'Workspace Override';
+  if (source === 'localhost') return 'Localhost';
+  if (source === 'system') return 'System Resolver';
+  return source;
+}
+
 type EventDisplay = {
   icon: IconProps['icon'];
   color: IconProps['color'];
@@ -383,6 +449,41 @@ function getEventDisplay(event: HttpResponseEventData): EventDisplay {
           ? `${event.hostname} → ${event.addresses.join(', ')} (overridden)`
           : `${event.hostname} → ${event.addresses.join(', ')} (${event.duration}ms)`,
       };
+    case 'connected':
+      return {
+        icon: 'plug',
+        color: 'secondary',
+        label: 'Connect',
+        summary: `Connected in ${event.duration}ms`,
+      };
+    case 'tls_handshake':
+      return {
+        icon: 'lock',
+        color: 'secondary',
+        label: 'TLS',
+        summary: `TLS handshake in ${event.duration}ms`,
+      };
+    case 'first_byte':
+      return {
+        icon: 'clock',
+        color: 'secondary',
+        label: 'Waiting',
+        summary: `First byte after ${event.duration}ms`,
+      };
+    case 'tls_session':
+      return {
+        icon: 'shield_check',
+        color: 'secondary',
+        label: 'TLS',
+        summary: `${event.version} ${event.cipher_suite} (${event.alpn})`,
+      };
+    case 'retry':
+      return {
+        icon: 'refresh',
+        color: 'warning',
+        label: 'Retry',
+        summary: `Attempt ${event.attempt} of ${event.max_attempts} in ${event.delay}ms (${event.reason})`,
+      };
     default:
       return {
         icon: 'info',
@@ -0,0 +1,42 @@
+import type { HttpVersion } from '@yaakapp-internal/models';
+import type { SelectProps } from './core/Select';
+import { Select } from './core/Select';
+
+const INHERIT_VALUE = '__inherit__';
+
+const versionOptions: { label: string; value: HttpVersion }[] = [
+  { label: 'Auto (HTTP/2 or HTTP/1.1)', value: 'auto' },
+  { label: 'HTTP/1.1', value: 'http1' },
+  { label: 'HTTP/2 (over TLS)', value: 'http2' },
+  { label: 'HTTP/2 prior knowledge (h2c)', value: 'http2_prior_knowledge' },
+];
+
+type Props = Pick<SelectProps<string>, 'name' | 'size' | 'labelPosition' | 'labelClassName'> &
+  (
+    | { inherit: true; value: HttpVersion | null; onChange: (v: HttpVersion | null) => void }
+    | { inherit?: false; value: HttpVersion; onChange: (v: HttpVersion) => void }
+  );
+
+export function HttpVersionSelect(props: Props) {
+  const { name, size, labelPosition, labelClassName } = props;
+  return (
+    <Select
+      name={name}
+      size={size}
+      labelPosition={labelPosition}
+      labelClassName={labelClassName}
+      label="HTTP Version"
+      help="HTTP/2 over TLS is negotiated with ALPN. Use prior knowledge for cleartext HTTP/2 (h2c) servers."
+      value={props.value ?? INHERIT_VALUE}
+      options={
+        props.inherit
+          ? [{ label: 'Inherit from parent', value: INHERIT_VALUE }, ...versionOptions]
+          : versionOptions
+      }
+      onChange={(v) => {
+        if (props.inherit) props.onChange(v === INHERIT_VALUE ? null : (v as HttpVersion));
+        else if (v !== INHERIT_VALUE) props.onChange(v as HttpVersion);
+      }}
+    />
+  );
+}
@@ -0,0 +1,96 @@
+import type { IpFamily, NetworkSettings } from '@yaakapp-internal/models';
+import { Checkbox } from './core/Checkbox';
+import { PlainInput } from './core/PlainInput';
+import { Select } from './core/Select';
+import { VStack } from './core/Stacks';
+
+const DEFAULT_SETTINGS: NetworkSettings = {
+  ipFamily: 'auto',
+  localAddress: '',
+  interface: '',
+};
+
+const ipFamilyOptions: { label: string; value: IpFamily }[] = [
+  { label: 'Automatic', value: 'auto' },
+  { label: 'IPv4 only', value: 'ipv4' },
+  { label: 'IPv6 only', value: 'ipv6' },
+];
+
+type Props = { stateKey: string } & (
+  | { inherit: true; value: NetworkSettings | null; onChange: (v: NetworkSettings | null) => void }
+  | { inherit?: false; value: NetworkSettings; onChange: (v: NetworkSettings) => void }
+);
+
+export function NetworkSettingsEditor(props: Props) {
+  const { value: network, stateKey } = props;
+
+  return (
+    <VStack space={3}>
+      {props.inherit && (
+        <Checkbox
+          checked={network != null}
+          title="Override network settings"
+          help="When disabled, the network settings of the parent folder or workspace are used."
+          onChange={(enabled) => {
+            if (props.inherit) props.onChange(enabled ? DEFAULT_SETTINGS : null);
+          }}
+        />
+      )}
+      {network != null && (
+        <NetworkSettingsFields network={network} stateKey={stateKey} onChange={props.onChange} />
+      )}
+    </VStack>
+  );
+}
+
+function NetworkSettingsFields({
+  network,
+  stateKey,
+  onChange,
+}: {
+  network: NetworkSettings;
+  stateKey: string;
+  onChange: (v: NetworkSettings) => void;
+}) {
+  const update = (patch: Partial<NetworkSettings>) => onChange({ ...network, ...patch });
+
+  return (
+    <VStack space={3}>
+      <Select
+        name="ipFamily"
+        size="sm"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        label="IP Version"
+        help="Automatic races IPv6 and IPv4 addresses (happy eyeballs) and uses whichever connects first."
+        value={network.ipFamily}
+        options={ipFamilyOptions}
+        onChange={(ipFamily) => update({ ipFamily })}
+      />
+      <PlainInput
+        size="sm"
+        name="localAddress"
+        label="Local Address"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        placeholder="Any"
+        help="The IP address connections are made from"
+        defaultValue={network.localAddress}
+        forceUpdateKey={stateKey}
+        onChange={(localAddress) => update({ localAddress })}
+      />
+      <PlainInput
+        size="sm"
+        name="interface"
+        label="Network Interface"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        placeholder="Any"
+        help="The name of the interface to send from, like en0 or eth0. Not supported on Windows."
+        defaultValue={network.interface}
+        forceUpdateKey={stateKey}
+        onChange={(iface) => update({ interface: iface })}
+      />
+    </VStack>
+  );
+}
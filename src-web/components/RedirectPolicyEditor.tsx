@@ -0,0 +1,79 @@
+import type { RedirectPolicy } from '@yaakapp-internal/models';
+import { Checkbox } from './core/Checkbox';
+import { PlainInput } from './core/PlainInput';
+import { VStack } from './core/Stacks';
+
+const DEFAULT_POLICY: RedirectPolicy = {
+  maxRedirects: 10,
+  keepCrossOriginCredentials: false,
+  convertPostToGet: true,
+};
+
+type Props = { stateKey: string } & (
+  | { inherit: true; value: RedirectPolicy | null; onChange: (v: RedirectPolicy | null) => void }
+  | { inherit?: false; value: RedirectPolicy; onChange: (v: RedirectPolicy) => void }
+);
+
+export function RedirectPolicyEditor(props: Props) {
+  const { value: policy, stateKey } = props;
+
+  return (
+    <VStack space={3}>
+      {props.inherit && (
+        <Checkbox
+          checked={policy != null}
+          title="Override redirect policy"
+          help="When disabled, the redirect policy of the parent folder or workspace is used."
+          onChange={(enabled) => {
+            if (props.inherit) props.onChange(enabled ? DEFAULT_POLICY : null);
+          }}
+        />
+      )}
+      {policy != null && (
+        <RedirectPolicyFields policy={policy} stateKey={stateKey} onChange={props.onChange} />
+      )}
+    </VStack>
+  );
+}
+
+function RedirectPolicyFields({
+  policy,
+  stateKey,
+  onChange,
+}: {
+  policy: RedirectPolicy;
+  stateKey: string;
+  onChange: (v: RedirectPolicy) => void;
+}) {
+  const update = (patch: Partial<RedirectPolicy>) => onChange({ ...policy, ...patch });
+
+  return (
+    <VStack space={3}>
+      <PlainInput
+        required
+        size="sm"
+        type="number"
+        name="maxRedirects"
+        label="Max Redirects"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        defaultValue={`${policy.maxRedirects}`}
+        forceUpdateKey={stateKey}
+        validate={(v) => Number.parseInt(v, 10) >= 0}
+        onChange={(v) => update({ maxRedirects: Number.parseInt(v, 10) || 0 })}
+      />
+      <Checkbox
+        checked={policy.keepCrossOriginCredentials}
+        title="Keep credentials across origins"
+        help="Send the Authorization and Cookie headers when redirected to another host. Browsers and most clients drop them."
+        onChange={(keepCrossOriginCredentials) => update({ keepCrossOriginCredentials })}
+      />
+      <Checkbox
+        checked={policy.convertPostToGet}
+        title="Change POST to GET on 301 and 302"
+        help="What browsers do. When disabled, the method and body are kept, like for 307 and 308."
+        onChange={(convertPostToGet) => update({ convertPostToGet })}
+      />
+    </VStack>
+  );
+}
@@ -0,0 +1,152 @@
+import type { RetryErrorKind, RetryPolicy } from '@yaakapp-internal/models';
+import { Checkbox } from './core/Checkbox';
+import { PlainInput } from './core/PlainInput';
+import { HStack, VStack } from './core/Stacks';
+
+const DEFAULT_POLICY: RetryPolicy = {
+  maxAttempts: 3,
+  statuses: [408, 429, 502, 503, 504],
+  errors: ['connect', 'timeout'],
+  initialDelay: 500,
+  maxDelay: 30000,
+  jitter: true,
+  respectRetryAfter: true,
+};
+
+const errorOptions: { label: string; value: RetryErrorKind }[] = [
+  { label: 'Connection failures', value: 'connect' },
+  { label: 'Timeouts', value: 'timeout' },
+  { label: 'Dropped connections', value: 'network' },
+];
+
+type Props = { stateKey: string } & (
+  | { inherit: true; value: RetryPolicy | null; onChange: (v: RetryPolicy | null) => void }
+  | { inherit?: false; value: RetryPolicy; onChange: (v: RetryPolicy) => void }
+);
+
+export function RetryPolicyEditor(props: Props) {
+  const { value: policy, stateKey } = props;
+
+  return (
+    <VStack space={3}>
+      {props.inherit && (
+        <Checkbox
+          checked={policy != null}
+          title="Override retry policy"
+          help="When disabled, the retry policy of the parent folder or workspace is used."
+          onChange={(enabled) => {
+            if (props.inherit) props.onChange(enabled ? DEFAULT_POLICY : null);
+          }}
+        />
+      )}
+      {policy != null && (
+        <RetryPolicyFields policy={policy} stateKey={stateKey} onChange={props.onChange} />
+      )}
+    </VStack>
+  );
+}
+
+function RetryPolicyFields({
+  policy,
+  stateKey,
+  onChange,
+}: {
+  policy: RetryPolicy;
+  stateKey: string;
+  onChange: (v: RetryPolicy) => void;
+}) {
+  const update = (patch: Partial<RetryPolicy>) => onChange({ ...policy, ...patch });
+
+  return (
+    <VStack space={3}>
+      <PlainInput
+        required
+        size="sm"
+        type="number"
+        name="retryMaxAttempts"
+        label="Max Attempts"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        help="Including the first one. Set to 1 to never retry."
+        defaultValue={`${policy.maxAttempts}`}
+        forceUpdateKey={stateKey}
+        validate={(v) => Number.parseInt(v, 10) >= 1}
+        onChange={(v) => update({ maxAttempts: Math.max(1, Number.parseInt(v, 10) || 1) })}
+      />
+      <PlainInput
+        size="sm"
+        name="retryStatuses"
+        label="Retry Statuses"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        placeholder="None"
+        help="Comma-separated status codes, e.g. 429, 503"
+        defaultValue={policy.statuses.join(', ')}
+        forceUpdateKey={stateKey}
+        onChange={(v) => update({ statuses: splitStatuses(v) })}
+      />
+      <HStack space={4} className="flex-wrap">
+        {errorOptions.map(({ label, value }) => (
+          <Checkbox
+            key={value}
+            checked={policy.errors.includes(value)}
+            title={`Retry ${label.toLowerCase()}`}
+            onChange={(checked) =>
+              update({
+                errors: checked
+                  ? [...policy.errors, value]
+                  : policy.errors.filter((e) => e !== value),
+              })
+            }
+          />
+        ))}
+      </HStack>
+      <PlainInput
+        required
+        size="sm"
+        type="number"
+        name="retryInitialDelay"
+        label="Initial Delay (ms)"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        help="Doubled after every attempt, up to the maximum delay"
+        defaultValue={`${policy.initialDelay}`}
+        forceUpdateKey={stateKey}
+        validate={(v) => Number.parseInt(v, 10) >= 0}
+        onChange={(v) => update({ initialDelay: Number.parseInt(v, 10) || 0 })}
+      />
+      <PlainInput
+        required
+        size="sm"
+        type="number"
+        name="retryMaxDelay"
+        label="Maximum Delay (ms)"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        defaultValue={`${policy.maxDelay}`}
+        forceUpdateKey={stateKey}
+        validate={(v) => Number.parseInt(v, 10) >= 0}
+        onChange={(v) => update({ maxDelay: Number.parseInt(v, 10) || 0 })}
+      />
+      <Checkbox
+        checked={policy.jitter}
+        title="Add jitter"
+        help="Wait a random delay between half and all of the backoff, so clients don't retry in lockstep."
+        onChange={(jitter) => update({ jitter })}
+      />
+      <Checkbox
+        checked={policy.respectRetryAfter}
+        title="Respect Retry-After"
+        help="Wait as long as the server asks. Requests aren't retried when it asks for longer than the maximum delay."
+        onChange={(respectRetryAfter) => update({ respectRetryAfter })}
+      />
+    </VStack>
+  );
+}
+
+function splitStatuses(value: string): number[] {
+  return value
+    .split(',')
+    .map((s) => Number.parseInt(s.trim(), 10))
+    .filter((s) => !Number.isNaN(s));
+}
@@ -9,9 +9,12 @@ import { Heading } from '../core/Heading';
 import { IconButton } from '../core/IconButton';
 import { KeyValueRow, KeyValueRows } from '../core/KeyValueRow';
 import { PlainInput } from '../core/PlainInput';
-import { Select } from '../core/Select';
 import { Separator } from '../core/Separator';
 import { VStack } from '../core/Stacks';
+import { HttpVersionSelect } from '../HttpVersionSelect';
+import { NetworkSettingsEditor } from '../NetworkSettingsEditor';
+import { RedirectPolicyEditor } from '../RedirectPolicyEditor';
+import { RetryPolicyEditor } from '../RetryPolicyEditor';
 
 export function SettingsGeneral() {
   const workspace = useAtomValue(activeWorkspaceAtom);
@@ -38,6 +41,7 @@ export function SettingsGeneral() {
           labelClassName="w-[14rem]"
           placeholder="0"
           labelPosition="left"
+          help="Deadline for the whole request, from sending it to receiving the end of the response. 0 waits forever."
           defaultValue={`${workspace.settingRequestTimeout}`}
           validate={(value) => Number.parseInt(value, 10) >= 0}
           onChange={(v) =>
@@ -46,6 +50,49 @@ export function SettingsGeneral() {
           type="number"
         />
 
+        <PlainInput
+          required
+          size="sm"
+          name="connectTimeout"
+          label="Connect Timeout (ms)"
+          labelClassName="w-[14rem]"
+          placeholder="0"
+          labelPosition="left"
+          help="How long to wait for a new connection, including the TLS handshake. 0 waits forever."
+          defaultValue={`${workspace.settingConnectTimeout}`}
+          validate={(value) => Number.parseInt(value, 10) >= 0}
+          onChange={(v) =>
+            patchModel(workspace, { settingConnectTimeout: Number.parseInt(v, 10) || 0 })
+          }
+          type="number"
+        />
+
+        <PlainInput
+          required
+          size="sm"
+          name="readTimeout"
+          label="Read Timeout (ms)"
+          labelClassName="w-[14rem]"
+          placeholder="0"
+          labelPosition="left"
+          help="How long to wait for the next chunk of the response body. 0 waits forever."
+          defaultValue={`${workspace.settingReadTimeout}`}
+          validate={(value) => Number.parseInt(value, 10) >= 0}
+          onChange={(v) =>
+            patchModel(workspace, { settingReadTimeout: Number.parseInt(v, 10) || 0 })
+          }
+          type="number"
+        />
+
+        <HttpVersionSelect
+          name="httpVersion"
+          size="sm"
+          labelPosition="left"
+          labelClassName="w-[14rem]"
+          value={workspace.settingHttpVersion}
+          onChange={(settingHttpVersion) => patchModel(workspace, { settingHttpVersion })}
+        />
+
         <Checkbox
           checked={workspace.settingValidateCertificates}
           help="When disabled, skip validation of server certificates, useful when interacting with self-signed certs."
@@ -64,6 +111,24 @@ export function SettingsGeneral() {
             })
           }
         />
+
+        <RetryPolicyEditor
+          stateKey={`retryPolicy.${workspace.id}`}
+          value={workspace.settingRetryPolicy}
+          onChange={(settingRetryPolicy) => patchModel(workspace, { settingRetryPolicy })}
+        />
+
+        <RedirectPolicyEditor
+          stateKey={`redirectPolicy.${workspace.id}`}
+          value={workspace.settingRedirectPolicy}
+          onChange={(settingRedirectPolicy) => patchModel(workspace, { settingRedirectPolicy })}
+        />
+
+        <NetworkSettingsEditor
+          stateKey={`network.${workspace.id}`}
+          value={workspace.settingNetwork}
+          onChange={(settingNetwork) => patchModel(workspace, { settingNetwork })}
+        />
       </VStack>
 
       <Separator className="my-4" />
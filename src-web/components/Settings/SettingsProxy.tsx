@@ -1,3 +1,4 @@
+import type { ProxySetting } from '@yaakapp-internal/models';
 import { patchModel, settingsAtom } from '@yaakapp-internal/models';
 import { useAtomValue } from 'jotai';
 
@@ -17,8 +18,8 @@ export function SettingsProxy() {
       <div className="mb-3">
         <Heading>Proxy</Heading>
         <p className="text-text-subtle">
-          Configure a proxy server for HTTP requests. Useful for corporate firewalls, debugging
-          traffic, or routing through specific infrastructure.
+          Configure a proxy server for HTTP, WebSocket and gRPC requests. Useful for corporate
+          firewalls, debugging traffic, or routing through specific infrastructure.
         </p>
       </div>
       <Select
@@ -41,6 +42,19 @@ export function SettingsProxy() {
                 bypass: '',
               },
             });
+          } else if (v === 'socks') {
+            await patchModel(settings, {
+              proxy: {
+                type: 'socks',
+                host: '',
+                remoteDns: true,
+                auth: null,
+                bypass: '',
+                disabled: false,
+              },
+            });
+          } else if (v === 'pac') {
+            await patchModel(settings, { proxy: { type: 'pac', url: '', disabled: false } });
           } else {
             await patchModel(settings, { proxy: { type: 'disabled' } });
           }
@@ -48,9 +62,23 @@ export function SettingsProxy() {
         options={[
           { label: 'Automatic proxy detection', value: 'automatic' },
           { label: 'Custom proxy configuration', value: 'enabled' },
+          { label: 'SOCKS5 proxy', value: 'socks' },
+          { label: 'Proxy auto-config (PAC) script', value: 'pac' },
           { label: 'No proxy', value: 'disabled' },
         ]}
       />
+      {settings.proxy?.type === 'socks' && (
+        <SocksProxyFields
+          proxy={settings.proxy}
+          onChange={(proxy) => patchModel(settings, { proxy })}
+        />
+      )}
+      {settings.proxy?.type === 'pac' && (
+        <PacProxyFields
+          proxy={settings.proxy}
+          onChange={(proxy) => patchModel(settings, { proxy })}
+        />
+      )}
       {settings.proxy?.type === 'enabled' && (
         <VStack space={1.5}>
           <Checkbox
@@ -206,3 +234,103 @@ export function SettingsProxy() {
     </VStack>
   );
 }
+
+type SocksProxySetting = Extract<ProxySetting, { type: 'socks' }>;
+type PacProxySetting = Extract<ProxySetting, { type: 'pac' }>;
+
+function SocksProxyFields({
+  proxy,
+  onChange,
+}: {
+  proxy: SocksProxySetting;
+  onChange: (proxy: SocksProxySetting) => void;
+}) {
+  const update = (patch: Partial<SocksProxySetting>) => onChange({ ...proxy, ...patch });
+
+  return (
+    <VStack space={1.5}>
+      <Checkbox
+        className="my-3"
+        checked={!proxy.disabled}
+        title="Enable proxy"
+        help="Use this to temporarily disable the proxy without losing the configuration"
+        onChange={(enabled) => update({ disabled: !enabled })}
+      />
+      <PlainInput
+        size="sm"
+        label="SOCKS5 proxy"
+        placeholder="localhost:1080"
+        defaultValue={proxy.host}
+        onChange={(host) => update({ host })}
+      />
+      <Checkbox
+        checked={proxy.remoteDns}
+        title="Resolve hostnames on the proxy"
+        help="Uses socks5h, so hosts only the proxy can resolve are reachable and DNS lookups stay behind it"
+        onChange={(remoteDns) => update({ remoteDns })}
+      />
+      <Separator className="my-6" />
+      <Checkbox
+        checked={proxy.auth != null}
+        title="Enable authentication"
+        onChange={(enabled) => update({ auth: enabled ? { user: '', password: '' } : null })}
+      />
+      {proxy.auth != null && (
+        <HStack space={1.5}>
+          <PlainInput
+            required
+            size="sm"
+            label="User"
+            placeholder="myUser"
+            defaultValue={proxy.auth.user}
+            onChange={(user) => update({ auth: { user, password: proxy.auth?.password ?? '' } })}
+          />
+          <PlainInput
+            size="sm"
+            label="Password"
+            type="password"
+            placeholder="s3cretPassw0rd"
+            defaultValue={proxy.auth.password}
+            onChange={(password) => update({ auth: { user: proxy.auth?.user ?? '', password } })}
+          />
+        </HStack>
+      )}
+      <Separator className="my-6" />
+      <PlainInput
+        label="Proxy Bypass"
+        help="Comma-separated list to bypass the proxy."
+        defaultValue={proxy.bypass}
+        placeholder="127.0.0.1, *.example.com, localhost:3000"
+        onChange={(bypass) => update({ bypass })}
+      />
+    </VStack>
+  );
+}
+
+function PacProxyFields({
+  proxy,
+  onChange,
+}: {
+  proxy: PacProxySetting;
+  onChange: (proxy: PacProxySetting) => void;
+}) {
+  return (
+    <VStack space={1.5}>
+      <Checkbox
+        className="my-3"
+        checked={!proxy.disabled}
+        title="Enable proxy"
+        help="Use this to temporarily disable the proxy without losing the configuration"
+        onChange={(enabled) => onChange({ ...proxy, disabled: !enabled })}
+      />
+      <PlainInput
+        size="sm"
+        label="Script URL or file path"
+        placeholder="http://wpad.example.com/proxy.pac"
+        help="The script's FindProxyForURL function picks the proxy for every request."
+        defaultValue={proxy.url}
+        onChange={(url) => onChange({ ...proxy, url })}
+      />
+    </VStack>
+  );
+}
@@ -0,0 +1,90 @@
+import type { TlsVersion, Workspace } from '@yaakapp-internal/models';
+import { patchModel } from '@yaakapp-internal/models';
+import { Checkbox } from './core/Checkbox';
+import { Heading } from './core/Heading';
+import { PlainInput } from './core/PlainInput';
+import { Select } from './core/Select';
+import { VStack } from './core/Stacks';
+
+const DEFAULT_VALUE = '__default__';
+
+const versionOptions: { label: string; value: TlsVersion | typeof DEFAULT_VALUE }[] = [
+  { label: 'Default', value: DEFAULT_VALUE },
+  { label: 'TLS 1.2', value: 'tls12' },
+  { label: 'TLS 1.3', value: 'tls13' },
+];
+
+interface Props {
+  workspace: Workspace;
+}
+
+export function TlsHandshakeEditor({ workspace }: Props) {
+  return (
+    <VStack space={3} className="pb-3">
+      <Heading level={3}>TLS Handshake</Heading>
+      <div className="text-text-subtle text-sm">
+        Restrict what&apos;s offered to servers, for example to check that they reject TLS 1.2 or
+        a cipher suite. Applies to HTTP, gRPC and WebSocket connections.
+      </div>
+      <Select
+        name="tlsMinVersion"
+        size="sm"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        label="Minimum Version"
+        value={workspace.settingTlsMinVersion ?? DEFAULT_VALUE}
+        options={versionOptions}
+        onChange={(v) =>
+          patchModel(workspace, { settingTlsMinVersion: v === DEFAULT_VALUE ? null : v })
+        }
+      />
+      <Select
+        name="tlsMaxVersion"
+        size="sm"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        label="Maximum Version"
+        value={workspace.settingTlsMaxVersion ?? DEFAULT_VALUE}
+        options={versionOptions}
+        onChange={(v) =>
+          patchModel(workspace, { settingTlsMaxVersion: v === DEFAULT_VALUE ? null : v })
+        }
+      />
+      <PlainInput
+        size="sm"
+        name="tlsCipherSuites"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        label="Cipher Suites"
+        placeholder="All supported"
+        help="Comma-separated rustls or IANA names, e.g. TLS_AES_256_GCM_SHA384"
+        defaultValue={workspace.settingTlsCipherSuites.join(', ')}
+        onChange={(v) => patchModel(workspace, { settingTlsCipherSuites: splitNames(v) })}
+      />
+      <PlainInput
+        size="sm"
+        name="tlsKxGroups"
+        labelPosition="left"
+        labelClassName="w-[14rem]"
+        label="Key Exchange Groups"
+        placeholder="All supported"
+        help="Comma-separated names, e.g. X25519, secp256r1"
+        defaultValue={workspace.settingTlsKxGroups.join(', ')}
+        onChange={(v) => patchModel(workspace, { settingTlsKxGroups: splitNames(v) })}
+      />
+      <Checkbox
+        checked={workspace.settingTlsSendSni}
+        title="Send server name (SNI)"
+        help="When disabled, the hostname isn't sent in the handshake. Use DNS overrides to connect to a different address under the same name."
+        onChange={(settingTlsSendSni) => patchModel(workspace, { settingTlsSendSni })}
+      />
+    </VStack>
+  );
+}
+
+function splitNames(value: string): string[] {
+  return value
+    .split(',')
+    .map((n) => n.trim())
+    .filter((n) => n !== '');
+}
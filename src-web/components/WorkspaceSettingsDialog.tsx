@@ -5,6 +5,8 @@ import { useHeadersTab } from '../hooks/useHeadersTab';
 import { useInheritedHeaders } from '../hooks/useInheritedHeaders';
 import { deleteModelWithConfirm } from '../lib/deleteModelWithConfirm';
 import { router } from '../lib/router';
+import { CertificateTrustEditor } from './CertificateTrustEditor';
+import { ClientCertificatesEditor } from './ClientCertificatesEditor';
 import { CopyIconButton } from './CopyIconButton';
 import { Banner } from './core/Banner';
 import { Button } from './core/Button';
@@ -18,6 +20,7 @@ import { HeadersEditor } from './HeadersEditor';
 import { HttpAuthenticationEditor } from './HttpAuthenticationEditor';
 import { MarkdownEditor } from './MarkdownEditor';
 import { SyncToFilesystemSetting } from './SyncToFilesystemSetting';
+import { TlsHandshakeEditor } from './TlsHandshakeEditor';
 import { WorkspaceEncryptionSetting } from './WorkspaceEncryptionSetting';
 
 interface Props {
@@ -27,6 +30,8 @@ interface Props {
 }
 
 const TAB_AUTH = 'auth';
+const TAB_CERTIFICATES = 'certificates';
+const TAB_CLIENT_CERTIFICATES = 'client_certificates';
 const TAB_DATA = 'data';
 const TAB_DNS = 'dns';
 const TAB_HEADERS = 'headers';
@@ -34,6 +39,8 @@ const TAB_GENERAL = 'general';
 
 export type WorkspaceSettingsTab =
   | typeof TAB_AUTH
+  | typeof TAB_CERTIFICATES
+  | typeof TAB_CLIENT_CERTIFICATES
   | typeof TAB_DNS
   | typeof TAB_HEADERS
   | typeof TAB_GENERAL
@@ -86,6 +93,22 @@ export function WorkspaceSettingsDialog({ workspaceId, hide, tab }: Props) {
               <CountBadge count={workspace.settingDnsOverrides.length} />
             ) : null,
         },
+        {
+          value: TAB_CERTIFICATES,
+          label: 'TLS',
+          rightSlot:
+            workspace.settingCertificatePins.length > 0 ? (
+              <CountBadge count={workspace.settingCertificatePins.length} />
+            ) : null,
+        },
+        {
+          value: TAB_CLIENT_CERTIFICATES,
+          label: 'Client Certificates',
+          rightSlot:
+            workspace.settingClientCertificates.length > 0 ? (
+              <CountBadge count={workspace.settingClientCertificates.length} />
+            ) : null,
+        },
       ]}
       storageKey="workspace_settings_tabs"
     >
@@ -166,6 +189,31 @@ export function WorkspaceSettingsDialog({ workspaceId, hide, tab }: Props) {
       </TabContent>
       <TabContent value={TAB_DNS} className="overflow-y-auto h-full px-4">
         <DnsOverridesEditor workspace={workspace} />
+        <VStack space={1.5} className="pb-3">
+          <PlainInput
+            size="sm"
+            label="DNS Server"
+            placeholder="System resolver"
+            help="An address like 1.1.1.1, a tcp:// address, or an https:// URL for DNS-over-HTTPS"
+            defaultValue={workspace.settingDnsServer}
+            onChange={(settingDnsServer) => patchModel(workspace, { settingDnsServer })}
+          />
+        </VStack>
+      </TabContent>
+      <TabContent value={TAB_CERTIFICATES} className="overflow-y-auto h-full px-4">
+        <CertificateTrustEditor workspace={workspace} />
+        <TlsHandshakeEditor workspace={workspace} />
+      </TabContent>
+      <TabContent value={TAB_CLIENT_CERTIFICATES} className="overflow-y-auto h-full px-4">
+        <ClientCertificatesEditor
+          title="Client Certificates"
+          description="Used for requests in this workspace before the ones in app settings. Paths and passphrases can use variables and functions."
+          stateKey={`clientCertificates.${workspace.id}`}
+          certificates={workspace.settingClientCertificates}
+          onChange={(settingClientCertificates) =>
+            patchModel(workspace, { settingClientCertificates })
+          }
+        />
       </TabContent>
     </Tabs>
   );
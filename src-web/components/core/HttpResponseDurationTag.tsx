@@ -19,8 +19,14 @@ export function HttpResponseDurationTag({ response }: Props) {
     return () => clearInterval(timeout.current);
   }, [response.createdAt, response.state]);
 
-  const dnsValue = response.elapsedDns > 0 ? formatMillis(response.elapsedDns) : '--';
-  const title = `DNS: ${dnsValue}\nHEADER: ${formatMillis(response.elapsedHeaders)}\nTOTAL: ${formatMillis(response.elapsed)}`;
+  const title = [
+    `DNS: ${formatPhase(response.elapsedDns)}`,
+    `CONNECT: ${formatPhase(response.elapsedConnect)}`,
+    `TLS: ${formatPhase(response.elapsedTls)}`,
+    `TTFB: ${formatPhase(response.elapsedTtfb)}`,
+    `HEADER: ${formatMillis(response.elapsedHeaders)}`,
+    `TOTAL: ${formatMillis(response.elapsed)}`,
+  ].join('\n');
 
   const elapsed = response.state === 'closed' ? response.elapsed : fallbackElapsed;
 
@@ -31,6 +37,10 @@ export function HttpResponseDurationTag({ response }: Props) {
   );
 }
 
+function formatPhase(ms: number) {
+  return ms > 0 ? formatMillis(ms) : '--';
+}
+
 function formatMillis(ms: number) {
   if (ms < 1000) {
     return `${ms} ms`;